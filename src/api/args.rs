@@ -1,14 +1,374 @@
+use crate::domain::config::{
+    ApiConfig, Config, ConsensusConfig, DEFAULT_BIND_ADDRESS, DEFAULT_CHAIN_ID,
+    DEFAULT_GENESIS_AMOUNT, DEFAULT_MAX_INBOUND_PEERS, DEFAULT_MAX_OUTBOUND_PEERS,
+    DEFAULT_P2P_PORT_OFFSET, DEFAULT_SLOT_DURATION, DEFAULT_VALIDATORS, GenesisConfig, KeysConfig,
+    LogConfig, LogFormat, NodeConfig, StorageConfig,
+};
+use crate::domain::error::ConfigError;
+use crate::domain::peer_address::PeerAddress;
+use crate::infrastructure::config_file;
+use crate::infrastructure::in_memory_user_state_repository::DEFAULT_SNAPSHOT_INTERVAL;
 use clap::Parser;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::Level;
+use uuid::Uuid;
 
+// Кожен параметр можна задати у файлі конфігурації, змінною середовища або прапорцем;
+// прапорець має найвищий пріоритет, файл — найнижчий
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub struct Args {
+    /// TOML config file; flags and environment variables override its values.
+    #[arg(long, env = "NODE_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    #[arg(long, env = "NODE_ID")]
+    pub id: Option<String>,
+
+    #[arg(long, env = "NODE_PORT")]
+    pub port: Option<u16>,
+
+    /// Port of the P2P transport for blocks, votes, transactions and sync [default: port + 1000].
+    #[arg(long, env = "NODE_P2P_PORT")]
+    pub p2p_port: Option<u16>,
+
+    /// Address the HTTP server binds to [default: 0.0.0.0].
+    #[arg(long, env = "NODE_BIND_ADDRESS")]
+    pub bind_address: Option<IpAddr>,
+
+    /// Name of the network this node belongs to, reported by GET /status [default: devnet].
+    #[arg(long, env = "NODE_CHAIN_ID")]
+    pub chain_id: Option<String>,
+
+    /// Peers as `[http|https://][node_id@]host:port`; a bare port means localhost.
+    #[arg(long, env = "NODE_PEERS", value_delimiter = ',')]
+    pub peers: Option<Vec<PeerAddress>>,
+
+    /// Seed nodes asked for peer addresses on startup.
+    #[arg(long, env = "NODE_BOOTSTRAP", value_delimiter = ',')]
+    pub bootstrap: Option<Vec<PeerAddress>>,
+
+    /// Address other nodes should use to reach this one [default: <id>@localhost:<port>].
+    #[arg(long, env = "NODE_ADVERTISE_ADDRESS")]
+    pub advertise_address: Option<PeerAddress>,
+
+    /// Most peers this node connects to itself [default: 8].
+    #[arg(long, env = "NODE_MAX_OUTBOUND_PEERS")]
+    pub max_outbound_peers: Option<usize>,
+
+    /// Most peers allowed to hold an inbound P2P connection [default: 16].
+    #[arg(long, env = "NODE_MAX_INBOUND_PEERS")]
+    pub max_inbound_peers: Option<usize>,
+
+    /// Ids of the validators taking turns as slot leader [default: v1,v2,v3].
+    #[arg(long, env = "NODE_VALIDATORS", value_delimiter = ',')]
+    pub validators: Option<Vec<String>>,
+
+    /// Identity key of every validator, as `node_id=public_key`; nodes outside the validator set are trusted by the shared key alone.
+    #[arg(long, env = "NODE_VALIDATOR_KEYS", value_delimiter = ',', value_parser = parse_validator_key)]
+    pub validator_keys: Option<Vec<(String, String)>>,
+
+    /// Length of a consensus slot in seconds [default: 5].
+    #[arg(long, env = "NODE_SLOT_DURATION_SECS")]
+    pub slot_duration_secs: Option<u64>,
+
+    /// Key used to sign and verify blocks.
+    #[arg(long, env = "SHARED_KEY", hide_env_values = true)]
+    pub shared_key: Option<String>,
+
+    /// Bearer token for the /admin endpoints; they are disabled without one.
+    #[arg(long, env = "ADMIN_TOKEN", hide_env_values = true)]
+    pub admin_token: Option<String>,
+
+    /// Account that funds the faucet in the genesis block.
+    #[arg(long, env = "GENESIS_SENDER_ID")]
+    pub genesis_sender_id: Option<Uuid>,
+
+    /// Faucet account that funds new users.
+    #[arg(long, env = "FAUCET_WALLET_ID")]
+    pub faucet_wallet_id: Option<Uuid>,
+
+    /// Amount credited to the faucet by the genesis block [default: 1000000].
+    #[arg(long, env = "GENESIS_AMOUNT")]
+    pub genesis_amount: Option<f64>,
+
+    /// Journal file for a disk-backed mempool; the mempool is kept in memory only if omitted.
+    #[arg(long, env = "NODE_MEMPOOL_PATH")]
+    pub mempool_path: Option<PathBuf>,
+
+    /// File the chain is persisted to; the chain is kept in memory only if omitted.
+    #[arg(long, env = "NODE_CHAIN_PATH")]
+    pub chain_path: Option<PathBuf>,
+
+    /// Directory for the persisted user state and its snapshots.
+    #[arg(long, env = "NODE_STATE_DIR")]
+    pub state_dir: Option<PathBuf>,
+
+    /// File the node's identity key is kept in, created on first start [default: <state-dir>/identity.key].
+    #[arg(long, env = "NODE_IDENTITY_KEY_PATH")]
+    pub identity_key_path: Option<PathBuf>,
+
+    /// File the address book of discovered peers is kept in [default: <state-dir>/peers.json].
+    #[arg(long, env = "NODE_ADDRESS_BOOK_PATH")]
+    pub address_book_path: Option<PathBuf>,
+
+    /// Take a user-state snapshot every this many blocks [default: 100].
+    #[arg(long, env = "NODE_SNAPSHOT_INTERVAL")]
+    pub snapshot_interval: Option<u64>,
+
+    /// On startup, download a state snapshot from peers instead of replaying their whole chain.
+    #[arg(long)]
+    pub fast_sync: bool,
+
+    /// Most verbose level to log: error, warn, info, debug or trace [default: info].
+    #[arg(long, env = "NODE_LOG_LEVEL")]
+    pub log_level: Option<Level>,
+
+    /// Log line format [default: text].
+    #[arg(long, env = "NODE_LOG_FORMAT", value_enum)]
+    pub log_format: Option<LogFormat>,
+
+    /// Serve the whole chain from GET /blocks and every balance from GET /balances when no paging parameters are given.
     #[arg(long)]
-    pub id: String,
+    pub legacy_unpaged_routes: bool,
 
+    /// Print the public identity key, creating the key file if needed, and exit.
     #[arg(long)]
-    pub port: u16,
+    pub print_identity_key: bool,
+}
+
+impl Args {
+    // Зводить файл, змінні середовища та прапорці в одну перевірену конфігурацію
+    pub fn into_config(self) -> Result<Config, ConfigError> {
+        let config = self.resolve()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Те саме без Config::validate: ключі валідаторів ще не зібрані, коли
+    // вузол уперше друкує свій (--print-identity-key)
+    pub fn resolve(self) -> Result<Config, ConfigError> {
+        let file = match &self.config {
+            Some(path) => config_file::load(path)?,
+            None => FileConfig::default(),
+        };
+
+        let log_level = match self.log_level {
+            Some(level) => level,
+            None => match file.log.level {
+                Some(level) => level.parse().map_err(|_| ConfigError::Invalid {
+                    key: "log.level",
+                    reason: format!("unknown level {}", level),
+                })?,
+                None => Level::INFO,
+            },
+        };
+
+        let id = self
+            .id
+            .or(file.node.id)
+            .ok_or(ConfigError::Missing { key: "node.id" })?;
+        let port = self
+            .port
+            .or(file.node.port)
+            .ok_or(ConfigError::Missing { key: "node.port" })?;
+        let p2p_port = self
+            .p2p_port
+            .or(file.node.p2p_port)
+            .or_else(|| port.checked_add(DEFAULT_P2P_PORT_OFFSET))
+            .ok_or(ConfigError::Missing {
+                key: "node.p2p_port",
+            })?;
+        let state_dir = self.state_dir.or(file.storage.state_dir);
+
+        Ok(Config {
+            node: NodeConfig {
+                advertise_address: self
+                    .advertise_address
+                    .or(file.node.advertise_address)
+                    .unwrap_or_else(|| PeerAddress::local(&id, port)),
+                id,
+                chain_id: self
+                    .chain_id
+                    .or(file.node.chain_id)
+                    .unwrap_or_else(|| DEFAULT_CHAIN_ID.to_string()),
+                bind_address: self
+                    .bind_address
+                    .or(file.node.bind_address)
+                    .unwrap_or(DEFAULT_BIND_ADDRESS),
+                port,
+                p2p_port,
+                peers: self.peers.or(file.node.peers).unwrap_or_default(),
+                bootstrap: self.bootstrap.or(file.node.bootstrap).unwrap_or_default(),
+                max_outbound_peers: self
+                    .max_outbound_peers
+                    .or(file.node.max_outbound_peers)
+                    .unwrap_or(DEFAULT_MAX_OUTBOUND_PEERS),
+                max_inbound_peers: self
+                    .max_inbound_peers
+                    .or(file.node.max_inbound_peers)
+                    .unwrap_or(DEFAULT_MAX_INBOUND_PEERS),
+            },
+            consensus: ConsensusConfig {
+                validators: self
+                    .validators
+                    .or(file.consensus.validators)
+                    .unwrap_or_else(|| DEFAULT_VALIDATORS.map(String::from).to_vec()),
+                validator_keys: self
+                    .validator_keys
+                    .map(|keys| keys.into_iter().collect())
+                    .or(file.consensus.validator_keys)
+                    .unwrap_or_default(),
+                slot_duration: self
+                    .slot_duration_secs
+                    .or(file.consensus.slot_duration_secs)
+                    .map(Duration::from_secs)
+                    .unwrap_or(DEFAULT_SLOT_DURATION),
+            },
+            genesis: GenesisConfig {
+                sender_id: self.genesis_sender_id.or(file.genesis.sender_id).ok_or(
+                    ConfigError::Missing {
+                        key: "genesis.sender_id",
+                    },
+                )?,
+                faucet_wallet_id: self
+                    .faucet_wallet_id
+                    .or(file.genesis.faucet_wallet_id)
+                    .ok_or(ConfigError::Missing {
+                        key: "genesis.faucet_wallet_id",
+                    })?,
+                amount: self
+                    .genesis_amount
+                    .or(file.genesis.amount)
+                    .unwrap_or(DEFAULT_GENESIS_AMOUNT),
+            },
+            keys: KeysConfig {
+                shared_key: self.shared_key.or(file.keys.shared_key).ok_or(
+                    ConfigError::Missing {
+                        key: "keys.shared_key",
+                    },
+                )?,
+                admin_token: self.admin_token.or(file.keys.admin_token),
+            },
+            storage: StorageConfig {
+                chain_path: self.chain_path.or(file.storage.chain_path),
+                mempool_path: self.mempool_path.or(file.storage.mempool_path),
+                identity_key_path: self
+                    .identity_key_path
+                    .or(file.storage.identity_key_path)
+                    .or_else(|| state_dir.as_ref().map(|dir| dir.join("identity.key"))),
+                address_book_path: self
+                    .address_book_path
+                    .or(file.storage.address_book_path)
+                    .or_else(|| state_dir.as_ref().map(|dir| dir.join("peers.json"))),
+                state_dir,
+                snapshot_interval: self
+                    .snapshot_interval
+                    .or(file.storage.snapshot_interval)
+                    .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL),
+                fast_sync: self.fast_sync || file.storage.fast_sync.unwrap_or(false),
+            },
+            api: ApiConfig {
+                legacy_unpaged_routes: self.legacy_unpaged_routes
+                    || file.api.legacy_unpaged_routes.unwrap_or(false),
+            },
+            log: LogConfig {
+                level: log_level,
+                format: self
+                    .log_format
+                    .or(file.log.format)
+                    .unwrap_or(LogFormat::Text),
+            },
+        })
+    }
+}
+
+// `v2=<ключ>`; сам ключ перевіряє Config::validate
+fn parse_validator_key(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((node_id, key)) if !node_id.trim().is_empty() => {
+            Ok((node_id.trim().to_string(), key.trim().to_string()))
+        }
+        _ => Err(format!("expected node_id=public_key, got {}", value)),
+    }
+}
+
+// Розділи файлу конфігурації; усі ключі необов'язкові, невідомі — помилка
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    node: FileNode,
+    consensus: FileConsensus,
+    genesis: FileGenesis,
+    keys: FileKeys,
+    storage: FileStorage,
+    api: FileApi,
+    log: FileLog,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileNode {
+    id: Option<String>,
+    chain_id: Option<String>,
+    bind_address: Option<IpAddr>,
+    port: Option<u16>,
+    p2p_port: Option<u16>,
+    peers: Option<Vec<PeerAddress>>,
+    bootstrap: Option<Vec<PeerAddress>>,
+    advertise_address: Option<PeerAddress>,
+    max_outbound_peers: Option<usize>,
+    max_inbound_peers: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileConsensus {
+    validators: Option<Vec<String>>,
+    validator_keys: Option<HashMap<String, String>>,
+    slot_duration_secs: Option<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileGenesis {
+    sender_id: Option<Uuid>,
+    faucet_wallet_id: Option<Uuid>,
+    amount: Option<f64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileKeys {
+    shared_key: Option<String>,
+    admin_token: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileStorage {
+    chain_path: Option<PathBuf>,
+    mempool_path: Option<PathBuf>,
+    state_dir: Option<PathBuf>,
+    identity_key_path: Option<PathBuf>,
+    address_book_path: Option<PathBuf>,
+    snapshot_interval: Option<u64>,
+    fast_sync: Option<bool>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileApi {
+    legacy_unpaged_routes: Option<bool>,
+}
 
-    #[arg(long, value_delimiter = ',')]
-    pub peers: Vec<String>,
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FileLog {
+    level: Option<String>,
+    format: Option<LogFormat>,
 }
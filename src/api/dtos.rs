@@ -1,4 +1,11 @@
-use serde::Deserialize;
+use crate::api::error::ErrorCode;
+use crate::domain::{
+    account_transfer::AccountTransfer, block::Block, chain_event::EventTopic,
+    transaction::Transaction,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -12,3 +19,287 @@ pub struct CreateTransactionDto {
 pub struct CreateUserDto {
     pub balance: f64,
 }
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize)]
+pub struct BlocksQuery {
+    // Діапазон висот для синхронізації між вузлами; відповідь — масив блоків
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+    // Посторінкова видача: курсор — висота останнього блоку попередньої сторінки
+    pub cursor: Option<u64>,
+    pub limit: Option<usize>,
+    pub order: Option<SortOrder>,
+    pub proposer: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl BlocksQuery {
+    pub fn is_range(&self) -> bool {
+        self.from.is_some() || self.to.is_some()
+    }
+
+    pub fn is_paged(&self) -> bool {
+        self.cursor.is_some()
+            || self.limit.is_some()
+            || self.order.is_some()
+            || self.proposer.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+    }
+}
+
+#[derive(Serialize)]
+pub struct BlocksPageDto {
+    pub blocks: Vec<Block>,
+    pub next_cursor: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct BalancesQuery {
+    // Курсор — адреса останнього рахунку попередньої сторінки
+    pub cursor: Option<Uuid>,
+    pub limit: Option<usize>,
+    pub order: Option<SortOrder>,
+    // Повернути N рахунків з найбільшим балансом замість сторінки за адресами
+    pub top: Option<usize>,
+}
+
+impl BalancesQuery {
+    pub fn is_paged(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some() || self.order.is_some() || self.top.is_some()
+    }
+}
+
+#[derive(Serialize)]
+pub struct AccountBalanceDto {
+    pub address: Uuid,
+    pub balance: f64,
+}
+
+#[derive(Serialize)]
+pub struct BalancesPageDto {
+    pub accounts: Vec<AccountBalanceDto>,
+    pub next_cursor: Option<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct HeadersQuery {
+    pub from: Option<u64>,
+    pub locator: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatusDto {
+    Pending {
+        transaction: Transaction,
+    },
+    // У запропонованому блоці, який ще не набрав кворуму голосів
+    Proposed {
+        transaction: Transaction,
+        block_height: u64,
+        block_hash: String,
+        votes: usize,
+        quorum: usize,
+    },
+    Confirmed {
+        transaction: Transaction,
+        block_height: u64,
+        block_hash: String,
+        position: usize,
+        confirmations: u64,
+    },
+    Unknown {
+        id: Uuid,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct AccountHistoryQuery {
+    pub cursor: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct AccountHistoryDto {
+    pub address: Uuid,
+    pub transfers: Vec<AccountTransfer>,
+    pub next_cursor: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    // Теми через кому: blocks, votes, mempool, chain. Без параметра — усі
+    pub topics: Option<String>,
+    pub from_height: Option<u64>,
+}
+
+impl EventsQuery {
+    pub fn topics(&self) -> Result<HashSet<EventTopic>, String> {
+        match &self.topics {
+            None => Ok(EventTopic::ALL.into_iter().collect()),
+            Some(topics) => topics
+                .split(',')
+                .map(|topic| topic.trim().parse())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    pub method: String,
+    pub params: Option<Value>,
+}
+
+#[derive(Serialize)]
+pub struct RpcErrorDto {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum RpcResponse {
+    Success {
+        jsonrpc: &'static str,
+        result: Value,
+        id: Value,
+    },
+    Failure {
+        jsonrpc: &'static str,
+        error: RpcErrorDto,
+        id: Value,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum RpcReply {
+    Single(RpcResponse),
+    Batch(Vec<RpcResponse>),
+}
+
+#[derive(Deserialize)]
+pub struct GetBlockParams {
+    pub height: Option<u64>,
+    pub hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AddressParams {
+    pub address: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct TransactionIdParams {
+    pub id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct ValidatorsDto {
+    pub validators: Vec<String>,
+    pub quorum: usize,
+}
+
+#[derive(Serialize)]
+pub struct SyncStatusDto {
+    pub syncing: bool,
+    pub current_height: Option<u64>,
+    pub target_height: Option<u64>,
+    pub last_synced_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct PeerScoreDto {
+    pub node_id: String,
+    pub score: i32,
+    pub offences: u32,
+    pub last_offence: Option<String>,
+    pub banned_until: Option<u64>,
+    pub ban_reason: Option<String>,
+    pub connected: bool,
+}
+
+#[derive(Deserialize)]
+pub struct BanPeerDto {
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PeerDto {
+    pub node_id: String,
+    pub address: Option<String>,
+    pub chain_id: String,
+    pub genesis_hash: String,
+    pub protocol_version: u32,
+    pub best_height: u64,
+    pub identity_key: String,
+    pub validator_pubkey: String,
+    pub inbound: bool,
+    pub outbound: bool,
+    pub p2p_connected: bool,
+    pub last_handshake_at: u64,
+}
+
+#[derive(Serialize)]
+pub struct PeerStatusDto {
+    pub address: String,
+    pub node_id: Option<String>,
+    pub height: Option<u64>,
+    pub reachable: bool,
+    pub updated_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct NodeStatusDto {
+    pub node_id: String,
+    pub chain_id: String,
+    pub height: Option<u64>,
+    pub hash: Option<String>,
+    pub finalized_height: Option<u64>,
+    // Запропоновані блоки, що ще чекають на кворум
+    pub pending_blocks: usize,
+    pub slot: u64,
+    pub leader: Option<String>,
+    pub peer_count: usize,
+    pub peers: Vec<PeerStatusDto>,
+    pub mempool_size: usize,
+    pub syncing: bool,
+    pub target_height: Option<u64>,
+    pub last_synced_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct ApiErrorDto {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct ErrorResponseDto {
+    pub error: ApiErrorDto,
+}
+
+#[derive(Serialize)]
+pub struct CreatedUserDto {
+    pub id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct BalanceDto {
+    pub balance: f64,
+}
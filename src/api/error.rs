@@ -0,0 +1,182 @@
+use axum::{
+    Json,
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::api::dtos::{ApiErrorDto, ErrorResponseDto};
+use crate::blockchain::use_cases::submit_transaction::TransactionRejection;
+use crate::domain::error::{BlockError, HandshakeError, StorageError, TransactionError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidAmount,
+    SameAddress,
+    UnknownAccount,
+    InsufficientBalance,
+    DuplicateTransaction,
+    InvalidBlockHash,
+    InvalidSignature,
+    InvalidTransactions,
+    InvalidStateRoot,
+    StaleBlock,
+    EmptyChain,
+    StorageError,
+    BlockNotFound,
+    SnapshotNotFound,
+    ChunkNotFound,
+    UnknownTopic,
+    RouteNotFound,
+    HandshakeRejected,
+    UnknownProposer,
+    PeerBanned,
+    PeerNotFound,
+    Unauthorized,
+}
+
+/// Error returned by every REST handler. Rendered as
+/// `{"error": {"code": "...", "message": "..."}}` with the matching HTTP status.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code, message)
+    }
+
+    pub fn not_found(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponseDto {
+            error: ApiErrorDto {
+                code: self.code,
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<TransactionRejection> for ApiError {
+    fn from(rejection: TransactionRejection) -> Self {
+        let (status, code) = match &rejection {
+            TransactionRejection::Invalid(TransactionError::SameAddress { .. }) => {
+                (StatusCode::BAD_REQUEST, ErrorCode::SameAddress)
+            }
+            TransactionRejection::Invalid(TransactionError::InvalidAmount { .. }) => {
+                (StatusCode::BAD_REQUEST, ErrorCode::InvalidAmount)
+            }
+            TransactionRejection::Invalid(TransactionError::InsufficientFunds { .. }) => {
+                (StatusCode::BAD_REQUEST, ErrorCode::InsufficientBalance)
+            }
+            TransactionRejection::SenderNotFound | TransactionRejection::ReceiverNotFound => {
+                (StatusCode::NOT_FOUND, ErrorCode::UnknownAccount)
+            }
+            TransactionRejection::AlreadyExists => {
+                (StatusCode::CONFLICT, ErrorCode::DuplicateTransaction)
+            }
+        };
+        Self::new(status, code, rejection.to_string())
+    }
+}
+
+impl From<BlockError> for ApiError {
+    fn from(e: BlockError) -> Self {
+        let code = match &e {
+            BlockError::InvalidHash { .. } => ErrorCode::InvalidBlockHash,
+            BlockError::InvalidSignature { .. } | BlockError::ForgedBlock { .. } => {
+                ErrorCode::InvalidSignature
+            }
+            BlockError::DoesNotExtendTip { .. } => ErrorCode::StaleBlock,
+            BlockError::InvalidTransaction { .. } => ErrorCode::InvalidTransactions,
+            BlockError::StateRootMismatch { .. } => ErrorCode::InvalidStateRoot,
+            BlockError::UnknownProposer { .. } => ErrorCode::UnknownProposer,
+            BlockError::EmptyChain => {
+                return Self::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::EmptyChain,
+                    e.to_string(),
+                );
+            }
+            BlockError::MissingState { .. } => {
+                return Self::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    ErrorCode::StorageError,
+                    e.to_string(),
+                );
+            }
+            BlockError::Storage(storage) => return storage.clone().into(),
+        };
+        Self::bad_request(code, e.to_string())
+    }
+}
+
+impl From<HandshakeError> for ApiError {
+    fn from(e: HandshakeError) -> Self {
+        Self::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::HandshakeRejected,
+            e.to_string(),
+        )
+    }
+}
+
+impl From<StorageError> for ApiError {
+    fn from(e: StorageError) -> Self {
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::StorageError,
+            e.to_string(),
+        )
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(
+            rejection.status(),
+            ErrorCode::InvalidRequest,
+            rejection.body_text(),
+        )
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        Self::new(
+            rejection.status(),
+            ErrorCode::InvalidRequest,
+            rejection.body_text(),
+        )
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(
+            rejection.status(),
+            ErrorCode::InvalidRequest,
+            rejection.body_text(),
+        )
+    }
+}
@@ -0,0 +1,66 @@
+use axum::extract::{FromRequest, FromRequestParts};
+use axum::http::{StatusCode, header::AUTHORIZATION, request::Parts};
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::domain::app_state::AppState;
+use crate::domain::blockchain_repository::BlockchainRepository;
+use crate::domain::mempool_repository::MempoolRepository;
+use crate::domain::user_state_repository::UserStateRepository;
+
+// Обгортки над екстракторами axum, що повертають помилки розбору у форматі ApiError
+
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(ApiError))]
+pub struct ApiJson<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(ApiError))]
+pub struct ApiQuery<T>(pub T);
+
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(ApiError))]
+pub struct ApiPath<T>(pub T);
+
+/// Guards the `/admin` endpoints: the request must carry
+/// `Authorization: Bearer <admin token>` matching `keys.admin_token`.
+pub struct Admin;
+
+impl<B, M, U> FromRequestParts<AppState<B, M, U>> for Admin
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &AppState<B, M, U>,
+    ) -> Result<Self, Self::Rejection> {
+        let unauthorized = |message: &str| {
+            ApiError::new(StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized, message)
+        };
+        let Some(expected) = &app_state.config.keys.admin_token else {
+            return Err(unauthorized(
+                "Admin endpoints are disabled; start the node with --admin-token",
+            ));
+        };
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| unauthorized("Missing bearer token"))?;
+        // Порівняння за сталий час, щоб токен не вгадувався за часом відповіді
+        let matches = token.len() == expected.len()
+            && token
+                .bytes()
+                .zip(expected.bytes())
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0;
+        if !matches {
+            return Err(unauthorized("Invalid bearer token"));
+        }
+        Ok(Admin)
+    }
+}
@@ -1,26 +1,57 @@
-use crate::api::dtos::{CreateTransactionDto, CreateUserDto};
-use crate::blockchain::use_cases::add_block_to_chain::add_block_to_chain;
-use crate::blockchain::use_cases::sync_chain_task::sync_chain_task;
+use crate::api::dtos::{
+    AccountBalanceDto, AccountHistoryDto, AccountHistoryQuery, BalanceDto, BalancesPageDto,
+    BalancesQuery, BanPeerDto, BlocksPageDto, BlocksQuery, CreateTransactionDto, CreateUserDto,
+    CreatedUserDto, EventsQuery, HeadersQuery, NodeStatusDto, PeerDto, PeerScoreDto, PeerStatusDto,
+    SortOrder, TransactionStatusDto,
+};
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::extract::{Admin, ApiJson, ApiPath, ApiQuery};
+use crate::api::{openapi, routes, rpc};
+use crate::blockchain::use_cases::ban_peers::{ban_peer, unix_now};
+use crate::blockchain::use_cases::discover_peers::shared_addresses;
+use crate::blockchain::use_cases::handshake_peers::local_handshake;
+use crate::blockchain::use_cases::peer_transport::broadcast;
+use crate::blockchain::use_cases::submit_transaction::submit_transaction;
+use crate::domain::block_locator::{BlockHeaderEntry, headers_after};
 use crate::domain::blockchain_repository::BlockchainRepository;
+use crate::domain::chain_event::{ChainEvent, EventTopic};
+use crate::domain::handshake::Handshake;
 use crate::domain::mempool_repository::MempoolRepository;
+use crate::domain::metrics;
+use crate::domain::peer_message::PeerMessage;
+use crate::domain::peer_score::{DEFAULT_BAN_DURATION, PeerScore};
+use crate::domain::state_snapshot::{SnapshotChunk, SnapshotManifest};
 use crate::domain::user_state_repository::UserStateRepository;
-use crate::domain::vote::Vote;
 use crate::domain::{app_state::AppState, block::Block, transaction::Transaction};
 use axum::{
     Json,
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    extract::{
+        State,
+        ws::{
+            CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code,
+            rejection::WebSocketUpgradeRejection,
+        },
+    },
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
 };
-use serde_json::{Value, json};
-use std::collections::HashMap;
-use std::env;
-use std::time::{SystemTime, UNIX_EPOCH};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-pub async fn get_all_blocks_handler<B, M, U>(
+pub const MAX_BLOCKS_PER_REQUEST: u64 = 500;
+pub const MAX_HEADERS_PER_REQUEST: u64 = 2000;
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+pub const MAX_PAGE_SIZE: usize = 100;
+const PAGE_SCAN_BATCH: u64 = 100;
+
+pub async fn get_blocks_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-) -> Json<Vec<Block>>
+    ApiQuery(query): ApiQuery<BlocksQuery>,
+) -> Response
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
@@ -28,8 +59,134 @@ where
 {
     let repo = app_state.blockchain_repo.lock().await;
 
-    let blocks = repo.get_all_blocks().await;
-    Json(blocks)
+    if query.is_range() {
+        let from = query.from.unwrap_or(0);
+        let to = query
+            .to
+            .unwrap_or(u64::MAX)
+            .min(from.saturating_add(MAX_BLOCKS_PER_REQUEST - 1));
+        return Json(repo.get_range(from, to).await).into_response();
+    }
+
+    if app_state.config.api.legacy_unpaged_routes && !query.is_paged() {
+        return Json(repo.get_all_blocks().await).into_response();
+    }
+
+    let Some(tip) = repo.height().await else {
+        return Json(BlocksPageDto {
+            blocks: Vec::new(),
+            next_cursor: None,
+        })
+        .into_response();
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let order = query.order.unwrap_or_default();
+    let matches = |block: &Block| {
+        query
+            .proposer
+            .as_ref()
+            .is_none_or(|p| &block.header.proposer_id == p)
+            && query.since.is_none_or(|since| block.timestamp >= since)
+            && query.until.is_none_or(|until| block.timestamp <= until)
+    };
+
+    // Ланцюг переглядається пакетами, поки не знайдеться на один відфільтрований блок більше за сторінку
+    let mut blocks = Vec::new();
+    let mut position = match order {
+        SortOrder::Asc => query.cursor.map_or(Some(0), |c| c.checked_add(1)),
+        SortOrder::Desc => query.cursor.map_or(Some(tip), |c| c.checked_sub(1)),
+    }
+    .filter(|height| *height <= tip);
+    while let Some(height) = position
+        && blocks.len() <= limit
+    {
+        let (from, to) = match order {
+            SortOrder::Asc => (height, height.saturating_add(PAGE_SCAN_BATCH - 1).min(tip)),
+            SortOrder::Desc => (height.saturating_sub(PAGE_SCAN_BATCH - 1), height),
+        };
+        let mut batch = repo.get_range(from, to).await;
+        if order == SortOrder::Desc {
+            batch.reverse();
+        }
+        for block in batch {
+            if blocks.len() > limit {
+                break;
+            }
+            if matches(&block) {
+                blocks.push(block);
+            }
+        }
+        position = match order {
+            SortOrder::Asc => to.checked_add(1).filter(|h| *h <= tip),
+            SortOrder::Desc => from.checked_sub(1),
+        };
+    }
+
+    // Зайвий блок лише показує, що є наступна сторінка
+    let has_more = blocks.len() > limit;
+    blocks.truncate(limit);
+    let next_cursor = if has_more {
+        blocks.last().map(|b| b.header.height)
+    } else {
+        None
+    };
+    Json(BlocksPageDto {
+        blocks,
+        next_cursor,
+    })
+    .into_response()
+}
+
+pub async fn get_block_by_height_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiPath(height): ApiPath<u64>,
+) -> Result<Json<Block>, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let repo = app_state.blockchain_repo.lock().await;
+    repo.get_block_by_height(height)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(ErrorCode::BlockNotFound, "Block not found"))
+}
+
+pub async fn get_block_by_hash_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiPath(hash): ApiPath<String>,
+) -> Result<Json<Block>, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let repo = app_state.blockchain_repo.lock().await;
+    repo.get_block_by_hash(&hash)
+        .await
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(ErrorCode::BlockNotFound, "Block not found"))
+}
+
+pub async fn get_headers_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiQuery(query): ApiQuery<HeadersQuery>,
+) -> Json<Vec<BlockHeaderEntry>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let repo = app_state.blockchain_repo.lock().await;
+    let locator: Vec<String> = query
+        .locator
+        .map(|locator| locator.split(',').map(String::from).collect())
+        .unwrap_or_default();
+    Json(headers_after(&*repo, &locator, query.from, MAX_HEADERS_PER_REQUEST).await)
 }
 
 pub async fn get_all_transactions_handler<B, M, U>(
@@ -49,107 +206,251 @@ where
     Json(transactions)
 }
 
-pub async fn get_balance_handler<B, M, U>(
+pub async fn get_transaction_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-    Path(address): Path<Uuid>,
-) -> Json<Value>
+    ApiPath(id): ApiPath<Uuid>,
+) -> impl IntoResponse
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    let user_state_repo = app_state.user_state_repo.lock().await;
-    let balance = user_state_repo.get_balance(&address);
-    Json(json!({ "balance": balance }))
+    match transaction_status(&app_state, id).await {
+        status @ TransactionStatusDto::Unknown { .. } => {
+            (StatusCode::NOT_FOUND, Json(status)).into_response()
+        }
+        status => Json(status).into_response(),
+    }
 }
 
-pub async fn get_all_balances_handler<B, M, U>(
+pub async fn transaction_status<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    id: Uuid,
+) -> TransactionStatusDto
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    {
+        let repo = app_state.blockchain_repo.lock().await;
+        if let Some(location) = repo.get_transaction_location(&id).await
+            && let Some(block) = repo.get_block_by_height(location.block_height).await
+        {
+            let tip_height = repo.height().await.unwrap_or(location.block_height);
+            if let Some(transaction) = block.transactions.get(location.position) {
+                return TransactionStatusDto::Confirmed {
+                    transaction: transaction.clone(),
+                    block_height: location.block_height,
+                    block_hash: location.block_hash,
+                    position: location.position,
+                    confirmations: tip_height.saturating_sub(location.block_height) + 1,
+                };
+            }
+        }
+    }
+
+    // Лідер вже забрав транзакцію з мемпулу в блок, але голосів ще не вистачає
+    let proposed = app_state
+        .pending_blocks
+        .lock()
+        .await
+        .values()
+        .find_map(|block| {
+            let transaction = block.transactions.iter().find(|t| t.id == id)?;
+            Some((block.header.height, block.hash.clone(), transaction.clone()))
+        });
+    if let Some((block_height, block_hash, transaction)) = proposed {
+        let votes = app_state
+            .vote_counts
+            .lock()
+            .await
+            .get(&block_hash)
+            .map_or(0, Vec::len);
+        return TransactionStatusDto::Proposed {
+            transaction,
+            block_height,
+            block_hash,
+            votes,
+            quorum: app_state.node.lock().await.quorum(),
+        };
+    }
+
+    let mempool_repo = app_state.mempool_repo.lock().await;
+    match mempool_repo.get_transaction_by_id(&id) {
+        Some(transaction) => TransactionStatusDto::Pending { transaction },
+        None => TransactionStatusDto::Unknown { id },
+    }
+}
+
+pub async fn get_balance_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-) -> Json<HashMap<Uuid, f64>>
+    ApiPath(address): ApiPath<Uuid>,
+) -> Json<BalanceDto>
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
     let user_state_repo = app_state.user_state_repo.lock().await;
-    let balances = user_state_repo.get_balances().clone();
-    Json(balances)
+    let balance = user_state_repo.get_balance(&address);
+    Json(BalanceDto { balance })
 }
 
-pub async fn create_transaction_handler<B, M, U>(
+pub async fn get_account_history_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-    Json(payload): Json<CreateTransactionDto>,
-) -> impl IntoResponse
+    ApiPath(address): ApiPath<Uuid>,
+    ApiQuery(query): ApiQuery<AccountHistoryQuery>,
+) -> Json<AccountHistoryDto>
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    if payload.from == payload.to {
-        return (
-            StatusCode::BAD_REQUEST,
-            "Sender and receiver addresses cannot be the same",
-        )
-            .into_response();
-    }
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let repo = app_state.blockchain_repo.lock().await;
+    let mut transfers = repo
+        .get_account_transfers(&address, query.cursor, limit)
+        .await;
 
-    if payload.amount <= 0.0 {
-        return (
-            StatusCode::BAD_REQUEST,
-            "Transaction amount must be positive",
-        )
-            .into_response();
+    // Ланцюг після швидкої синхронізації починається зі снапшоту, і баланси в індексі
+    // пораховано від нуля; зсув до справжніх дає поточний баланс рахунку
+    if repo.get_block_by_height(0).await.is_none()
+        && let Some(newest) = repo.get_account_transfers(&address, None, 1).await.first()
+    {
+        let balance = app_state.user_state_repo.lock().await.get_balance(&address);
+        let offset = balance - newest.balance_after;
+        for transfer in &mut transfers {
+            transfer.balance_after += offset;
+        }
     }
 
-    if payload.amount.is_nan() || payload.amount.is_infinite() {
-        return (
-            StatusCode::BAD_REQUEST,
-            "Transaction amount cannot be NaN or infinite",
-        )
-            .into_response();
-    }
+    let next_cursor = match transfers.last() {
+        Some(last) if transfers.len() == limit && last.seq > 0 => Some(last.seq),
+        _ => None,
+    };
+    Json(AccountHistoryDto {
+        address,
+        transfers,
+        next_cursor,
+    })
+}
 
+pub async fn get_balances_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiQuery(query): ApiQuery<BalancesQuery>,
+) -> Response
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
     let user_state_repo = app_state.user_state_repo.lock().await;
 
-    if !user_state_repo.get_balances().contains_key(&payload.from) {
-        return (StatusCode::NOT_FOUND, "Sender not found").into_response();
+    if app_state.config.api.legacy_unpaged_routes && !query.is_paged() {
+        let balances: HashMap<Uuid, f64> = user_state_repo.get_balances().clone();
+        return Json(balances).into_response();
     }
 
-    if !user_state_repo.get_balances().contains_key(&payload.to) {
-        return (StatusCode::NOT_FOUND, "Receiver not found").into_response();
+    let mut accounts: Vec<(Uuid, f64)> = user_state_repo
+        .get_balances()
+        .iter()
+        .map(|(address, balance)| (*address, *balance))
+        .collect();
+    drop(user_state_repo);
+
+    if let Some(top) = query.top {
+        accounts.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        accounts.truncate(top.min(MAX_PAGE_SIZE));
+        return Json(BalancesPageDto {
+            accounts: accounts
+                .into_iter()
+                .map(|(address, balance)| AccountBalanceDto { address, balance })
+                .collect(),
+            next_cursor: None,
+        })
+        .into_response();
     }
 
-    let sender_balance = user_state_repo.get_balance(&payload.from);
-    if sender_balance < payload.amount {
-        return (StatusCode::BAD_REQUEST, "Insufficient balance").into_response();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let order = query.order.unwrap_or_default();
+    accounts.retain(|(address, _)| match (query.cursor, order) {
+        (None, _) => true,
+        (Some(cursor), SortOrder::Asc) => *address > cursor,
+        (Some(cursor), SortOrder::Desc) => *address < cursor,
+    });
+    accounts.sort_by_key(|(address, _)| *address);
+    if order == SortOrder::Desc {
+        accounts.reverse();
     }
 
-    let transaction = Transaction::new(payload.from, payload.to, payload.amount);
-    let mut mempool_repo = app_state.mempool_repo.lock().await;
+    let has_more = accounts.len() > limit;
+    accounts.truncate(limit);
+    let next_cursor = if has_more {
+        accounts.last().map(|(address, _)| *address)
+    } else {
+        None
+    };
+    Json(BalancesPageDto {
+        accounts: accounts
+            .into_iter()
+            .map(|(address, balance)| AccountBalanceDto { address, balance })
+            .collect(),
+        next_cursor,
+    })
+    .into_response()
+}
 
-    if mempool_repo.check_exists_by_id(&transaction.id) {
-        return (StatusCode::CONFLICT, "Transaction already exists").into_response();
-    }
-    mempool_repo.add_transaction(transaction.clone());
-    (StatusCode::CREATED, Json(transaction)).into_response()
+pub async fn create_transaction_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiJson(payload): ApiJson<CreateTransactionDto>,
+) -> Result<(StatusCode, Json<Transaction>), ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let transaction = submit_transaction(
+        app_state.user_state_repo.clone(),
+        app_state.mempool_repo.clone(),
+        &app_state.events,
+        payload.from,
+        payload.to,
+        payload.amount,
+    )
+    .await
+    .inspect_err(|rejection| {
+        app_state
+            .metrics
+            .inc(metrics::MEMPOOL_REJECTIONS, &[("reason", rejection.kind())])
+    })?;
+    broadcast(&app_state, PeerMessage::TxAnnounce(transaction.clone())).await;
+    Ok((StatusCode::CREATED, Json(transaction)))
 }
+
 pub async fn create_user_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-    Json(payload): Json<CreateUserDto>,
-) -> Json<Value>
+    ApiJson(payload): ApiJson<CreateUserDto>,
+) -> Result<Json<CreatedUserDto>, ApiError>
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    let mut user_state_repo = app_state.user_state_repo.lock().await;
-    let new_user_id = Uuid::new_v4();
-    user_state_repo.set_balance(new_user_id, payload.balance);
+    if !payload.balance.is_finite() || payload.balance <= 0.0 {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidAmount,
+            "Initial balance must be a positive number",
+        ));
+    }
 
-    let faucet_wallet_str =
-        env::var("FAUCET_WALLET_ID").expect("FAUCET_WALLET_ID must be set in .env");
-    let faucet_wallet_id =
-        Uuid::parse_str(&faucet_wallet_str).expect("Failed to parse FAUCET_WALLET_ID");
+    let faucet_wallet_id = app_state.config.genesis.faucet_wallet_id;
 
     let new_user_id = Uuid::new_v4();
     let funding_tx = Transaction {
@@ -159,183 +460,606 @@ where
         amount: payload.balance,
         timestamp: SystemTime::now()
             .duration_since(UNIX_EPOCH)
-            .unwrap()
+            .unwrap_or_default()
             .as_secs(),
     };
 
     {
         let mut mempool = app_state.mempool_repo.lock().await;
-        mempool.add_transaction(funding_tx);
+        mempool.add_transaction(funding_tx.clone());
     }
+    app_state.events.publish(ChainEvent::TransactionAdmitted {
+        transaction: funding_tx.clone(),
+    });
+    broadcast(&app_state, PeerMessage::TxAnnounce(funding_tx)).await;
 
-    println!(
-        "[API /user]: 💸 Funding transaction created for new user {}",
-        new_user_id
-    );
+    info!(user_id = %new_user_id, "Funding transaction created for new user");
 
-    Json(json!({ "id": new_user_id }))
+    Ok(Json(CreatedUserDto { id: new_user_id }))
 }
 
-pub async fn accept_block_handler<B, M, U>(
+pub async fn get_snapshot_manifest_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-    Json(received_block): Json<Block>,
-) -> (StatusCode, String)
+) -> Result<Json<SnapshotManifest>, ApiError>
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    println!(
-        "[API /block]: 📥 Отримано блок #{} від {}",
-        received_block.header.height, received_block.header.proposer_id
-    );
-
-    let expected_hash = received_block.calculate_hash();
-    if received_block.hash != expected_hash {
-        println!("[API /block]: ❌ ВІДХИЛЕНО: Неправильний хеш!");
-        return (StatusCode::BAD_REQUEST, "Invalid block hash".to_string());
-    }
+    let user_state_repo = app_state.user_state_repo.lock().await;
+    user_state_repo
+        .latest_snapshot()
+        .map(|snapshot| Json(snapshot.manifest()))
+        .ok_or_else(|| ApiError::not_found(ErrorCode::SnapshotNotFound, "No snapshot available"))
+}
 
-    let shared_key = app_state.shared_key.clone();
-    if !received_block.verify_signature(&shared_key) {
-        println!("[API /block]: ❌ ВІДХИЛЕНО: Неправильний підпис!");
-        return (
-            StatusCode::BAD_REQUEST,
-            "Invalid block signature".to_string(),
-        );
-    }
+pub async fn get_snapshot_chunk_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiPath((height, index)): ApiPath<(u64, usize)>,
+) -> Result<Json<SnapshotChunk>, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let user_state_repo = app_state.user_state_repo.lock().await;
+    let snapshot = user_state_repo
+        .latest_snapshot()
+        .filter(|s| s.height == height)
+        .ok_or_else(|| ApiError::not_found(ErrorCode::SnapshotNotFound, "Snapshot not found"))?;
+    snapshot
+        .chunk(index)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(ErrorCode::ChunkNotFound, "Chunk not found"))
+}
+
+pub async fn events_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiQuery(query): ApiQuery<EventsQuery>,
+    ws: Result<WebSocketUpgrade, WebSocketUpgradeRejection>,
+) -> Result<Response, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let ws = ws.map_err(|rejection| {
+        ApiError::new(
+            rejection.status(),
+            ErrorCode::InvalidRequest,
+            rejection.body_text(),
+        )
+    })?;
+    let topics = query
+        .topics()
+        .map_err(|reason| ApiError::bad_request(ErrorCode::UnknownTopic, reason))?;
+    Ok(ws.on_upgrade(move |socket| stream_events(app_state, socket, topics, query.from_height)))
+}
 
-    let mut blockchain = app_state.blockchain_repo.lock().await;
+async fn stream_events<B, M, U>(
+    app_state: AppState<B, M, U>,
+    mut socket: WebSocket,
+    topics: HashSet<EventTopic>,
+    from_height: Option<u64>,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Підписуємося до читання історії, щоб не пропустити блоки, додані під час відтворення
+    let mut receiver = app_state.events.subscribe();
+    let mut replayed_to: Option<u64> = None;
+    // Блоки вище цієї висоти ще чекають кворуму й відтворюються як block_accepted
+    let finalized_height = app_state.consensus_status.lock().await.finalized_height;
 
-    let last_block = blockchain.get_last_block().await;
+    if let Some(mut next) = from_height
+        && topics.contains(&EventTopic::Blocks)
     {
-        if received_block.header.parent_hash == last_block.hash
-            && received_block.header.height == last_block.header.height + 1
-        {
-            {
-                let mut user_state = app_state.user_state_repo.lock().await;
-                for tx in &received_block.transactions {
-                    if !user_state.apply_transaction(tx) {
-                        println!(
-                            "[API /block]: ❌ ВІДХИЛЕНО: Блок содержит невалидную транзакцию {}.",
-                            tx.id
-                        );
-
-                        return (
-                            StatusCode::BAD_REQUEST,
-                            "Block contains invalid transactions".to_string(),
-                        );
-                    }
+        loop {
+            let blocks = app_state
+                .blockchain_repo
+                .lock()
+                .await
+                .get_range(next, next.saturating_add(MAX_BLOCKS_PER_REQUEST - 1))
+                .await;
+            let Some(last) = blocks.last() else {
+                break;
+            };
+            next = last.header.height + 1;
+            replayed_to = Some(last.header.height);
+            for block in blocks {
+                let event = if Some(block.header.height) <= finalized_height {
+                    ChainEvent::BlockFinalized { block }
+                } else {
+                    ChainEvent::BlockAccepted { block }
+                };
+                if send_event(&mut socket, &event).await.is_err() {
+                    return;
                 }
             }
+        }
+    }
 
-            println!(
-                "[API /block]: ✅ Блок #{} пройшов усі перевірки.",
-                received_block.header.height
-            );
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    if let ChainEvent::ChainReorganized { .. } = event {
+                        replayed_to = None;
+                    }
+                    let already_sent = match (&event, replayed_to) {
+                        (ChainEvent::BlockAccepted { block }, Some(replayed)) => {
+                            block.header.height <= replayed
+                        }
+                        (ChainEvent::BlockFinalized { block }, Some(replayed)) => {
+                            block.header.height <= replayed
+                                && Some(block.header.height) <= finalized_height
+                        }
+                        _ => false,
+                    };
+                    if already_sent {
+                        continue;
+                    }
+                    if topics.contains(&event.topic())
+                        && send_event(&mut socket, &event).await.is_err()
+                    {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    // Клієнт не встигає: закриваємо з'єднання, щоб він перепідключився з from_height
+                    let frame = CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: format!("Lagged behind by {} events", skipped).into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(frame))).await;
+                    return;
+                }
+                Err(RecvError::Closed) => return,
+            },
+        }
+    }
+}
 
-            let block_hash = received_block.hash.clone();
-            blockchain.add_block(received_block).await;
+async fn send_event(socket: &mut WebSocket, event: &ChainEvent) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(event).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text.into())).await
+}
 
-            drop(blockchain);
+pub async fn rpc_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    body: String,
+) -> Response
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    match rpc::handle_payload(&app_state, &body).await {
+        Some(response) => Json(response).into_response(),
+        // Пакет із самих сповіщень не потребує відповіді
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
 
-            let (my_id, peer_addresses) = {
-                let node = app_state.node.lock().await;
-                (node.id.clone(), node.peers.clone())
-            };
+// Лише перевірка сумісності: тут пір нічим не доводить, що володіє ключем зі свого
+// handshake, тож вхідний слот і адресу в книзі він отримує після підписаного hello в P2P
+pub async fn handshake_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    ApiJson(handshake): ApiJson<Handshake>,
+) -> Result<Json<Handshake>, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let local = local_handshake(&app_state).await;
+    if let Err(e) = handshake.check(
+        &local,
+        &app_state.config.keys.shared_key,
+        &app_state.config.consensus.validator_keys,
+    ) {
+        warn!(node_id = %handshake.node_id, reason = %e, "Refused handshake");
+        app_state
+            .metrics
+            .inc(metrics::PEER_HANDSHAKE_FAILURES, &[("reason", e.kind())]);
+        return Err(e.into());
+    }
 
-            let vote = Vote {
-                block_hash: block_hash,
-                voter_id: my_id,
-                decision: "ACK".to_string(),
-            };
+    let node_id = handshake.node_id.clone();
+    if app_state
+        .scores
+        .lock()
+        .await
+        .is_banned(&node_id, unix_now())
+    {
+        debug!(node_id = %node_id, "Refused handshake from banned peer");
+        app_state
+            .metrics
+            .inc(metrics::PEER_HANDSHAKE_FAILURES, &[("reason", "banned")]);
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::PeerBanned,
+            format!("Node {} is banned", node_id),
+        ));
+    }
+    Ok(Json(local))
+}
 
-            let http_client = app_state.http_client;
-            for peer_addr in &peer_addresses {
-                let target_url = format!("http://{}/vote", peer_addr);
-                let _ = http_client.post(&target_url).json(&vote).send().await;
-            }
+// Ті самі адреси, що піри отримують P2P-запитом. Відповідь публічна: вона містить лише адреси, які вузли й так оголошують,
+// а кожну з них отримувач ще перевіряє власним handshake перед тим, як до неї підключитися
+pub async fn get_peer_addresses_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+) -> Json<Vec<String>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    Json(shared_addresses(&app_state).await)
+}
 
-            return (StatusCode::OK, "Block accepted, ACK sent".to_string());
-        } else if received_block.header.height > last_block.header.height {
-            println!(
-                "[API /block]: 🍴 КОНФЛІКТ (FORK)! Наша висота {}, отримано {}.",
-                last_block.header.height, received_block.header.height
-            );
-
-            tokio::spawn(sync_chain_task(app_state.clone()));
-
-            return (
-                StatusCode::CONFLICT,
-                "Fork detected, starting sync".to_string(),
-            );
-        } else {
-            println!("[API /block]: ❌ ВІДХИЛЕНО: Блок належить до коротшого ланцюга.");
-            return (
-                StatusCode::BAD_REQUEST,
-                "Block is from a shorter chain".to_string(),
-            );
+pub async fn get_peers_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+) -> Json<Vec<PeerDto>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let links = app_state.links.lock().await;
+    let peers = app_state
+        .peers
+        .lock()
+        .await
+        .all()
+        .into_iter()
+        .map(|peer| PeerDto {
+            p2p_connected: links.contains(&peer.handshake.node_id),
+            node_id: peer.handshake.node_id,
+            address: peer.address.map(|address| address.to_string()),
+            chain_id: peer.handshake.chain_id,
+            genesis_hash: peer.handshake.genesis_hash,
+            protocol_version: peer.handshake.protocol_version,
+            best_height: peer.handshake.best_height,
+            identity_key: peer.handshake.identity_key,
+            validator_pubkey: peer.handshake.validator_pubkey,
+            inbound: peer.inbound,
+            outbound: peer.outbound,
+            last_handshake_at: peer.last_handshake_at,
+        })
+        .collect();
+    Json(peers)
+}
+
+pub async fn get_admin_peers_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    _: Admin,
+) -> Json<Vec<PeerScoreDto>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let connected: Vec<String> = app_state
+        .peers
+        .lock()
+        .await
+        .all()
+        .into_iter()
+        .map(|peer| peer.handshake.node_id)
+        .collect();
+    let mut scored = app_state.scores.lock().await.all(unix_now());
+    // Підключені піри без порушень показуються з нульовим рахунком
+    for node_id in &connected {
+        if !scored.iter().any(|(id, _)| id == node_id) {
+            scored.push((node_id.clone(), PeerScore::default()));
         }
     }
+    scored.sort_by(|a, b| a.0.cmp(&b.0));
+    Json(
+        scored
+            .into_iter()
+            .map(|(node_id, score)| {
+                let connected = connected.contains(&node_id);
+                peer_score_dto(node_id, score, connected)
+            })
+            .collect(),
+    )
 }
 
-pub async fn accept_vote_handler<B, M, U>(
+pub async fn ban_peer_handler<B, M, U>(
     State(app_state): State<AppState<B, M, U>>,
-    Json(vote): Json<Vote>,
-) -> (StatusCode, String)
+    _: Admin,
+    ApiPath(node_id): ApiPath<String>,
+    ApiJson(payload): ApiJson<BanPeerDto>,
+) -> Result<Json<PeerScoreDto>, ApiError>
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    if vote.decision != "ACK" {
-        return (StatusCode::OK, "Vote received (NACK)".to_string());
+    if node_id == app_state.config.node.id {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidRequest,
+            "A node cannot ban itself",
+        ));
     }
-    let total_validators = {
+    let duration = match payload.duration_secs {
+        Some(0) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidRequest,
+                "duration_secs must be positive",
+            ));
+        }
+        Some(secs) => Duration::from_secs(secs),
+        None => DEFAULT_BAN_DURATION,
+    };
+    let reason = payload
+        .reason
+        .unwrap_or_else(|| "banned by an operator".to_string());
+    ban_peer(&app_state, &node_id, duration, reason).await;
+    let score = app_state
+        .scores
+        .lock()
+        .await
+        .get(&node_id, unix_now())
+        .unwrap_or_default();
+    Ok(Json(peer_score_dto(node_id, score, false)))
+}
+
+pub async fn unban_peer_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+    _: Admin,
+    ApiPath(node_id): ApiPath<String>,
+) -> Result<Json<PeerScoreDto>, ApiError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let now = unix_now();
+    let score = {
+        let mut scores = app_state.scores.lock().await;
+        if !scores.unban(&node_id, now) {
+            return Err(ApiError::not_found(
+                ErrorCode::PeerNotFound,
+                format!("Node {} is not banned", node_id),
+            ));
+        }
+        scores.get(&node_id, now).unwrap_or_default()
+    };
+    info!(node_id = %node_id, "Peer unbanned by an operator");
+    let connected = app_state.peers.lock().await.is_known(&node_id);
+    Ok(Json(peer_score_dto(node_id, score, connected)))
+}
+
+fn peer_score_dto(node_id: String, score: PeerScore, connected: bool) -> PeerScoreDto {
+    PeerScoreDto {
+        node_id,
+        score: score.score,
+        offences: score.offences,
+        last_offence: score.last_offence.map(String::from),
+        banned_until: score.banned_until,
+        ban_reason: score.ban_reason,
+        connected,
+    }
+}
+
+pub async fn get_status_handler<B, M, U>(
+    State(app_state): State<AppState<B, M, U>>,
+) -> Json<NodeStatusDto>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Валідатор додає блок лідера до ланцюга, щойно той пройшов перевірку, ще до кворуму,
+    // тож вершина може бути вище за finalized_height; остаточність показує лише він
+    let tip = app_state
+        .blockchain_repo
+        .lock()
+        .await
+        .get_last_block()
+        .await;
+    let mempool_size = app_state
+        .mempool_repo
+        .lock()
+        .await
+        .get_all_transactions()
+        .len();
+    let pending_blocks = app_state.pending_blocks.lock().await.len();
+    let consensus = app_state.consensus_status.lock().await.clone();
+    let sync = app_state.sync_status.lock().await.clone();
+    let (node_id, chain_id, peer_addresses) = {
         let node = app_state.node.lock().await;
-        node.validator_ids.len()
+        (node.id.clone(), node.chain_id.clone(), node.peers.clone())
     };
-    let quorum_needed = (total_validators / 2) + 1;
-    let mut vote_counts = app_state.vote_counts.lock().await;
-    let voters_for_this_block = vote_counts
-        .entry(vote.block_hash.clone())
-        .or_insert_with(Vec::new);
-    if !voters_for_this_block.contains(&vote.voter_id) {
-        voters_for_this_block.push(vote.voter_id.clone());
+
+    let peers: Vec<PeerStatusDto> = peer_addresses
+        .into_iter()
+        .map(|address| {
+            let known = sync.peers.get(&address.to_string());
+            PeerStatusDto {
+                height: known.and_then(|p| p.height),
+                reachable: known.is_some_and(|p| p.reachable),
+                updated_at: known.map(|p| p.updated_at),
+                node_id: address.node_id.clone(),
+                address: address.to_string(),
+            }
+        })
+        .collect();
+
+    Json(NodeStatusDto {
+        node_id,
+        chain_id,
+        height: tip.as_ref().map(|b| b.header.height),
+        hash: tip.as_ref().map(|b| b.hash.clone()),
+        finalized_height: consensus.finalized_height,
+        pending_blocks,
+        slot: consensus.slot,
+        leader: consensus.leader,
+        peer_count: peers.len(),
+        peers,
+        mempool_size,
+        syncing: sync.syncing,
+        target_height: sync.target_height,
+        last_synced_at: sync.last_synced_at,
+    })
+}
+
+pub async fn metrics_handler<B, M, U>(State(app_state): State<AppState<B, M, U>>) -> Response
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mempool_size = app_state
+        .mempool_repo
+        .lock()
+        .await
+        .get_all_transactions()
+        .len();
+    app_state
+        .metrics
+        .set(metrics::MEMPOOL_SIZE, &[], mempool_size as f64);
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        app_state.metrics.render(),
+    )
+        .into_response()
+}
+
+pub async fn openapi_handler<B, M, U>() -> Json<Value>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    Json(openapi::document(&routes::endpoints::<B, M, U>()))
+}
+
+pub async fn fallback_handler() -> ApiError {
+    ApiError::not_found(ErrorCode::RouteNotFound, "Route not found")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_status_handler, handshake_handler, transaction_status};
+    use crate::api::dtos::TransactionStatusDto;
+    use crate::api::extract::ApiJson;
+    use crate::blockchain::use_cases::accept_block::accept_block;
+    use crate::blockchain::use_cases::handshake_peers::local_handshake;
+    use crate::domain::app_state::testing::{app_state, child_block, transfer};
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::domain::config::Config;
+    use crate::domain::gossip::Gossip;
+    use crate::domain::mempool_repository::MempoolRepository;
+    use crate::domain::user_state_repository::UserStateRepository;
+    use axum::extract::State;
+
+    #[tokio::test]
+    async fn http_handshake_takes_no_slot_and_adds_no_address() {
+        let validators = ["v1", "v2", "v3"];
+        let peer = app_state(Config::for_tests("v2", &validators)).await;
+        let app_state = app_state(Config::for_tests("v1", &validators)).await;
+        let handshake = local_handshake(&peer).await;
+        assert!(handshake.listen_address.is_some());
+
+        let reply = handshake_handler(State(app_state.clone()), ApiJson(handshake))
+            .await
+            .unwrap();
+
+        assert_eq!(reply.0.node_id, "v1");
+        assert!(app_state.peers.lock().await.all().is_empty());
+        assert_eq!(app_state.address_book.lock().await.len(), 0);
     }
-    let current_vote_count = voters_for_this_block.len();
-    let my_id = app_state.node.lock().await.id.clone();
-    println!(
-        "[API /vote]: 📥 (Я {}) Отримано голос від {}: {} за блок ...{}",
-        my_id,
-        vote.voter_id,
-        vote.decision,
-        &vote.block_hash[..5]
-    );
-    if current_vote_count >= quorum_needed {
-        println!(
-            "[API /vote]: КВОРУМ ЗІБРАНО! Лідер додає блок ...{}!",
-            &vote.block_hash[..5]
-        );
-
-        let block_to_add = {
-            let mut pending_blocks = app_state.pending_blocks.lock().await;
-            pending_blocks.remove(&vote.block_hash)
-        };
 
-        if let Some(block) = block_to_add {
-            add_block_to_chain(app_state.blockchain_repo.clone(), block.clone()).await;
-            println!(
-                "[API /vote]: ✅ Лідер успішно додав блок #{} до свого ланцюга.",
-                block.clone().header.height
-            );
-            vote_counts.remove(&vote.block_hash);
-        } else {
-            println!(
-                "[API /vote]: ⚠️ Блок ...{} вже був доданий.",
-                &vote.block_hash[..5]
-            );
+    #[tokio::test]
+    async fn status_reports_an_accepted_tip_as_not_yet_final() {
+        let app_state = app_state(Config::for_tests("v2", &["v1", "v2", "v3", "v4"])).await;
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        let block = child_block(&genesis, "v1", vec![transfer(2, 10.0)], &mut balances);
+
+        // Голоси лідера й цього вузла — два з трьох потрібних
+        accept_block(&app_state, "v1", Gossip::new(block.clone())).await;
+        let status = get_status_handler(State(app_state)).await.0;
+
+        assert_eq!(status.height, Some(1));
+        assert_eq!(status.hash, Some(block.hash));
+        assert_eq!(status.finalized_height, Some(0));
+    }
+
+    #[tokio::test]
+    async fn transaction_in_a_proposed_block_is_not_unknown() {
+        let app_state = app_state(Config::for_tests("v1", &["v1", "v2", "v3"])).await;
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        let tx = transfer(2, 10.0);
+        app_state
+            .mempool_repo
+            .lock()
+            .await
+            .add_transaction(tx.clone());
+        assert!(matches!(
+            transaction_status(&app_state, tx.id).await,
+            TransactionStatusDto::Pending { .. }
+        ));
+
+        // Лідер забрав транзакцію з мемпулу і чекає голосів за блок
+        app_state.mempool_repo.lock().await.drain_transactions();
+        let block = child_block(&genesis, "v1", vec![tx.clone()], &mut balances);
+        app_state
+            .vote_counts
+            .lock()
+            .await
+            .insert(block.hash.clone(), vec!["v1".to_string()]);
+        app_state
+            .pending_blocks
+            .lock()
+            .await
+            .insert(block.hash.clone(), block.clone());
+
+        match transaction_status(&app_state, tx.id).await {
+            TransactionStatusDto::Proposed {
+                block_hash,
+                block_height,
+                votes,
+                quorum,
+                ..
+            } => {
+                assert_eq!(block_hash, block.hash);
+                assert_eq!(block_height, 1);
+                assert_eq!((votes, quorum), (1, 2));
+            }
+            _ => panic!("transaction should be proposed"),
         }
     }
-    (StatusCode::OK, "Vote received".to_string())
 }
@@ -0,0 +1,61 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{Instrument, debug, info_span};
+
+use crate::domain::metrics::{self, Metrics};
+
+// Кожен запит отримує спан із методом і шаблоном маршруту
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+    let span = info_span!("request", method = %request.method(), route = %route);
+    async move {
+        let started_at = Instant::now();
+        let response = next.run(request).await;
+        debug!(
+            status = response.status().as_u16(),
+            elapsed_ms = started_at.elapsed().as_millis() as u64,
+            "Request completed"
+        );
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+// Час обробки запиту за шаблоном маршруту, а не за конкретним шляхом,
+// щоб кількість серій не росла разом із висотами й адресами
+pub async fn track_request_duration(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_default();
+    let method = request.method().to_string();
+    let started_at = Instant::now();
+
+    let response = next.run(request).await;
+
+    metrics.observe(
+        metrics::HTTP_REQUEST_DURATION,
+        &[
+            ("route", &route),
+            ("method", &method),
+            ("status", response.status().as_str()),
+        ],
+        started_at.elapsed(),
+    );
+    response
+}
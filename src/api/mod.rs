@@ -1,4 +1,10 @@
 pub mod args;
 pub mod dtos;
+pub mod error;
+pub mod extract;
 pub mod handlers;
+pub mod middleware;
+pub mod openapi;
+pub mod routes;
+pub mod rpc;
 pub mod server;
@@ -0,0 +1,1011 @@
+use crate::api::handlers::{
+    DEFAULT_PAGE_SIZE, MAX_BLOCKS_PER_REQUEST, MAX_HEADERS_PER_REQUEST, MAX_PAGE_SIZE,
+};
+use axum::http::Method;
+use serde_json::{Map, Value, json};
+
+const OPENAPI_VERSION: &str = "3.0.3";
+
+// Документ OpenAPI 3 для REST API вузла. До нього потрапляють лише зареєстровані маршрути
+// з routes.rs; тест нижче перевіряє, що кожен із них описаний в operations()
+pub fn document(endpoints: &[(Method, &str)]) -> Value {
+    let operations = operations();
+    let mut paths = Map::new();
+    for (method, path) in endpoints {
+        let method = method.as_str().to_lowercase();
+        if let Some(operation) = operations.get(*path).and_then(|o| o.get(&method)) {
+            let entry = paths.entry(path.to_string()).or_insert_with(|| json!({}));
+            entry[method] = operation.clone();
+        }
+    }
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "block_chain node API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "adminToken": { "type": "http", "scheme": "bearer" }
+            },
+            "responses": {
+                "Error": {
+                    "description": "Request failed",
+                    "content": {
+                        "application/json": { "schema": schema_ref("ErrorResponse") }
+                    }
+                }
+            }
+        }
+    })
+}
+
+// Опис кожного ендпоінта за шляхом і методом
+fn operations() -> Value {
+    json!({
+        "/": {
+            "get": operation("Health check", &[], None, text_response("Greeting from the node")),
+        },
+        "/openapi.json": {
+            "get": operation(
+                "This OpenAPI document",
+                &[],
+                None,
+                json_response("OpenAPI 3 document", json!({ "type": "object" })),
+            ),
+        },
+        "/status": {
+            "get": operation(
+                "Node health: chain tip, current slot and leader, peers, mempool and sync progress",
+                &[],
+                None,
+                json_response("Node status", schema_ref("NodeStatus")),
+            ),
+        },
+        "/metrics": {
+            "get": operation(
+                "Prometheus metrics",
+                &[],
+                None,
+                text_response("Metrics in the Prometheus text exposition format"),
+            ),
+        },
+        "/blocks": {
+            "get": operation(
+                "List blocks: a height range for peers, the whole chain in legacy mode, or a page",
+                &[
+                    query_param("from", integer(), "First height of a range"),
+                    query_param(
+                        "to",
+                        integer(),
+                        &format!(
+                            "Last height of a range, at most {} blocks",
+                            MAX_BLOCKS_PER_REQUEST
+                        ),
+                    ),
+                    query_param(
+                        "cursor",
+                        integer(),
+                        "Height of the last block of the previous page",
+                    ),
+                    limit_param(),
+                    order_param(),
+                    query_param("proposer", string(), "Only blocks proposed by this validator"),
+                    query_param("since", integer(), "Only blocks with timestamp >= since"),
+                    query_param("until", integer(), "Only blocks with timestamp <= until"),
+                ],
+                None,
+                json_response(
+                    "A page of blocks, or an array for range requests",
+                    json!({ "oneOf": [schema_ref("BlocksPage"), array_of("Block")] }),
+                ),
+            ),
+        },
+        "/blocks/{height}": {
+            "get": operation(
+                "Get a block by height",
+                &[path_param("height", integer())],
+                None,
+                json_response("The block", schema_ref("Block")),
+            ),
+        },
+        "/block/hash/{hash}": {
+            "get": operation(
+                "Get a block by hash",
+                &[path_param("hash", string())],
+                None,
+                json_response("The block", schema_ref("Block")),
+            ),
+        },
+        "/headers": {
+            "get": operation(
+                &format!("Get up to {} block headers", MAX_HEADERS_PER_REQUEST),
+                &[
+                    query_param("from", integer(), "First height to return"),
+                    query_param("locator", string(), "Comma-separated block locator hashes"),
+                ],
+                None,
+                json_response("Block headers", array_of("BlockHeaderEntry")),
+            ),
+        },
+        "/user": {
+            "post": operation(
+                "Create a user funded from the faucet",
+                &[],
+                Some("CreateUserDto"),
+                json_response("The new user", schema_ref("CreatedUser")),
+            ),
+        },
+        "/transactions": {
+            "get": operation(
+                "List pending transactions in the mempool",
+                &[],
+                None,
+                json_response("Pending transactions", array_of("Transaction")),
+            ),
+            "post": operation(
+                "Submit a transaction to the mempool",
+                &[],
+                Some("CreateTransactionDto"),
+                json!({
+                    "201": {
+                        "description": "The accepted transaction",
+                        "content": {
+                            "application/json": { "schema": schema_ref("Transaction") }
+                        }
+                    }
+                }),
+            ),
+        },
+        "/transactions/{id}": {
+            "get": operation(
+                "Get the status of a transaction; unknown ids answer 404 with status `unknown`",
+                &[path_param("id", uuid())],
+                None,
+                json_response("Transaction status", schema_ref("TransactionStatus")),
+            ),
+        },
+        "/balances": {
+            "get": operation(
+                "List balances: all of them in legacy mode, the top N, or a page sorted by address",
+                &[
+                    query_param(
+                        "cursor",
+                        uuid(),
+                        "Address of the last account of the previous page",
+                    ),
+                    limit_param(),
+                    order_param(),
+                    query_param("top", integer(), "Return the N largest balances"),
+                ],
+                None,
+                json_response(
+                    "A page of balances, or an address-to-balance map in legacy mode",
+                    json!({
+                        "oneOf": [
+                            schema_ref("BalancesPage"),
+                            { "type": "object", "additionalProperties": number() },
+                        ]
+                    }),
+                ),
+            ),
+        },
+        "/accounts/{address}/transactions": {
+            "get": operation(
+                "Confirmed transfers of an account, newest first",
+                &[
+                    path_param("address", uuid()),
+                    query_param("cursor", integer(), "`next_cursor` of the previous page"),
+                    limit_param(),
+                ],
+                None,
+                json_response("Account history", schema_ref("AccountHistory")),
+            ),
+        },
+        "/balance/{address}": {
+            "get": operation(
+                "Get the balance of an account",
+                &[path_param("address", uuid())],
+                None,
+                json_response("The balance", schema_ref("Balance")),
+            ),
+        },
+        "/handshake": {
+            "post": operation(
+                "Check compatibility and get this node's handshake; a peer on another chain, genesis or protocol version is refused",
+                &[],
+                Some("Handshake"),
+                json_response("This node's handshake", schema_ref("Handshake")),
+            ),
+        },
+        "/peers": {
+            "get": operation(
+                "Peers that completed a handshake with this node",
+                &[],
+                None,
+                json_response("Peers", array_of("Peer")),
+            ),
+        },
+        "/peers/addresses": {
+            "get": operation(
+                "Addresses of live peers this node knows, for peer exchange",
+                &[],
+                None,
+                json_response(
+                    "Peer addresses as `[https://][node_id@]host:port`",
+                    json!({ "type": "array", "items": string() }),
+                ),
+            ),
+        },
+        "/admin/peers": {
+            "get": admin(operation(
+                "Connected, penalized and banned peers with their misbehaviour score",
+                &[],
+                None,
+                json_response("Peer scores", array_of("PeerScore")),
+            )),
+        },
+        "/admin/peers/{node_id}/ban": {
+            "post": admin(operation(
+                "Ban a peer; it is disconnected and refused until the ban ends",
+                &[path_param("node_id", string())],
+                Some("BanPeer"),
+                json_response("The banned peer", schema_ref("PeerScore")),
+            )),
+            "delete": admin(operation(
+                "Lift a peer's ban and reset its score",
+                &[path_param("node_id", string())],
+                None,
+                json_response("The unbanned peer", schema_ref("PeerScore")),
+            )),
+        },
+        "/snapshot": {
+            "get": operation(
+                "Manifest of the latest state snapshot",
+                &[],
+                None,
+                json_response("Snapshot manifest", schema_ref("SnapshotManifest")),
+            ),
+        },
+        "/snapshot/{height}/chunks/{index}": {
+            "get": operation(
+                "One chunk of accounts from a state snapshot",
+                &[path_param("height", integer()), path_param("index", integer())],
+                None,
+                json_response("Snapshot chunk", schema_ref("SnapshotChunk")),
+            ),
+        },
+        "/events": {
+            "get": operation(
+                "WebSocket stream of node events",
+                &[
+                    query_param(
+                        "topics",
+                        string(),
+                        "Comma-separated topics: blocks, votes, mempool, chain",
+                    ),
+                    query_param(
+                        "from_height",
+                        integer(),
+                        "Replay stored blocks from this height",
+                    ),
+                ],
+                None,
+                json!({ "101": { "description": "Switching to the WebSocket protocol" } }),
+            ),
+        },
+        "/rpc": {
+            "post": operation(
+                "JSON-RPC 2.0 call or batch of at most 100 calls",
+                &[],
+                Some("RpcRequest"),
+                json!({
+                    "200": {
+                        "description": "JSON-RPC response or batch of responses",
+                        "content": { "application/json": { "schema": { "type": "object" } } }
+                    },
+                    "204": { "description": "The batch contained only notifications" }
+                }),
+            ),
+        },
+    })
+}
+
+fn schemas() -> Value {
+    json!({
+        "Transaction": object(&[
+            ("id", uuid()),
+            ("from", uuid()),
+            ("to", uuid()),
+            ("amount", number()),
+            ("timestamp", integer()),
+        ]),
+        "BlockHeader": object(&[
+            ("height", integer()),
+            ("parent_hash", string()),
+            ("proposer_id", string()),
+            ("tx_count", integer()),
+            ("state_root", string()),
+        ]),
+        "Block": object(&[
+            ("index", integer()),
+            ("header", schema_ref("BlockHeader")),
+            ("timestamp", integer()),
+            ("transactions", array_of("Transaction")),
+            ("hash", string()),
+            ("signature", string()),
+            ("proposer_signature", string()),
+        ]),
+        "BlockHeaderEntry": object(&[
+            ("hash", string()),
+            ("header", schema_ref("BlockHeader")),
+        ]),
+        "CreateTransactionDto": object(&[
+            ("from", uuid()),
+            ("to", uuid()),
+            ("amount", number()),
+        ]),
+        "CreateUserDto": object(&[("balance", number())]),
+        "CreatedUser": object(&[("id", uuid())]),
+        "Balance": object(&[("balance", number())]),
+        "AccountBalance": object(&[("address", uuid()), ("balance", number())]),
+        "BalancesPage": object(&[
+            ("accounts", array_of("AccountBalance")),
+            ("next_cursor", nullable(uuid())),
+        ]),
+        "BlocksPage": object(&[
+            ("blocks", array_of("Block")),
+            ("next_cursor", nullable(integer())),
+        ]),
+        "TransactionStatus": {
+            "type": "object",
+            "required": ["status"],
+            "properties": {
+                "status": { "type": "string", "enum": ["pending", "proposed", "confirmed", "unknown"] },
+                "transaction": schema_ref("Transaction"),
+                "block_height": integer(),
+                "block_hash": string(),
+                "votes": integer(),
+                "quorum": integer(),
+                "position": integer(),
+                "confirmations": integer(),
+                "id": uuid(),
+            }
+        },
+        "AccountTransfer": object(&[
+            ("seq", integer()),
+            ("transaction_id", uuid()),
+            ("block_height", integer()),
+            ("block_hash", string()),
+            ("direction", json!({ "type": "string", "enum": ["incoming", "outgoing"] })),
+            ("counterparty", uuid()),
+            ("amount", number()),
+            ("balance_after", number()),
+            ("timestamp", integer()),
+        ]),
+        "AccountHistory": object(&[
+            ("address", uuid()),
+            ("transfers", array_of("AccountTransfer")),
+            ("next_cursor", nullable(integer())),
+        ]),
+        "SnapshotManifest": object(&[
+            ("height", integer()),
+            ("block_hash", string()),
+            ("state_root", string()),
+            ("total_accounts", integer()),
+            ("chunk_size", integer()),
+            ("chunk_count", integer()),
+        ]),
+        "SnapshotChunk": object(&[
+            ("height", integer()),
+            ("index", integer()),
+            (
+                "accounts",
+                json!({
+                    "type": "array",
+                    "description": "Pairs of [address, balance]",
+                    "items": { "type": "array", "minItems": 2, "maxItems": 2, "items": {} }
+                }),
+            ),
+        ]),
+        "Handshake": object(&[
+            ("node_id", string()),
+            ("chain_id", string()),
+            ("genesis_hash", string()),
+            ("protocol_version", integer()),
+            ("best_height", integer()),
+            ("identity_key", string()),
+            ("validator_pubkey", string()),
+            ("listen_address", nullable(string())),
+            ("p2p_port", nullable(integer())),
+        ]),
+        "Peer": object(&[
+            ("node_id", string()),
+            ("address", nullable(string())),
+            ("chain_id", string()),
+            ("genesis_hash", string()),
+            ("protocol_version", integer()),
+            ("best_height", integer()),
+            ("identity_key", string()),
+            ("validator_pubkey", string()),
+            ("inbound", boolean()),
+            ("outbound", boolean()),
+            ("p2p_connected", boolean()),
+            ("last_handshake_at", integer()),
+        ]),
+        "PeerScore": object(&[
+            ("node_id", string()),
+            ("score", json!({ "type": "integer", "maximum": 0 })),
+            ("offences", integer()),
+            ("last_offence", nullable(string())),
+            ("banned_until", nullable(integer())),
+            ("ban_reason", nullable(string())),
+            ("connected", boolean()),
+        ]),
+        "BanPeer": {
+            "type": "object",
+            "properties": {
+                "duration_secs": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "description": "Defaults to 600"
+                },
+                "reason": string(),
+            }
+        },
+        "PeerStatus": object(&[
+            ("address", string()),
+            ("node_id", nullable(string())),
+            ("height", nullable(integer())),
+            ("reachable", boolean()),
+            ("updated_at", nullable(integer())),
+        ]),
+        "NodeStatus": object(&[
+            ("node_id", string()),
+            ("chain_id", string()),
+            ("height", nullable(integer())),
+            ("hash", nullable(string())),
+            ("finalized_height", nullable(integer())),
+            ("pending_blocks", integer()),
+            ("slot", integer()),
+            ("leader", nullable(string())),
+            ("peer_count", integer()),
+            ("peers", array_of("PeerStatus")),
+            ("mempool_size", integer()),
+            ("syncing", boolean()),
+            ("target_height", nullable(integer())),
+            ("last_synced_at", nullable(integer())),
+        ]),
+        "ErrorResponse": object(&[(
+            "error",
+            object(&[("code", string()), ("message", string())]),
+        )]),
+        "RpcRequest": {
+            "type": "object",
+            "required": ["jsonrpc", "method"],
+            "properties": {
+                "jsonrpc": { "type": "string", "enum": ["2.0"] },
+                "method": string(),
+                "params": { "type": "object" },
+                "id": {},
+            }
+        },
+    })
+}
+
+fn operation(
+    summary: &str,
+    parameters: &[Value],
+    request: Option<&str>,
+    responses: Value,
+) -> Value {
+    let mut operation = json!({ "summary": summary, "responses": responses });
+    operation["responses"]["default"] = json!({ "$ref": "#/components/responses/Error" });
+    if !parameters.is_empty() {
+        operation["parameters"] = Value::Array(parameters.to_vec());
+    }
+    if let Some(schema) = request {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": { "application/json": { "schema": schema_ref(schema) } }
+        });
+    }
+    operation
+}
+
+// Адмінські ендпоінти вимагають `Authorization: Bearer <admin token>`
+fn admin(mut operation: Value) -> Value {
+    operation["security"] = json!([{ "adminToken": [] }]);
+    operation
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "200": {
+            "description": description,
+            "content": { "application/json": { "schema": schema } }
+        }
+    })
+}
+
+fn text_response(description: &str) -> Value {
+    json!({
+        "200": {
+            "description": description,
+            "content": { "text/plain": { "schema": string() } }
+        }
+    })
+}
+
+fn path_param(name: &str, schema: Value) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "schema": schema })
+}
+
+fn query_param(name: &str, schema: Value, description: &str) -> Value {
+    json!({ "name": name, "in": "query", "description": description, "schema": schema })
+}
+
+fn limit_param() -> Value {
+    query_param(
+        "limit",
+        integer(),
+        &format!(
+            "Page size, {} by default and at most {}",
+            DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE
+        ),
+    )
+}
+
+fn order_param() -> Value {
+    query_param(
+        "order",
+        json!({ "type": "string", "enum": ["asc", "desc"] }),
+        "Sort order, asc by default",
+    )
+}
+
+fn object(properties: &[(&str, Value)]) -> Value {
+    let required: Vec<&str> = properties.iter().map(|(name, _)| *name).collect();
+    let properties: Map<String, Value> = properties
+        .iter()
+        .map(|(name, schema)| (name.to_string(), schema.clone()))
+        .collect();
+    json!({ "type": "object", "required": required, "properties": properties })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn array_of(name: &str) -> Value {
+    json!({ "type": "array", "items": schema_ref(name) })
+}
+
+fn nullable(mut schema: Value) -> Value {
+    schema["nullable"] = Value::Bool(true);
+    schema
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+fn number() -> Value {
+    json!({ "type": "number" })
+}
+
+fn uuid() -> Value {
+    json!({ "type": "string", "format": "uuid" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::dtos::{
+        AccountBalanceDto, AccountHistoryDto, ApiErrorDto, BalanceDto, BalancesPageDto,
+        BlocksPageDto, CreateTransactionDto, CreateUserDto, CreatedUserDto, ErrorResponseDto,
+        NodeStatusDto, PeerDto, PeerScoreDto, PeerStatusDto, TransactionStatusDto,
+    };
+    use crate::api::error::ErrorCode;
+    use crate::api::routes::endpoints;
+    use crate::domain::account_transfer::{AccountTransfer, TransferDirection};
+    use crate::domain::block_locator::BlockHeaderEntry;
+    use crate::domain::handshake::{Handshake, PROTOCOL_VERSION};
+    use crate::domain::state_snapshot::{SnapshotChunk, SnapshotManifest};
+    use crate::domain::{block::Block, transaction::Transaction};
+    use crate::infrastructure::{
+        in_memory_blockchain_repository::InMemoryBlockchainRepository,
+        in_memory_user_state_repository::InMemoryUserStateRepository,
+        mempool_repository::InMemoryMempoolRepository,
+    };
+    use serde::Serialize;
+    use uuid::Uuid;
+
+    fn registered_routes() -> Vec<(Method, &'static str)> {
+        endpoints::<
+            InMemoryBlockchainRepository,
+            InMemoryMempoolRepository,
+            InMemoryUserStateRepository,
+        >()
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let routes = registered_routes();
+        let document = document(&routes);
+        assert!(!routes.is_empty());
+        for (method, path) in routes {
+            assert!(
+                document["paths"][path][method.as_str().to_lowercase()].is_object(),
+                "{} {} is registered in routes.rs but missing from the OpenAPI document",
+                method,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn every_described_operation_is_registered() {
+        let routes = registered_routes();
+        for (path, described) in operations().as_object().unwrap() {
+            for method in described.as_object().unwrap().keys() {
+                assert!(
+                    routes
+                        .iter()
+                        .any(|(m, p)| m.as_str().eq_ignore_ascii_case(method) && p == path),
+                    "{} {} is described but not registered",
+                    method.to_uppercase(),
+                    path
+                );
+            }
+        }
+    }
+
+    // Перевіряє значення проти схеми: типи, обов'язкові поля й відсутність неописаних полів
+    fn check(document: &Value, schema: &Value, value: &Value, at: &str) {
+        if let Some(target) = schema["$ref"].as_str() {
+            let resolved = document
+                .pointer(target.trim_start_matches('#'))
+                .unwrap_or_else(|| panic!("{}: dangling reference {}", at, target));
+            return check(document, resolved, value, at);
+        }
+        if let Some(variants) = schema["oneOf"].as_array() {
+            let matching = variants
+                .iter()
+                .filter(|variant| {
+                    std::panic::catch_unwind(|| check(document, variant, value, at)).is_ok()
+                })
+                .count();
+            assert_eq!(
+                matching, 1,
+                "{}: {} matches {} variants",
+                at, value, matching
+            );
+            return;
+        }
+        if value.is_null() {
+            assert!(
+                schema["nullable"] == true || schema.get("type").is_none(),
+                "{}: null is not allowed",
+                at
+            );
+            return;
+        }
+        if let Some(allowed) = schema["enum"].as_array() {
+            assert!(
+                allowed.contains(value),
+                "{}: {} is not in {:?}",
+                at,
+                value,
+                allowed
+            );
+        }
+        match schema["type"].as_str() {
+            Some("object") => {
+                let object = value
+                    .as_object()
+                    .unwrap_or_else(|| panic!("{}: expected an object, got {}", at, value));
+                for field in schema["required"].as_array().into_iter().flatten() {
+                    let field = field.as_str().unwrap();
+                    assert!(object.contains_key(field), "{}: missing {}", at, field);
+                }
+                if let Some(properties) = schema["properties"].as_object() {
+                    for (field, field_value) in object {
+                        let property = properties
+                            .get(field)
+                            .unwrap_or_else(|| panic!("{}: undocumented field {}", at, field));
+                        check(
+                            document,
+                            property,
+                            field_value,
+                            &format!("{}.{}", at, field),
+                        );
+                    }
+                }
+                if let Some(values) = schema.get("additionalProperties") {
+                    for (field, field_value) in object {
+                        check(document, values, field_value, &format!("{}.{}", at, field));
+                    }
+                }
+            }
+            Some("array") => {
+                let items = value
+                    .as_array()
+                    .unwrap_or_else(|| panic!("{}: expected an array, got {}", at, value));
+                for (index, item) in items.iter().enumerate() {
+                    check(
+                        document,
+                        &schema["items"],
+                        item,
+                        &format!("{}[{}]", at, index),
+                    );
+                }
+            }
+            Some("string") => assert!(value.is_string(), "{}: expected a string", at),
+            Some("integer") => assert!(value.is_u64(), "{}: expected an integer", at),
+            Some("number") => assert!(value.is_number(), "{}: expected a number", at),
+            Some("boolean") => assert!(value.is_boolean(), "{}: expected a boolean", at),
+            _ => {}
+        }
+    }
+
+    fn assert_schema<T: Serialize>(document: &Value, name: &str, value: T) {
+        let value = serde_json::to_value(value).unwrap();
+        check(document, &schema_ref(name), &value, name);
+    }
+
+    #[test]
+    fn response_schemas_match_serialized_types() {
+        let document = document(&registered_routes());
+        let transaction = Transaction::new(Uuid::new_v4(), Uuid::new_v4(), 1.0);
+        let block = Block::new(
+            1,
+            0,
+            "v1".to_string(),
+            1,
+            vec![transaction.clone()],
+            "0".to_string(),
+            String::new(),
+            "key".to_string(),
+        );
+        let handshake = Handshake {
+            node_id: "v1".to_string(),
+            chain_id: "devnet".to_string(),
+            genesis_hash: block.hash.clone(),
+            protocol_version: PROTOCOL_VERSION,
+            best_height: 1,
+            identity_key: "ab".repeat(32),
+            validator_pubkey: Handshake::validator_key("key", "v1", &"ab".repeat(32)),
+            listen_address: Some("v1@localhost:3001".parse().unwrap()),
+            p2p_port: Some(4001),
+        };
+        let address = Uuid::new_v4();
+
+        assert_schema(&document, "Transaction", &transaction);
+        assert_schema(&document, "Block", &block);
+        assert_schema(
+            &document,
+            "BlockHeaderEntry",
+            BlockHeaderEntry::from(&block),
+        );
+        assert_schema(&document, "Handshake", &handshake);
+        assert_schema(
+            &document,
+            "BlocksPage",
+            BlocksPageDto {
+                blocks: vec![block.clone()],
+                next_cursor: Some(1),
+            },
+        );
+        assert_schema(
+            &document,
+            "BalancesPage",
+            BalancesPageDto {
+                accounts: vec![AccountBalanceDto {
+                    address,
+                    balance: 2.5,
+                }],
+                next_cursor: None,
+            },
+        );
+        assert_schema(
+            &document,
+            "AccountHistory",
+            AccountHistoryDto {
+                address,
+                transfers: vec![AccountTransfer {
+                    seq: 0,
+                    transaction_id: transaction.id,
+                    block_height: 1,
+                    block_hash: block.hash.clone(),
+                    direction: TransferDirection::Incoming,
+                    counterparty: transaction.from,
+                    amount: 1.0,
+                    balance_after: 1.0,
+                    timestamp: 0,
+                }],
+                next_cursor: Some(1),
+            },
+        );
+        for status in [
+            TransactionStatusDto::Pending {
+                transaction: transaction.clone(),
+            },
+            TransactionStatusDto::Proposed {
+                transaction: transaction.clone(),
+                block_height: 1,
+                block_hash: block.hash.clone(),
+                votes: 1,
+                quorum: 2,
+            },
+            TransactionStatusDto::Confirmed {
+                transaction: transaction.clone(),
+                block_height: 1,
+                block_hash: block.hash.clone(),
+                position: 0,
+                confirmations: 1,
+            },
+            TransactionStatusDto::Unknown { id: transaction.id },
+        ] {
+            assert_schema(&document, "TransactionStatus", status);
+        }
+        assert_schema(
+            &document,
+            "NodeStatus",
+            NodeStatusDto {
+                node_id: "v1".to_string(),
+                chain_id: "devnet".to_string(),
+                height: Some(1),
+                hash: Some(block.hash.clone()),
+                finalized_height: None,
+                pending_blocks: 0,
+                slot: 7,
+                leader: Some("v1".to_string()),
+                peer_count: 1,
+                peers: vec![PeerStatusDto {
+                    address: "v2@localhost:3002".to_string(),
+                    node_id: Some("v2".to_string()),
+                    height: None,
+                    reachable: true,
+                    updated_at: Some(0),
+                }],
+                mempool_size: 0,
+                syncing: false,
+                target_height: None,
+                last_synced_at: None,
+            },
+        );
+        assert_schema(
+            &document,
+            "Peer",
+            PeerDto {
+                node_id: "v2".to_string(),
+                address: None,
+                chain_id: "devnet".to_string(),
+                genesis_hash: block.hash.clone(),
+                protocol_version: PROTOCOL_VERSION,
+                best_height: 1,
+                identity_key: "ab".repeat(32),
+                validator_pubkey: String::new(),
+                inbound: true,
+                outbound: false,
+                p2p_connected: true,
+                last_handshake_at: 0,
+            },
+        );
+        assert_schema(
+            &document,
+            "PeerScore",
+            PeerScoreDto {
+                node_id: "v2".to_string(),
+                score: 0,
+                offences: 1,
+                last_offence: Some("invalid_vote".to_string()),
+                banned_until: None,
+                ban_reason: None,
+                connected: true,
+            },
+        );
+        assert_schema(
+            &document,
+            "SnapshotManifest",
+            SnapshotManifest {
+                height: 1,
+                block_hash: block.hash.clone(),
+                state_root: String::new(),
+                total_accounts: 1,
+                chunk_size: 1,
+                chunk_count: 1,
+            },
+        );
+        assert_schema(
+            &document,
+            "SnapshotChunk",
+            SnapshotChunk {
+                height: 1,
+                index: 0,
+                accounts: vec![(address, 1.0)],
+            },
+        );
+        assert_schema(&document, "CreatedUser", CreatedUserDto { id: address });
+        assert_schema(&document, "Balance", BalanceDto { balance: 1.0 });
+        assert_schema(
+            &document,
+            "ErrorResponse",
+            ErrorResponseDto {
+                error: ApiErrorDto {
+                    code: ErrorCode::BlockNotFound,
+                    message: "Block not found".to_string(),
+                },
+            },
+        );
+    }
+
+    #[test]
+    fn schemas_reject_undocumented_fields() {
+        let document = document(&registered_routes());
+        let mut value = serde_json::to_value(BalanceDto { balance: 1.0 }).unwrap();
+        value["extra"] = json!(1);
+        let result = std::panic::catch_unwind(|| {
+            check(&document, &schema_ref("Balance"), &value, "Balance")
+        });
+        assert!(result.is_err());
+    }
+
+    // Тіло, зібране лише з обов'язкових полів схеми, має прийматися обробником
+    fn sample_body(document: &Value, name: &str) -> Value {
+        let schema = &document["components"]["schemas"][name];
+        let body: Map<String, Value> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| {
+                let field = field.as_str().unwrap();
+                let property = &schema["properties"][field];
+                let value = match (property["type"].as_str(), property["format"].as_str()) {
+                    (Some("string"), Some("uuid")) => json!(Uuid::new_v4()),
+                    (Some("number"), _) => json!(1.5),
+                    other => panic!("no sample for {:?}", other),
+                };
+                (field.to_string(), value)
+            })
+            .collect();
+        Value::Object(body)
+    }
+
+    #[test]
+    fn request_schemas_deserialize() {
+        let document = document(&registered_routes());
+        serde_json::from_value::<CreateTransactionDto>(sample_body(
+            &document,
+            "CreateTransactionDto",
+        ))
+        .unwrap();
+        serde_json::from_value::<CreateUserDto>(sample_body(&document, "CreateUserDto")).unwrap();
+    }
+
+    #[test]
+    fn references_resolve() {
+        let document = document(&registered_routes());
+        let text = document.to_string();
+        for reference in text.split("\"$ref\":\"").skip(1) {
+            let target = reference.split('"').next().unwrap();
+            let pointer = target.trim_start_matches('#');
+            assert!(
+                document.pointer(pointer).is_some(),
+                "dangling reference {}",
+                target
+            );
+        }
+    }
+}
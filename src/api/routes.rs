@@ -0,0 +1,124 @@
+use axum::{
+    Router,
+    handler::Handler,
+    http::Method,
+    routing::{MethodFilter, MethodRouter, on},
+};
+
+use crate::api::handlers::{
+    ban_peer_handler, create_transaction_handler, create_user_handler, events_handler,
+    get_account_history_handler, get_admin_peers_handler, get_all_transactions_handler,
+    get_balance_handler, get_balances_handler, get_block_by_hash_handler,
+    get_block_by_height_handler, get_blocks_handler, get_headers_handler,
+    get_peer_addresses_handler, get_peers_handler, get_snapshot_chunk_handler,
+    get_snapshot_manifest_handler, get_status_handler, get_transaction_handler, handshake_handler,
+    metrics_handler, openapi_handler, rpc_handler, unban_peer_handler,
+};
+use crate::domain::{
+    app_state::AppState, blockchain_repository::BlockchainRepository,
+    mempool_repository::MempoolRepository, user_state_repository::UserStateRepository,
+};
+
+/// One REST endpoint: the HTTP method, the axum path and its handler.
+pub struct Route<B, M, U>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    pub method: Method,
+    pub path: &'static str,
+    pub handler: MethodRouter<AppState<B, M, U>>,
+}
+
+// Єдиний список маршрутів REST API: з нього будуються і роутер у server.rs,
+// і розділ paths документа OpenAPI
+pub fn routes<B, M, U>() -> Vec<Route<B, M, U>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    vec![
+        route(Method::GET, "/", || async { "Hello, World!" }),
+        route(Method::GET, "/openapi.json", openapi_handler::<B, M, U>),
+        route(Method::GET, "/status", get_status_handler),
+        route(Method::GET, "/metrics", metrics_handler),
+        route(Method::GET, "/blocks", get_blocks_handler),
+        route(Method::GET, "/blocks/{height}", get_block_by_height_handler),
+        route(Method::GET, "/block/hash/{hash}", get_block_by_hash_handler),
+        route(Method::GET, "/headers", get_headers_handler),
+        route(Method::POST, "/user", create_user_handler),
+        route(Method::POST, "/transactions", create_transaction_handler),
+        route(Method::GET, "/transactions", get_all_transactions_handler),
+        route(Method::GET, "/transactions/{id}", get_transaction_handler),
+        route(Method::GET, "/balances", get_balances_handler),
+        route(
+            Method::GET,
+            "/accounts/{address}/transactions",
+            get_account_history_handler,
+        ),
+        route(Method::GET, "/balance/{address}", get_balance_handler),
+        route(Method::POST, "/handshake", handshake_handler),
+        route(Method::GET, "/peers", get_peers_handler),
+        route(Method::GET, "/peers/addresses", get_peer_addresses_handler),
+        route(Method::GET, "/admin/peers", get_admin_peers_handler),
+        route(Method::POST, "/admin/peers/{node_id}/ban", ban_peer_handler),
+        route(
+            Method::DELETE,
+            "/admin/peers/{node_id}/ban",
+            unban_peer_handler,
+        ),
+        route(Method::GET, "/snapshot", get_snapshot_manifest_handler),
+        route(
+            Method::GET,
+            "/snapshot/{height}/chunks/{index}",
+            get_snapshot_chunk_handler,
+        ),
+        route(Method::GET, "/events", events_handler),
+        route(Method::POST, "/rpc", rpc_handler),
+    ]
+}
+
+// Пари (метод, шлях) для документа OpenAPI
+pub fn endpoints<B, M, U>() -> Vec<(Method, &'static str)>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    routes::<B, M, U>()
+        .into_iter()
+        .map(|route| (route.method, route.path))
+        .collect()
+}
+
+// Маршрути з одним шляхом axum зливає в один MethodRouter
+pub fn router<B, M, U>() -> Router<AppState<B, M, U>>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    routes()
+        .into_iter()
+        .fold(Router::new().without_v07_checks(), |router, route| {
+            router.route(route.path, route.handler)
+        })
+}
+
+fn route<B, M, U, H, T>(method: Method, path: &'static str, handler: H) -> Route<B, M, U>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+    H: Handler<T, AppState<B, M, U>>,
+    T: 'static,
+{
+    let filter = MethodFilter::try_from(method.clone()).expect("route method is a standard one");
+    Route {
+        method,
+        path,
+        handler: on(filter, handler),
+    }
+}
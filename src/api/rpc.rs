@@ -0,0 +1,331 @@
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+
+use crate::api::dtos::{
+    AccountBalanceDto, AddressParams, CreateTransactionDto, GetBlockParams, RpcErrorDto, RpcReply,
+    RpcRequest, RpcResponse, SyncStatusDto, TransactionIdParams, ValidatorsDto,
+};
+use crate::api::handlers::transaction_status;
+use crate::blockchain::use_cases::peer_transport::broadcast;
+use crate::blockchain::use_cases::submit_transaction::submit_transaction;
+use crate::domain::{
+    app_state::AppState, blockchain_repository::BlockchainRepository,
+    mempool_repository::MempoolRepository, metrics, peer_message::PeerMessage,
+    user_state_repository::UserStateRepository,
+};
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+// Коди від -32000 до -32099 зарезервовані специфікацією для помилок сервера
+pub const TRANSACTION_REJECTED: i64 = -32000;
+
+const JSONRPC_VERSION: &str = "2.0";
+// Більший пакет відхиляється цілком, щоб один запит не тримав вузол надто довго
+pub const MAX_BATCH_SIZE: usize = 100;
+
+// Розбирає тіло запиту /rpc: один виклик або пакет.
+// None означає, що відповідати не треба (лише сповіщення).
+pub async fn handle_payload<B, M, U>(app_state: &AppState<B, M, U>, body: &str) -> Option<RpcReply>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let payload: Value = match serde_json::from_str(body) {
+        Ok(payload) => payload,
+        Err(_) => {
+            return Some(RpcReply::Single(failure(
+                Value::Null,
+                error(PARSE_ERROR, "Parse error"),
+            )));
+        }
+    };
+
+    match payload {
+        Value::Array(calls) if calls.is_empty() => Some(RpcReply::Single(failure(
+            Value::Null,
+            error(INVALID_REQUEST, "Invalid Request"),
+        ))),
+        Value::Array(calls) if calls.len() > MAX_BATCH_SIZE => Some(RpcReply::Single(failure(
+            Value::Null,
+            error(
+                INVALID_REQUEST,
+                &format!("Batch exceeds {} calls", MAX_BATCH_SIZE),
+            ),
+        ))),
+        Value::Array(calls) => {
+            let mut responses = Vec::with_capacity(calls.len());
+            for call in calls {
+                if let Some(response) = handle_call(app_state, call).await {
+                    responses.push(response);
+                }
+            }
+            (!responses.is_empty()).then_some(RpcReply::Batch(responses))
+        }
+        call => handle_call(app_state, call).await.map(RpcReply::Single),
+    }
+}
+
+async fn handle_call<B, M, U>(app_state: &AppState<B, M, U>, call: Value) -> Option<RpcResponse>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Виклик без поля id є сповіщенням: виконуємо, але не відповідаємо
+    let id = call.get("id").cloned();
+    let valid_id = matches!(
+        id,
+        None | Some(Value::Null | Value::Number(_) | Value::String(_))
+    );
+    let request = match serde_json::from_value::<RpcRequest>(call) {
+        Ok(request)
+            if valid_id
+                && request.jsonrpc == JSONRPC_VERSION
+                && matches!(
+                    request.params,
+                    None | Some(Value::Object(_) | Value::Array(_))
+                ) =>
+        {
+            request
+        }
+        _ => {
+            return Some(failure(
+                id.filter(|_| valid_id).unwrap_or(Value::Null),
+                error(INVALID_REQUEST, "Invalid Request"),
+            ));
+        }
+    };
+
+    let params = request.params.unwrap_or(Value::Object(Map::new()));
+    let result = dispatch(app_state, &request.method, params).await;
+    let id = id?;
+    Some(match result {
+        Ok(result) => RpcResponse::Success {
+            jsonrpc: JSONRPC_VERSION,
+            result,
+            id,
+        },
+        Err(error) => failure(id, error),
+    })
+}
+
+async fn dispatch<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcErrorDto>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    match method {
+        "getBlock" => {
+            let params: GetBlockParams = parse_params(params)?;
+            let repo = app_state.blockchain_repo.lock().await;
+            let block = match (params.height, params.hash) {
+                (Some(height), None) => repo.get_block_by_height(height).await,
+                (None, Some(hash)) => repo.get_block_by_hash(&hash).await,
+                _ => {
+                    return Err(error(
+                        INVALID_PARAMS,
+                        "Expected exactly one of height or hash",
+                    ));
+                }
+            };
+            to_result(block)
+        }
+        "getBalance" => {
+            let params: AddressParams = parse_params(params)?;
+            let balance = app_state
+                .user_state_repo
+                .lock()
+                .await
+                .get_balance(&params.address);
+            to_result(AccountBalanceDto {
+                address: params.address,
+                balance,
+            })
+        }
+        "sendTransaction" => {
+            let params: CreateTransactionDto = parse_params(params)?;
+            let transaction = submit_transaction(
+                app_state.user_state_repo.clone(),
+                app_state.mempool_repo.clone(),
+                &app_state.events,
+                params.from,
+                params.to,
+                params.amount,
+            )
+            .await
+            .map_err(|rejection| {
+                app_state
+                    .metrics
+                    .inc(metrics::MEMPOOL_REJECTIONS, &[("reason", rejection.kind())]);
+                error(TRANSACTION_REJECTED, &rejection.to_string())
+            })?;
+            broadcast(app_state, PeerMessage::TxAnnounce(transaction.clone())).await;
+            to_result(transaction)
+        }
+        "getTransaction" => {
+            let params: TransactionIdParams = parse_params(params)?;
+            to_result(transaction_status(app_state, params.id).await)
+        }
+        "getValidators" => {
+            let node = app_state.node.lock().await;
+            to_result(ValidatorsDto {
+                validators: node.validator_ids.clone(),
+                quorum: node.quorum(),
+            })
+        }
+        "getSyncStatus" => {
+            let current_height = app_state.blockchain_repo.lock().await.height().await;
+            let status = app_state.sync_status.lock().await.clone();
+            to_result(SyncStatusDto {
+                syncing: status.syncing,
+                current_height,
+                target_height: status.target_height,
+                last_synced_at: status.last_synced_at,
+            })
+        }
+        _ => Err(error(METHOD_NOT_FOUND, "Method not found")),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcErrorDto> {
+    serde_json::from_value(params)
+        .map_err(|e| error(INVALID_PARAMS, &format!("Invalid params: {}", e)))
+}
+
+fn to_result<T: Serialize>(value: T) -> Result<Value, RpcErrorDto> {
+    serde_json::to_value(value).map_err(|e| error(INTERNAL_ERROR, &e.to_string()))
+}
+
+fn error(code: i64, message: &str) -> RpcErrorDto {
+    RpcErrorDto {
+        code,
+        message: message.to_string(),
+    }
+}
+
+fn failure(id: Value, error: RpcErrorDto) -> RpcResponse {
+    RpcResponse::Failure {
+        jsonrpc: JSONRPC_VERSION,
+        error,
+        id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_BATCH_SIZE, handle_payload};
+    use crate::domain::app_state::testing::{FAUCET, TestAppState, app_state};
+    use crate::domain::config::Config;
+    use serde_json::{Value, json};
+
+    async fn call(app_state: &TestAppState, body: &str) -> Option<Value> {
+        handle_payload(app_state, body)
+            .await
+            .map(|reply| serde_json::to_value(reply).unwrap())
+    }
+
+    fn error_code(response: &Value) -> i64 {
+        response["error"]["code"].as_i64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn reports_standard_error_codes() {
+        let app_state = app_state(Config::for_tests("v1", &["v1"])).await;
+
+        let parse = call(&app_state, "{not json").await.unwrap();
+        assert_eq!(error_code(&parse), -32700);
+        assert_eq!(parse["id"], Value::Null);
+
+        let invalid = call(
+            &app_state,
+            r#"{"jsonrpc":"1.0","method":"getValidators","id":1}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(error_code(&invalid), -32600);
+        assert_eq!(invalid["id"], 1);
+
+        let unknown = call(&app_state, r#"{"jsonrpc":"2.0","method":"mine","id":2}"#)
+            .await
+            .unwrap();
+        assert_eq!(error_code(&unknown), -32601);
+
+        let bad_params = call(
+            &app_state,
+            r#"{"jsonrpc":"2.0","method":"getBalance","params":{"address":"nope"},"id":3}"#,
+        )
+        .await
+        .unwrap();
+        assert_eq!(error_code(&bad_params), -32602);
+        assert_eq!(bad_params["id"], 3);
+    }
+
+    #[tokio::test]
+    async fn answers_a_batch_in_order_and_skips_notifications() {
+        let app_state = app_state(Config::for_tests("v1", &["v1"])).await;
+        let body = json!([
+            {"jsonrpc": "2.0", "method": "getValidators", "id": "a"},
+            {"jsonrpc": "2.0", "method": "getValidators"},
+            {"jsonrpc": "2.0", "method": "getBalance", "params": {"address": FAUCET}, "id": 7},
+            {"jsonrpc": "2.0", "method": "mine", "id": 8},
+        ]);
+
+        let reply = call(&app_state, &body.to_string()).await.unwrap();
+
+        let responses = reply.as_array().unwrap();
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[0]["id"], "a");
+        assert_eq!(responses[0]["result"]["quorum"], 1);
+        assert_eq!(responses[1]["id"], 7);
+        assert_eq!(responses[1]["result"]["address"], FAUCET.to_string());
+        assert_eq!(responses[2]["id"], 8);
+        assert_eq!(error_code(&responses[2]), -32601);
+    }
+
+    #[tokio::test]
+    async fn notifications_get_no_reply() {
+        let app_state = app_state(Config::for_tests("v1", &["v1"])).await;
+
+        let single = call(&app_state, r#"{"jsonrpc":"2.0","method":"getValidators"}"#).await;
+        let batch = call(
+            &app_state,
+            r#"[{"jsonrpc":"2.0","method":"getValidators"},{"jsonrpc":"2.0","method":"mine"}]"#,
+        )
+        .await;
+
+        assert!(single.is_none());
+        assert!(batch.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_and_oversized_batches() {
+        let app_state = app_state(Config::for_tests("v1", &["v1"])).await;
+
+        let empty = call(&app_state, "[]").await.unwrap();
+        assert_eq!(error_code(&empty), -32600);
+
+        let calls =
+            vec![json!({"jsonrpc": "2.0", "method": "getValidators", "id": 1}); MAX_BATCH_SIZE + 1];
+        let oversized = call(&app_state, &Value::Array(calls).to_string())
+            .await
+            .unwrap();
+        assert!(oversized.is_object());
+        assert_eq!(error_code(&oversized), -32600);
+
+        let calls =
+            vec![json!({"jsonrpc": "2.0", "method": "getValidators", "id": 1}); MAX_BATCH_SIZE];
+        let full = call(&app_state, &Value::Array(calls).to_string())
+            .await
+            .unwrap();
+        assert_eq!(full.as_array().unwrap().len(), MAX_BATCH_SIZE);
+    }
+}
@@ -1,99 +1,252 @@
 use crate::api::args::Args;
-use crate::api::handlers::{
-    accept_block_handler, accept_vote_handler, create_transaction_handler, create_user_handler,
-    get_all_balances_handler, get_all_blocks_handler, get_all_transactions_handler,
-    get_balance_handler,
-};
+use crate::api::handlers::fallback_handler;
+use crate::api::middleware::{trace_request, track_request_duration};
+use crate::api::routes::router;
 use crate::blockchain::use_cases::create_genesis_block::create_genesis_block;
+use crate::blockchain::use_cases::discover_peers::{discover_peers, peer_discovery_task};
+use crate::blockchain::use_cases::fast_sync::fast_sync;
+use crate::blockchain::use_cases::peer_transport::p2p_listener_task;
 use crate::blockchain::use_cases::pos_consensus_loop::pos_consensus_loop;
+use crate::blockchain::use_cases::restore_user_state::restore_user_state;
+use crate::blockchain::use_cases::revalidate_mempool::revalidate_mempool;
+use crate::blockchain::use_cases::sync_chain_task::sync_chain_task;
+use crate::domain::address_book::{AddressBook, AddressBookStore, AddressSource};
 use crate::domain::app_state::AppState;
 use crate::domain::blockchain_repository::BlockchainRepository;
+use crate::domain::config::Config;
+use crate::domain::mempool_repository::MempoolRepository;
 use crate::domain::node::Node;
+use crate::domain::node_identity::NodeIdentity;
 use crate::domain::user_state_repository::UserStateRepository;
 use crate::infrastructure::{
+    file_address_book_store::FileAddressBookStore,
+    file_blockchain_repository::FileBlockchainRepository,
+    file_mempool_repository::FileMempoolRepository,
+    file_user_state_repository::FileUserStateRepository, identity_file,
     in_memory_blockchain_repository::InMemoryBlockchainRepository,
-    in_memory_user_state_repository::InMemoryUserStateRepository,
+    in_memory_user_state_repository::InMemoryUserStateRepository, log_subscriber,
     mempool_repository::InMemoryMempoolRepository,
 };
-use axum::{
-    Router,
-    routing::{get, post},
-};
+use axum::middleware;
 use clap::Parser;
 use reqwest::Client;
-use std::collections::HashMap;
-use std::env;
+use std::process;
 use std::sync::Arc;
 use tokio::signal;
 use tokio::sync::Mutex;
+use tracing::{error, info, warn};
 
 pub async fn app() {
     let args = Args::parse();
-    let http_client = Client::new();
-    println!("  -> Id: {}", args.id);
-    println!("  -> Port: {}", args.port);
-    println!("  -> Peers: {:?}", args.peers);
-    let validator_ids: Vec<String> = vec!["v1".to_string(), "v2".to_string(), "v3".to_string()];
-    let node = Node::new(args.id, args.peers, validator_ids);
-    let shared_key = env::var("SHARED_KEY").expect("SHARED_KEY");
-    let blockchain_repo = InMemoryBlockchainRepository::new();
-    let mempool_repo = InMemoryMempoolRepository::new();
-    let user_state_repo = InMemoryUserStateRepository::new();
+    if args.print_identity_key {
+        print_identity_key(args);
+    }
+    let config = match args.into_config() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            process::exit(2);
+        }
+    };
+    log_subscriber::install(config.log.level, config.log.format);
+    info!(
+        node_id = %config.node.id,
+        chain_id = %config.node.chain_id,
+        port = config.node.port,
+        p2p_port = config.node.p2p_port,
+        peers = ?config.node.peers,
+        bootstrap = ?config.node.bootstrap,
+        advertise_address = %config.node.advertise_address,
+        validators = ?config.consensus.validators,
+        slot_duration_secs = config.consensus.slot_duration.as_secs(),
+        "Starting node"
+    );
+
+    match config.storage.chain_path.clone() {
+        Some(path) => {
+            let blockchain_repo =
+                FileBlockchainRepository::open(&path).expect("Failed to open chain file");
+            with_mempool(config, blockchain_repo).await
+        }
+        None => with_mempool(config, InMemoryBlockchainRepository::new()).await,
+    }
+}
+
+// Операторові потрібен відкритий ключ кожного валідатора ще до того, як конфігурація стане повною
+fn print_identity_key(args: Args) -> ! {
+    let path = match args.resolve() {
+        Ok(config) => config.storage.identity_key_path,
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            process::exit(2);
+        }
+    };
+    let Some(path) = path else {
+        eprintln!("Set --identity-key-path or --state-dir to keep the identity key");
+        process::exit(2);
+    };
+    match identity_file::load_or_create(&path) {
+        Ok(identity) => {
+            println!("{}", identity.public_key());
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Failed to load the identity key: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+async fn with_mempool<B>(config: Config, blockchain_repo: B)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+{
+    match config.storage.mempool_path.clone() {
+        Some(path) => {
+            let mempool_repo =
+                FileMempoolRepository::open(&path).expect("Failed to open mempool journal");
+            with_user_state(config, blockchain_repo, mempool_repo).await
+        }
+        None => with_user_state(config, blockchain_repo, InMemoryMempoolRepository::new()).await,
+    }
+}
 
-    let shared_blockchain_repo = Arc::new(Mutex::new(blockchain_repo));
-    let shared_mempool_repo = Arc::new(Mutex::new(mempool_repo));
-    let shared_user_state_repo = Arc::new(Mutex::new(user_state_repo));
-    let shared_node = Arc::new(Mutex::new(node));
+async fn with_user_state<B, M>(config: Config, blockchain_repo: B, mempool_repo: M)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+{
+    let snapshot_interval = config.storage.snapshot_interval;
+    match config.storage.state_dir.clone() {
+        Some(dir) => {
+            let user_state_repo = FileUserStateRepository::open(&dir, snapshot_interval)
+                .expect("Failed to open state directory");
+            run_node(config, blockchain_repo, mempool_repo, user_state_repo).await
+        }
+        None => {
+            let user_state_repo =
+                InMemoryUserStateRepository::with_snapshot_interval(snapshot_interval);
+            run_node(config, blockchain_repo, mempool_repo, user_state_repo).await
+        }
+    }
+}
+
+async fn run_node<B, M, U>(config: Config, blockchain_repo: B, mempool_repo: M, user_state_repo: U)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let http_client = Client::new();
+    // Піри підключаються в discover_peers; до першого проходу список порожній
+    let node = Node::new(
+        config.node.id.clone(),
+        config.node.chain_id.clone(),
+        Vec::new(),
+        config.consensus.validators.clone(),
+    );
+    let (mut address_book, address_store) = match &config.storage.address_book_path {
+        Some(path) => {
+            let (store, book) =
+                FileAddressBookStore::open(path).expect("Failed to open address book");
+            (book, Some(Arc::new(store) as Arc<dyn AddressBookStore>))
+        }
+        None => (AddressBook::default(), None),
+    };
+    for peer in &config.node.peers {
+        address_book.add(peer, AddressSource::Config);
+    }
+    for peer in &config.node.bootstrap {
+        address_book.add(peer, AddressSource::Bootstrap);
+    }
+    if address_book.is_empty() {
+        info!("No known peers, waiting for other nodes to connect");
+    }
+    let identity = match &config.storage.identity_key_path {
+        Some(path) => identity_file::load_or_create(path).expect("Failed to load the identity key"),
+        None => {
+            info!("No identity key file, using a temporary key");
+            NodeIdentity::generate()
+        }
+    };
+    info!(identity_key = %identity.public_key(), "Node identity");
+    // Інакше піри відкидали б кожен голос і handshake цього валідатора
+    if let Some(expected) = config.consensus.validator_keys.get(&config.node.id)
+        && !expected.eq_ignore_ascii_case(&identity.public_key())
+    {
+        error!(
+            expected = %expected,
+            identity_key = %identity.public_key(),
+            "Identity key differs from the one pinned in consensus.validator_keys"
+        );
+        process::exit(2);
+    }
+    let config = Arc::new(config);
 
     let app_state = AppState {
-        blockchain_repo: shared_blockchain_repo,
-        mempool_repo: shared_mempool_repo,
-        user_state_repo: shared_user_state_repo,
-        node: shared_node,
-        shared_key: shared_key,
         http_client: http_client.clone(),
-        vote_counts: Arc::new(Mutex::new(HashMap::new())),
-        pending_blocks: Arc::new(Mutex::new(HashMap::new())),
+        address_book: Arc::new(Mutex::new(address_book)),
+        address_store,
+        ..AppState::new(
+            config.clone(),
+            node,
+            identity,
+            blockchain_repo,
+            mempool_repo,
+            user_state_repo,
+        )
     };
     let consensus_state = app_state.clone();
-    create_genesis_block(app_state.blockchain_repo.clone()).await;
-    println!("[Startup]: Rebuilding state from Genesis Block...");
+    create_genesis_block(app_state.blockchain_repo.clone(), &config).await;
+    info!("Restoring user state");
     {
         let blockchain = app_state.blockchain_repo.lock().await;
         let mut user_state = app_state.user_state_repo.lock().await;
 
         let all_blocks = blockchain.get_all_blocks().await;
-        println!("All blocks: {:?}", all_blocks);
-        user_state.rebuild_from_blocks(&all_blocks).await;
+        if let Err(e) = restore_user_state(&mut *user_state, &all_blocks).await {
+            warn!(reason = %e, "State restored only up to the last valid block");
+        }
+        // Збережений ланцюг вважається остаточним; далі висота росте з кворумами
+        app_state.consensus_status.lock().await.finalized_height =
+            all_blocks.last().map(|b| b.header.height);
     }
-    println!("[Startup]: State rebuilt. Faucet is funded.");
+    info!("User state restored");
+    // Слухаємо P2P до першого проходу пошуку пірів, щоб ті, до кого ми підключимося,
+    // могли одразу підключитися у відповідь
+    let p2p_listener = tokio::net::TcpListener::bind(config.p2p_socket())
+        .await
+        .expect("Failed to bind the P2P port");
+    info!(address = %config.p2p_socket(), "Listening for peers");
+    tokio::spawn(p2p_listener_task(app_state.clone(), p2p_listener));
+    discover_peers(&app_state, 0).await;
+    if config.storage.fast_sync && !fast_sync(app_state.clone()).await {
+        sync_chain_task(app_state.clone()).await;
+    }
+    revalidate_mempool(
+        app_state.blockchain_repo.clone(),
+        app_state.mempool_repo.clone(),
+        app_state.user_state_repo.clone(),
+        &app_state.events,
+    )
+    .await;
+    tokio::spawn(peer_discovery_task(app_state.clone()));
     tokio::spawn(pos_consensus_loop(consensus_state));
-    let app = Router::new()
-        .without_v07_checks()
-        .route("/", get(|| async { "Hello, World!" }))
-        .route("/blocks", get(get_all_blocks_handler))
-        .route("/user", post(create_user_handler))
-        .route(
-            "/transactions",
-            post(create_transaction_handler).get(get_all_transactions_handler),
-        )
-        .route("/block", post(accept_block_handler))
-        .route("/balances", get(get_all_balances_handler))
-        .route("/balance/:address", get(get_balance_handler))
-        .route("/vote", post(accept_vote_handler))
+    let app = router()
+        .route_layer(middleware::from_fn_with_state(
+            app_state.metrics.clone(),
+            track_request_duration,
+        ))
+        .route_layer(middleware::from_fn(trace_request))
+        .fallback(fallback_handler)
         .with_state(app_state.clone());
-    let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", args.port))
+    let listener = tokio::net::TcpListener::bind(config.bind_socket())
         .await
         .unwrap();
-    println!(
-        "Node {} listens on 0.0.0.0:{}",
-        app_state.node.lock().await.id,
-        args.port
-    );
+    info!(address = %config.bind_socket(), "Listening");
     tokio::spawn(async move {
         axum::serve(listener, app).await.unwrap();
     });
-    println!("Click Ctrl+C to stop the node.");
     signal::ctrl_c().await.expect("error waiting for Ctrl+C");
-    println!("...Received Ctrl+C, shutting down...");
+    info!("Received Ctrl+C, shutting down");
 }
@@ -0,0 +1,336 @@
+use crate::blockchain::use_cases::add_block_to_chain::finalize_block;
+use crate::blockchain::use_cases::apply_block_state::apply_block_state;
+use crate::blockchain::use_cases::ban_peers::penalize_peer;
+use crate::blockchain::use_cases::peer_transport::{already_seen, first_seen, publish, relay};
+use crate::blockchain::use_cases::sync_chain_task::sync_chain_task;
+use crate::domain::{
+    app_state::AppState, block::Block, blockchain_repository::BlockchainRepository,
+    chain_event::ChainEvent, error::BlockError, gossip::Gossip,
+    mempool_repository::MempoolRepository, metrics, peer_message::PeerMessage,
+    peer_score::Misbehaviour, user_state_repository::UserStateRepository, vote::Vote,
+};
+use tracing::{debug, error, info, warn};
+
+// Блок лідера слоту, напряму чи через інших пірів: перевіряє його, передає далі,
+// додає до ланцюга й розсилає свій ACK
+pub async fn accept_block<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    sender: &str,
+    gossip: Gossip<Block>,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    debug!(ttl = gossip.ttl, "Block received");
+    let forward = gossip.next_hop();
+    let block = gossip.item;
+    if already_seen(app_state, &block.hash, "block").await {
+        return;
+    }
+
+    let validator_ids = app_state.node.lock().await.validator_ids.clone();
+    let validation = block
+        .validate(
+            &app_state.config.keys.shared_key,
+            &app_state.config.consensus.validator_keys,
+        )
+        .and_then(|()| {
+            if validator_ids.contains(&block.header.proposer_id) {
+                Ok(())
+            } else {
+                Err(BlockError::UnknownProposer {
+                    height: block.header.height,
+                    proposer_id: block.header.proposer_id.clone(),
+                })
+            }
+        });
+    if let Err(e) = validation {
+        warn!(reason = %e, "Block rejected");
+        reject_block(app_state, e.kind());
+        punish_block_sender(app_state, sender, &e).await;
+        return;
+    }
+    // Пересилаються лише перевірені блоки: відправник відповідає за все, що передав.
+    // Хеш запам'ятовується тільки тепер, тож підробка з чужим хешем не закриє дорогу справжньому блоку
+    if !first_seen(app_state, &block.hash, "block").await {
+        return;
+    }
+    if let Some(forward) = forward {
+        relay(app_state, sender, PeerMessage::Block(forward)).await;
+    }
+    app_state.events.publish(ChainEvent::proposed(&block));
+
+    let mut blockchain = app_state.blockchain_repo.lock().await;
+    let Some(last_block) = blockchain.get_last_block().await else {
+        reject_block(app_state, BlockError::EmptyChain.kind());
+        return;
+    };
+
+    if block.header.height > last_block.header.height + 1
+        || (block.header.height == last_block.header.height + 1
+            && block.header.parent_hash != last_block.hash)
+    {
+        warn!(
+            local_height = last_block.header.height,
+            "Block is ahead of the local chain, starting sync"
+        );
+        reject_block(app_state, "fork");
+        tokio::spawn(sync_chain_task(app_state.clone()));
+        return;
+    }
+    if block.header.height <= last_block.header.height {
+        warn!(
+            local_height = last_block.header.height,
+            "Block rejected: it belongs to a shorter chain"
+        );
+        reject_block(app_state, "stale");
+        return;
+    }
+
+    {
+        let mut user_state = app_state.user_state_repo.lock().await;
+        let balances_before = user_state.get_balances().clone();
+        if let Err(e) = apply_block_state(&mut *user_state, &block) {
+            warn!(reason = %e, "Block rejected");
+            reject_block(app_state, e.kind());
+            drop(user_state);
+            drop(blockchain);
+            punish_block_sender(app_state, sender, &e).await;
+            return;
+        }
+        if let Err(e) = blockchain.add_block(block.clone()).await {
+            error!(error = %e, "Failed to store block");
+            user_state.reset_balances(balances_before);
+            reject_block(app_state, "storage");
+            return;
+        }
+        user_state.commit_block(&block);
+    }
+
+    info!("Block accepted");
+    app_state.metrics.inc(metrics::BLOCKS_ACCEPTED, &[]);
+
+    let block_hash = block.hash.clone();
+    app_state.mempool_repo.lock().await.remove_included(&block);
+    drop(blockchain);
+    app_state.events.publish(ChainEvent::BlockAccepted {
+        block: block.clone(),
+    });
+    // Лідер голосує за свій блок неявно, цей вузол — своїм ACK нижче; голоси інших
+    // валідаторів могли прийти раніше за сам блок
+    let (voter_id, quorum, is_validator) = {
+        let node = app_state.node.lock().await;
+        (
+            node.id.clone(),
+            node.quorum(),
+            node.validator_ids.contains(&node.id),
+        )
+    };
+    let quorum_reached = {
+        let mut vote_counts = app_state.vote_counts.lock().await;
+        let voters = vote_counts.entry(block_hash.clone()).or_default();
+        let own_votes = [
+            Some(&block.header.proposer_id),
+            is_validator.then_some(&voter_id),
+        ];
+        for id in own_votes.into_iter().flatten() {
+            if !voters.contains(id) {
+                voters.push(id.clone());
+            }
+        }
+        let reached = voters.len() >= quorum;
+        if reached {
+            vote_counts.remove(&block_hash);
+        }
+        reached
+    };
+    if quorum_reached {
+        info!("Quorum already reached, block finalized");
+        finalize_block(app_state, block).await;
+    }
+
+    let vote = Vote::signed(
+        &app_state.identity,
+        &app_state.config.keys.shared_key,
+        &voter_id,
+        block_hash,
+        "ACK",
+    );
+    publish(app_state, PeerMessage::Vote(Gossip::new(vote))).await;
+}
+
+fn reject_block<B, M, U>(app_state: &AppState<B, M, U>, reason: &str)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    app_state
+        .metrics
+        .inc(metrics::BLOCKS_REJECTED, &[("reason", reason)]);
+}
+
+// Штрафує відправника лише за помилки, у яких винен він, а не за відставання чи форк
+async fn punish_block_sender<B, M, U>(app_state: &AppState<B, M, U>, sender: &str, e: &BlockError)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    if let Some(misbehaviour) = Misbehaviour::from_block_error(e) {
+        penalize_peer(app_state, sender, misbehaviour).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::accept_block;
+    use crate::blockchain::use_cases::accept_vote::accept_vote;
+    use crate::blockchain::use_cases::ban_peers::unix_now;
+    use crate::domain::app_state::testing::{app_state, child_block, transfer};
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::domain::chain_event::ChainEvent;
+    use crate::domain::config::Config;
+    use crate::domain::gossip::Gossip;
+    use crate::domain::mempool_repository::MempoolRepository;
+    use crate::domain::node_identity::NodeIdentity;
+    use crate::domain::user_state_repository::UserStateRepository;
+    use crate::domain::vote::Vote;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn accepted_block_prunes_its_transactions_from_the_mempool() {
+        let app_state = app_state(Config::for_tests("v2", &["v1", "v2"])).await;
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        let included = transfer(2, 10.0);
+        let pending = transfer(3, 5.0);
+        {
+            let mut mempool = app_state.mempool_repo.lock().await;
+            mempool.add_transaction(included.clone());
+            mempool.add_transaction(pending.clone());
+        }
+
+        let block = child_block(&genesis, "v1", vec![included], &mut balances);
+        accept_block(&app_state, "v1", Gossip::new(block.clone())).await;
+
+        let tip = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await;
+        assert_eq!(tip.map(|b| b.hash), Some(block.hash));
+        let mempool = app_state.mempool_repo.lock().await;
+        let ids: Vec<Uuid> = mempool
+            .get_all_transactions()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![pending.id]);
+    }
+
+    #[tokio::test]
+    async fn accepted_block_is_finalized_only_once_votes_reach_quorum() {
+        let app_state = app_state(Config::for_tests("v2", &["v1", "v2", "v3", "v4"])).await;
+        let mut events = app_state.events.subscribe();
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        let block = child_block(&genesis, "v1", vec![transfer(2, 10.0)], &mut balances);
+
+        accept_block(&app_state, "v1", Gossip::new(block.clone())).await;
+        assert!(matches!(
+            events.try_recv(),
+            Ok(ChainEvent::BlockProposed { height: 1, .. })
+        ));
+        assert!(
+            matches!(events.try_recv(), Ok(ChainEvent::BlockAccepted { block: b }) if b.hash == block.hash)
+        );
+        assert!(events.try_recv().is_err());
+        assert_eq!(
+            app_state.consensus_status.lock().await.finalized_height,
+            Some(0)
+        );
+
+        // Лідер і цей вузол — лише два голоси з трьох потрібних
+        let vote = Vote::signed(
+            &NodeIdentity::for_tests("v3"),
+            &app_state.config.keys.shared_key,
+            "v3",
+            block.hash.clone(),
+            "ACK",
+        );
+        accept_vote(&app_state, "v3", Gossip::new(vote)).await;
+        assert!(matches!(
+            events.try_recv(),
+            Ok(ChainEvent::VoteReceived { votes: 3, .. })
+        ));
+        assert!(
+            matches!(events.try_recv(), Ok(ChainEvent::BlockFinalized { block: b }) if b.hash == block.hash)
+        );
+        assert_eq!(
+            app_state.consensus_status.lock().await.finalized_height,
+            Some(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn block_not_signed_by_its_proposer_is_rejected() {
+        let app_state = app_state(Config::for_tests("v2", &["v1", "v2", "v3"])).await;
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        // v3 знає спільний ключ, але підписати блок від імені v1 може лише своїм ключем
+        let mut forged = child_block(&genesis, "v1", vec![transfer(2, 10.0)], &mut balances);
+        forged.sign_as_proposer(&NodeIdentity::for_tests("v3"));
+
+        accept_block(&app_state, "v3", Gossip::new(forged)).await;
+
+        let tip = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        assert_eq!(tip.hash, genesis.hash);
+        let score = app_state.scores.lock().await.get("v3", unix_now());
+        assert_eq!(
+            score.and_then(|score| score.last_offence),
+            Some("forged_block")
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use crate::blockchain::use_cases::add_block_to_chain::{add_block_to_chain, finalize_block};
+use crate::blockchain::use_cases::ban_peers::penalize_peer;
+use crate::blockchain::use_cases::peer_transport::{already_seen, first_seen, relay};
+use crate::domain::{
+    app_state::AppState, blockchain_repository::BlockchainRepository, chain_event::ChainEvent,
+    gossip::Gossip, mempool_repository::MempoolRepository, metrics, peer_message::PeerMessage,
+    peer_score::Misbehaviour, user_state_repository::UserStateRepository, vote::Vote,
+};
+use tracing::{debug, error, info};
+
+// Голос валідатора за блок, напряму чи через інших пірів; коли блок лідера набирає кворум,
+// він додається до ланцюга
+pub async fn accept_vote<B, M, U>(app_state: &AppState<B, M, U>, sender: &str, gossip: Gossip<Vote>)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let forward = gossip.next_hop();
+    let vote = gossip.item;
+    let hash_valid =
+        vote.block_hash.len() == 64 && vote.block_hash.bytes().all(|b| b.is_ascii_hexdigit());
+    if !hash_valid || !matches!(vote.decision.as_str(), "ACK" | "NACK") {
+        penalize_peer(app_state, sender, Misbehaviour::InvalidVote).await;
+        return;
+    }
+    if already_seen(app_state, &vote.id(), "vote").await {
+        return;
+    }
+    // Чесний пір пересилає лише перевірені голоси, тож за підробку відповідає той, хто її надіслав
+    let config = &app_state.config;
+    if !vote.verify(&config.keys.shared_key, &config.consensus.validator_keys) {
+        penalize_peer(app_state, sender, Misbehaviour::ForgedVote).await;
+        return;
+    }
+    if !first_seen(app_state, &vote.id(), "vote").await {
+        return;
+    }
+    if let Some(forward) = forward {
+        relay(app_state, sender, PeerMessage::Vote(forward)).await;
+    }
+    app_state
+        .metrics
+        .inc(metrics::VOTES_RECEIVED, &[("decision", &vote.decision)]);
+    if vote.decision != "ACK" {
+        return;
+    }
+    let quorum_needed = app_state.node.lock().await.quorum();
+    let mut vote_counts = app_state.vote_counts.lock().await;
+    let voters_for_this_block = vote_counts
+        .entry(vote.block_hash.clone())
+        .or_insert_with(Vec::new);
+    if !voters_for_this_block.contains(&vote.voter_id) {
+        voters_for_this_block.push(vote.voter_id.clone());
+    }
+    let current_vote_count = voters_for_this_block.len();
+    app_state.events.publish(ChainEvent::VoteReceived {
+        block_hash: vote.block_hash.clone(),
+        voter_id: vote.voter_id.clone(),
+        votes: current_vote_count,
+    });
+    debug!(
+        block_hash = %vote.block_hash,
+        voter = %vote.voter_id,
+        votes = current_vote_count,
+        "Vote received"
+    );
+    // Кворум рахується один раз, коли голосів стає рівно стільки, скільки потрібно
+    if current_vote_count != quorum_needed {
+        return;
+    }
+    drop(vote_counts);
+    let block_to_add = {
+        let mut pending_blocks = app_state.pending_blocks.lock().await;
+        pending_blocks.remove(&vote.block_hash)
+    };
+    let Some(block) = block_to_add else {
+        finalize_accepted_block(app_state, &vote.block_hash, current_vote_count).await;
+        return;
+    };
+    app_state.vote_counts.lock().await.remove(&vote.block_hash);
+    app_state.metrics.quorum_reached(&block.hash);
+    if let Err(e) = add_block_to_chain(app_state, block.clone()).await {
+        error!(block_hash = %block.hash, error = %e, "Failed to store block");
+        return;
+    }
+    info!(
+        height = block.header.height,
+        block_hash = %block.hash,
+        votes = current_vote_count,
+        "Quorum reached, block added to the chain"
+    );
+}
+
+// Не лідер уже додав блок у accept_block, і тепер він остаточний. Якщо голоси
+// обігнали сам блок, лічильник лишається, і блок завершить accept_block
+async fn finalize_accepted_block<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    block_hash: &str,
+    votes: usize,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let accepted = app_state
+        .blockchain_repo
+        .lock()
+        .await
+        .get_block_by_hash(block_hash)
+        .await;
+    let Some(block) = accepted else {
+        debug!(block_hash = %block_hash, "Quorum reached for a block that has not arrived yet");
+        return;
+    };
+    // Хто забрав лічильник, той і публікує подію, тож accept_block її не повторить
+    if app_state
+        .vote_counts
+        .lock()
+        .await
+        .remove(block_hash)
+        .is_none()
+    {
+        return;
+    }
+    info!(
+        height = block.header.height,
+        block_hash = %block.hash,
+        votes,
+        "Quorum reached, block finalized"
+    );
+    finalize_block(app_state, block).await;
+}
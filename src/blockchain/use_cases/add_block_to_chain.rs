@@ -1,13 +1,40 @@
-use std::sync::Arc;
+use crate::domain::{
+    app_state::AppState, block::Block, blockchain_repository::BlockchainRepository,
+    chain_event::ChainEvent, error::StorageError, mempool_repository::MempoolRepository,
+    user_state_repository::UserStateRepository,
+};
 
-use tokio::sync::Mutex;
-
-use crate::domain::{block::Block, blockchain_repository::BlockchainRepository};
+pub async fn add_block_to_chain<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    block: Block,
+) -> Result<(), StorageError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    {
+        let mut repo_lock = app_state.blockchain_repo.lock().await;
+        repo_lock.add_block(block.clone()).await?;
+        app_state.user_state_repo.lock().await.commit_block(&block);
+        app_state.mempool_repo.lock().await.remove_included(&block);
+    }
+    finalize_block(app_state, block).await;
+    Ok(())
+}
 
-pub async fn add_block_to_chain<B>(blockchain_repository: Arc<Mutex<B>>, block: Block)
+// Блок у ланцюгу набрав кворум голосів (або прийшов з довшого ланцюга під час синхронізації)
+pub async fn finalize_block<B, M, U>(app_state: &AppState<B, M, U>, block: Block)
 where
     B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
 {
-    let mut repo_lock = blockchain_repository.lock().await;
-    repo_lock.add_block(block).await;
+    {
+        let mut status = app_state.consensus_status.lock().await;
+        status.finalized_height = status.finalized_height.max(Some(block.header.height));
+    }
+    app_state
+        .events
+        .publish(ChainEvent::BlockFinalized { block });
 }
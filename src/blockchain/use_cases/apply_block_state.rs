@@ -0,0 +1,49 @@
+use crate::domain::{block::Block, error::BlockError, user_state_repository::UserStateRepository};
+use crate::infrastructure::in_memory_user_state_repository::InMemoryUserStateRepository;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+// Застосовує транзакції блоку і звіряє корінь стану із заголовком.
+// У разі помилки повертає баланси до стану перед блоком.
+pub fn apply_block_state<U>(user_state: &mut U, block: &Block) -> Result<(), BlockError>
+where
+    U: UserStateRepository + ?Sized,
+{
+    let height = block.header.height;
+    let balances_before = user_state.get_balances().clone();
+    for tx in &block.transactions {
+        if let Err(reason) = user_state.apply_transaction(tx) {
+            user_state.reset_balances(balances_before);
+            return Err(BlockError::InvalidTransaction {
+                height,
+                transaction_id: tx.id,
+                reason,
+            });
+        }
+    }
+
+    let actual = user_state.state_root();
+    if actual != block.header.state_root {
+        user_state.reset_balances(balances_before);
+        return Err(BlockError::StateRootMismatch {
+            height,
+            expected: block.header.state_root.clone(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+// Відтворює блоки на копії балансів, звіряючи корінь стану кожного; справжній стан
+// не змінюється. Повертає баланси після останнього блоку
+pub fn replay_on_copy(
+    balances: HashMap<Uuid, f64>,
+    blocks: &[Block],
+) -> Result<HashMap<Uuid, f64>, BlockError> {
+    let mut scratch = InMemoryUserStateRepository::with_snapshot_interval(u64::MAX);
+    scratch.reset_balances(balances);
+    for block in blocks {
+        apply_block_state(&mut scratch, block)?;
+    }
+    Ok(scratch.get_balances().clone())
+}
@@ -0,0 +1,99 @@
+use crate::domain::{
+    app_state::AppState, blockchain_repository::BlockchainRepository,
+    mempool_repository::MempoolRepository, metrics, peer_score::Misbehaviour,
+    user_state_repository::UserStateRepository,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+// Знімає з піра бали за порушення і банує його, якщо рахунок упав нижче порогу
+pub async fn penalize_peer<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    misbehaviour: Misbehaviour,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    app_state
+        .metrics
+        .inc(metrics::PEER_PENALTIES, &[("reason", misbehaviour.kind())]);
+    let banned_until = app_state
+        .scores
+        .lock()
+        .await
+        .penalize(node_id, misbehaviour, unix_now());
+    warn!(
+        node_id = %node_id,
+        reason = misbehaviour.kind(),
+        penalty = misbehaviour.penalty(),
+        "Peer misbehaved"
+    );
+    if let Some(banned_until) = banned_until {
+        warn!(node_id = %node_id, banned_until, "Peer banned for misbehaviour");
+        app_state
+            .metrics
+            .inc(metrics::PEER_BANS, &[("source", "score")]);
+        disconnect(app_state, node_id).await;
+    }
+}
+
+// Бан вручну через адмінський API; повертає момент закінчення бану
+pub async fn ban_peer<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    duration: Duration,
+    reason: String,
+) -> u64
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let banned_until =
+        app_state
+            .scores
+            .lock()
+            .await
+            .ban(node_id, duration, reason.clone(), unix_now());
+    warn!(node_id = %node_id, banned_until, reason = %reason, "Peer banned by an operator");
+    app_state
+        .metrics
+        .inc(metrics::PEER_BANS, &[("source", "admin")]);
+    disconnect(app_state, node_id).await;
+    banned_until
+}
+
+// Забанений пір одразу перестає отримувати блоки й голоси
+async fn disconnect<B, M, U>(app_state: &AppState<B, M, U>, node_id: &str)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    {
+        let mut links = app_state.links.lock().await;
+        if links.remove(node_id, None) {
+            app_state
+                .metrics
+                .set(metrics::PEER_LINKS, &[], links.len() as f64);
+        }
+    }
+    let removed = app_state.peers.lock().await.remove(node_id);
+    if let Some(address) = removed.and_then(|info| info.address) {
+        app_state
+            .node
+            .lock()
+            .await
+            .peers
+            .retain(|peer| *peer != address);
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -1,42 +1,49 @@
-use std::env;
-
 use crate::domain::{
-    block::Block, blockchain_repository::BlockchainRepository, transaction::Transaction,
+    block::Block, blockchain_repository::BlockchainRepository, config::Config,
+    state_snapshot::compute_state_root, transaction::Transaction,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tracing::{error, info};
 use uuid::Uuid;
-pub async fn create_genesis_block<B>(blockchain_repository: Arc<Mutex<B>>)
+pub async fn create_genesis_block<B>(blockchain_repository: Arc<Mutex<B>>, config: &Config)
 where
     B: BlockchainRepository + Send + Sync + 'static,
 {
-    let shared_key = env::var("SHARED_KEY").expect("SHARED_KEY");
-
-    let genesis_sender_str = env::var("GENESIS_SENDER_ID").expect("GENESIS_SENDER_ID");
-    let faucet_wallet_str = env::var("FAUCET_WALLET_ID").expect("FAUCET_WALLET_ID");
+    if blockchain_repository.lock().await.height().await.is_some() {
+        info!("Chain already has a genesis block");
+        return;
+    }
 
-    let genesis_sender_id = Uuid::parse_str(&genesis_sender_str).unwrap();
-    let faucet_wallet_id = Uuid::parse_str(&faucet_wallet_str).unwrap();
+    let mut repo_lock = blockchain_repository.lock().await;
+    if let Err(e) = repo_lock.add_block(genesis_block(config)).await {
+        error!(error = %e, "Failed to store the genesis block");
+    }
+}
 
+// Генезис залежить лише від конфігурації (id транзакції в хеш не входить), тому його хеш
+// відомий і вузлу, чий ланцюг починається зі снапшоту
+pub fn genesis_block(config: &Config) -> Block {
+    let faucet_wallet_id = config.genesis.faucet_wallet_id;
     let genesis_tx = Transaction {
         id: Uuid::new_v4(),
-        from: genesis_sender_id, // <-- ОТ "СИСТЕМЫ"
-        to: faucet_wallet_id,    // <-- НА "КРАН"
-        amount: 1000000.0,
+        from: config.genesis.sender_id, // <-- ОТ "СИСТЕМЫ"
+        to: faucet_wallet_id,           // <-- НА "КРАН"
+        amount: config.genesis.amount,
         timestamp: 0,
     };
 
-    let mut repo_lock = blockchain_repository.lock().await;
+    let genesis_balances = HashMap::from([(faucet_wallet_id, genesis_tx.amount)]);
 
-    let genesis_block = Block::new(
+    Block::new(
         0,
         0,
         "GENESIS".to_string(),
         0,
         vec![genesis_tx],
         "0".to_string(),
-        shared_key.to_string(),
-    );
-
-    repo_lock.add_block(genesis_block.clone()).await;
+        compute_state_root(&genesis_balances),
+        config.keys.shared_key.clone(),
+    )
 }
@@ -1,3 +1,4 @@
+use crate::domain::node_identity::NodeIdentity;
 use crate::domain::transaction::Transaction;
 use crate::domain::{block::Block, blockchain_repository::BlockchainRepository};
 use std::sync::Arc;
@@ -8,15 +9,17 @@ pub async fn create_new_block<B>(
     blockchain_repository: Arc<Mutex<B>>,
     transactions: Vec<Transaction>,
     proposer_id: &str,
+    state_root: String,
     shared_key: &str,
-) -> Block
+    identity: &NodeIdentity,
+) -> Option<Block>
 where
     B: BlockchainRepository + Send + Sync + 'static,
 {
     let repo_lock = blockchain_repository.lock().await;
-    let last_block = repo_lock.get_last_block().await;
+    let last_block = repo_lock.get_last_block().await?;
 
-    let new_block = Block::new(
+    let mut block = Block::new(
         last_block.index + 1,
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -26,8 +29,9 @@ where
         last_block.header.height + 1,
         transactions,
         last_block.hash.clone(),
+        state_root,
         shared_key.to_string(),
     );
-
-    new_block
+    block.sign_as_proposer(identity);
+    Some(block)
 }
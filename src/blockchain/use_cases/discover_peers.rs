@@ -0,0 +1,281 @@
+use crate::blockchain::use_cases::ban_peers::unix_now;
+use crate::blockchain::use_cases::handshake_peers::{
+    HandshakeFailure, handshake_peer, local_handshake,
+};
+use crate::blockchain::use_cases::peer_transport::{connect_peer, request};
+use crate::domain::{
+    address_book::{AddressSource, MAX_SHARED_ADDRESSES},
+    app_state::AppState,
+    blockchain_repository::BlockchainRepository,
+    error::HandshakeError,
+    handshake::Handshake,
+    mempool_repository::MempoolRepository,
+    metrics,
+    peer_address::PeerAddress,
+    peer_message::{SyncQuery, SyncReply},
+    user_state_repository::UserStateRepository,
+};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+// Як часто перевіряємо з'єднання, добираємо нових пірів і обмінюємося адресами
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(5);
+
+pub async fn peer_discovery_task<B, M, U>(app_state: AppState<B, M, U>)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut round = 0;
+    loop {
+        tokio::time::sleep(DISCOVERY_INTERVAL).await;
+        round += 1;
+        discover_peers(&app_state, round).await;
+    }
+}
+
+/// One pass of peer maintenance: re-handshakes the current outbound peers,
+/// fills free outbound slots from the address book, asks peers for the
+/// addresses they know, opens P2P connections to outbound peers that have
+/// none yet and saves the book if anything changed.
+///
+/// `round` picks which connected peer is asked for addresses, so every peer
+/// is asked in turn; peers connected during this pass are always asked.
+pub async fn discover_peers<B, M, U>(app_state: &AppState<B, M, U>, round: usize)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let local = local_handshake(app_state).await;
+    let max_outbound = app_state.config.node.max_outbound_peers;
+    let previous = app_state.node.lock().await.peers.clone();
+
+    // Живі з'єднання лишаються, ті, що не відповіли, звільняють слот
+    let mut connected = Vec::new();
+    let mut handshakes = Vec::new();
+    for peer in &previous {
+        match handshake_peer(app_state, peer, &local).await {
+            Ok(handshake) if is_banned(app_state, &handshake.node_id).await => {
+                info!(peer = %peer, node_id = %handshake.node_id, "Disconnected from banned peer");
+                app_state.peers.lock().await.forget_address(peer);
+            }
+            Ok(handshake) => {
+                app_state.address_book.lock().await.record_success(
+                    peer,
+                    &handshake.node_id,
+                    unix_now(),
+                );
+                app_state
+                    .peers
+                    .lock()
+                    .await
+                    .record_outbound(peer, handshake.clone());
+                connected.push(peer.clone());
+                handshakes.push((peer.clone(), handshake));
+            }
+            Err(failure) => {
+                info!(peer = %peer, "Disconnected from peer");
+                forget(app_state, peer, &failure).await;
+            }
+        }
+    }
+
+    let mut newly_connected = Vec::new();
+    if connected.len() < max_outbound {
+        let candidates = app_state.address_book.lock().await.due(unix_now());
+        for entry in candidates {
+            if connected.len() >= max_outbound {
+                break;
+            }
+            if connected.contains(&entry.address) || is_self(app_state, &local, &entry.address) {
+                continue;
+            }
+            // Вузол уже підключений за іншою адресою або забанений
+            if let Some(node_id) = &entry.node_id
+                && (app_state.peers.lock().await.is_outbound(node_id)
+                    || is_banned(app_state, node_id).await)
+            {
+                continue;
+            }
+            match handshake_peer(app_state, &entry.address, &local).await {
+                Ok(handshake) if is_banned(app_state, &handshake.node_id).await => {
+                    app_state
+                        .address_book
+                        .lock()
+                        .await
+                        .record_failure(&entry.address, unix_now());
+                }
+                Ok(handshake) => {
+                    let mut registry = app_state.peers.lock().await;
+                    if registry.is_outbound(&handshake.node_id) {
+                        continue;
+                    }
+                    info!(
+                        peer = %entry.address,
+                        node_id = %handshake.node_id,
+                        best_height = handshake.best_height,
+                        source = ?entry.source,
+                        "Connected to peer"
+                    );
+                    app_state.address_book.lock().await.record_success(
+                        &entry.address,
+                        &handshake.node_id,
+                        unix_now(),
+                    );
+                    registry.record_outbound(&entry.address, handshake.clone());
+                    drop(registry);
+                    connected.push(entry.address.clone());
+                    handshakes.push((entry.address.clone(), handshake));
+                    newly_connected.push(entry.address);
+                }
+                Err(failure) => forget(app_state, &entry.address, &failure).await,
+            }
+        }
+    }
+    app_state.node.lock().await.peers = connected.clone();
+
+    // Блоки, голоси й синхронізація йдуть P2P-з'єднанням; обірване відкривається знову
+    for (peer, handshake) in &handshakes {
+        connect_peer(app_state, peer, handshake).await;
+    }
+
+    // Нові піри (зокрема bootstrap-вузли на старті) і ще один по черзі
+    let mut asked = newly_connected;
+    if let Some(peer) = connected.get(round % connected.len().max(1))
+        && !asked.contains(peer)
+    {
+        asked.push(peer.clone());
+    }
+    for peer in &asked {
+        exchange_addresses(app_state, &local, peer).await;
+    }
+
+    let connected_inbound = app_state.links.lock().await.inbound_ids();
+    let (inbound, outbound) = {
+        let mut registry = app_state.peers.lock().await;
+        registry.retain_inbound(&connected_inbound);
+        (registry.inbound_count(), registry.outbound_count())
+    };
+    let mut book = app_state.address_book.lock().await;
+    app_state.metrics.set(
+        metrics::PEERS_CONNECTED,
+        &[("direction", "inbound")],
+        inbound as f64,
+    );
+    app_state.metrics.set(
+        metrics::PEERS_CONNECTED,
+        &[("direction", "outbound")],
+        outbound as f64,
+    );
+    app_state
+        .metrics
+        .set(metrics::ADDRESS_BOOK_SIZE, &[], book.len() as f64);
+    if book.take_changed()
+        && let Some(store) = &app_state.address_store
+        && let Err(e) = store.save(&book)
+    {
+        warn!(error = %e, "Failed to save address book");
+    }
+}
+
+// Запитує в піра відомі йому адреси й додає нові в книгу
+/// The node's own address followed by up to [`MAX_SHARED_ADDRESSES`] addresses
+/// that answered a handshake recently; what a peer gets when it asks for addresses.
+pub async fn shared_addresses<B, M, U>(app_state: &AppState<B, M, U>) -> Vec<String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut addresses = vec![app_state.config.node.advertise_address.to_string()];
+    addresses.extend(
+        app_state
+            .address_book
+            .lock()
+            .await
+            .shareable(MAX_SHARED_ADDRESSES)
+            .into_iter()
+            .map(|address| address.to_string()),
+    );
+    addresses
+}
+
+// Адреси питаємо P2P-з'єднанням, відкритим щойно після handshake
+async fn exchange_addresses<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    local: &Handshake,
+    peer: &PeerAddress,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let Some(node_id) = app_state.peers.lock().await.node_id_at(peer) else {
+        return;
+    };
+    let addresses = match request(app_state, &node_id, SyncQuery::Addresses).await {
+        Ok(SyncReply::Addresses { addresses }) => addresses,
+        Ok(_) => {
+            warn!(peer = %peer, "Peer answered an address request with something else");
+            return;
+        }
+        Err(e) => {
+            debug!(peer = %peer, error = %e, "Failed to ask peer for addresses");
+            return;
+        }
+    };
+
+    let mut book = app_state.address_book.lock().await;
+    let mut learned = 0;
+    for address in addresses.iter().take(MAX_SHARED_ADDRESSES) {
+        // Некоректні адреси від піра просто пропускаємо
+        let Ok(address) = address.parse::<PeerAddress>() else {
+            continue;
+        };
+        if !is_self(app_state, local, &address) && book.add(&address, AddressSource::Exchange) {
+            learned += 1;
+        }
+    }
+    if learned > 0 {
+        info!(peer = %peer, learned, "Learned peer addresses");
+    }
+}
+
+// Адреса, за якою немає сумісного піра: з'єднання закривається, спроби відкладаються
+async fn forget<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    peer: &PeerAddress,
+    failure: &HandshakeFailure,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    app_state.peers.lock().await.forget_address(peer);
+    let mut book = app_state.address_book.lock().await;
+    match failure {
+        HandshakeFailure::Mismatch(HandshakeError::SelfConnection) => book.remove(peer),
+        _ => book.record_failure(peer, unix_now()),
+    }
+}
+
+async fn is_banned<B, M, U>(app_state: &AppState<B, M, U>, node_id: &str) -> bool
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    app_state.scores.lock().await.is_banned(node_id, unix_now())
+}
+
+fn is_self<B, M, U>(app_state: &AppState<B, M, U>, local: &Handshake, address: &PeerAddress) -> bool
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    address.node_id.as_ref() == Some(&local.node_id)
+        || address.authority() == app_state.config.node.advertise_address.authority()
+}
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use reqwest::Client;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::api::handlers::MAX_BLOCKS_PER_REQUEST;
+use crate::blockchain::use_cases::{
+    apply_block_state::replay_on_copy, create_genesis_block::genesis_block,
+};
+use crate::domain::{
+    app_state::AppState,
+    block::Block,
+    block_locator::BlockHeaderEntry,
+    blockchain_repository::BlockchainRepository,
+    mempool_repository::MempoolRepository,
+    peer_address::PeerAddress,
+    state_snapshot::{SnapshotChunk, SnapshotManifest, StateSnapshot, compute_state_root},
+    user_state_repository::UserStateRepository,
+};
+
+// Швидка синхронізація: завантажує снапшот стану в піра, перевіряє його за state_root
+// у заголовку блоку і відтворює лише блоки після снапшоту. Нижче снапшоту вузол
+// отримує тільки заголовки, тож його ланцюг починається з блоку снапшоту.
+// Повертає false, якщо жоден пір не віддав придатного снапшоту.
+pub async fn fast_sync<B, M, U>(app_state: AppState<B, M, U>) -> bool
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    info!("Looking for a state snapshot on peers");
+
+    let configured_peers = app_state.node.lock().await.peers.clone();
+    let (peers, http_client, shared_key) = {
+        (
+            app_state.peers.lock().await.handshaken(&configured_peers),
+            app_state.http_client.clone(),
+            app_state.config.keys.shared_key.clone(),
+        )
+    };
+    let genesis_hash = genesis_block(&app_state.config).hash;
+
+    let mut manifests: Vec<(PeerAddress, SnapshotManifest)> = Vec::new();
+    for peer_addr in &peers {
+        let target_url = peer_addr.url("/snapshot");
+        match http_client.get(&target_url).send().await {
+            Ok(response) => {
+                if let Ok(manifest) = response.json::<SnapshotManifest>().await {
+                    manifests.push((peer_addr.clone(), manifest));
+                }
+            }
+            Err(e) => warn!(peer = %peer_addr, error = %e, "Failed to fetch snapshot manifest"),
+        }
+    }
+    manifests.sort_by_key(|(_, m)| std::cmp::Reverse(m.height));
+
+    for (peer_addr, manifest) in manifests {
+        match sync_from_peer(
+            &http_client,
+            &peer_addr,
+            &manifest,
+            &genesis_hash,
+            &shared_key,
+            &app_state.config.consensus.validator_keys,
+        )
+        .await
+        {
+            Ok((chain, snapshot)) => {
+                let mut blockchain = app_state.blockchain_repo.lock().await;
+                let mut user_state = app_state.user_state_repo.lock().await;
+
+                let tail: Vec<Block> = chain
+                    .iter()
+                    .filter(|b| b.header.height > snapshot.height)
+                    .cloned()
+                    .collect();
+                if let Err(e) = blockchain.replace_chain(chain).await {
+                    error!(error = %e, "Failed to store the downloaded chain");
+                    return false;
+                }
+                user_state.install_snapshot(snapshot);
+                // Хвіст уже перевірено на копії стану, тож відтворення не має зупинитися
+                if let Err(e) = user_state.replay_blocks(&tail).await {
+                    error!(reason = %e, "Replay after the snapshot stopped early");
+                }
+
+                let mut mempool = app_state.mempool_repo.lock().await;
+                for block in &tail {
+                    mempool.remove_included(block);
+                }
+
+                info!(
+                    peer = %peer_addr,
+                    height = manifest.height,
+                    replayed = tail.len(),
+                    "Snapshot installed"
+                );
+                return true;
+            }
+            Err(reason) => warn!(
+                peer = %peer_addr,
+                height = manifest.height,
+                reason = %reason,
+                "Snapshot rejected"
+            ),
+        }
+    }
+
+    warn!("No usable snapshot found");
+    false
+}
+
+async fn sync_from_peer(
+    http_client: &Client,
+    peer_addr: &PeerAddress,
+    manifest: &SnapshotManifest,
+    genesis_hash: &str,
+    shared_key: &str,
+    pinned: &HashMap<String, String>,
+) -> Result<(Vec<Block>, StateSnapshot), String> {
+    // До снапшоту блоки не відтворюються, тож вистачає заголовків: вони мають іти від нашого
+    // генезису без розривів і привести до блоку снапшоту
+    let mut last: Option<BlockHeaderEntry> = None;
+    while last
+        .as_ref()
+        .is_none_or(|entry| entry.header.height < manifest.height)
+    {
+        let from = last.as_ref().map_or(0, |entry| entry.header.height + 1);
+        let page = http_client
+            .get(peer_addr.url("/headers"))
+            .query(&[("from", from)])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json::<Vec<BlockHeaderEntry>>()
+            .await
+            .map_err(|e| e.to_string())?;
+        if page.is_empty() {
+            return Err("snapshot height is not on the peer's chain".to_string());
+        }
+        for entry in page
+            .into_iter()
+            .take_while(|entry| entry.header.height <= manifest.height)
+        {
+            match &last {
+                None if entry.header.height != 0 || entry.hash != genesis_hash => {
+                    return Err("peer's chain does not start at our genesis".to_string());
+                }
+                Some(parent)
+                    if entry.header.height != parent.header.height + 1
+                        || entry.header.parent_hash != parent.hash =>
+                {
+                    return Err(format!(
+                        "header #{} does not link to its parent",
+                        entry.header.height
+                    ));
+                }
+                _ => {}
+            }
+            last = Some(entry);
+        }
+    }
+    // Заголовки лише заявлені піром, тож тут відсіюється явна невідповідність, а
+    // state_root звіряється з самим блоком снапшоту у verify_tail
+    let anchor_header = last.ok_or("peer returned no headers")?;
+    if anchor_header.hash != manifest.block_hash {
+        return Err("snapshot block hash does not match the chain".to_string());
+    }
+
+    // Повні блоки — лише від блоку снапшоту до вершини
+    let mut chain: Vec<Block> = Vec::new();
+    loop {
+        let from = manifest.height + chain.len() as u64;
+        let page = http_client
+            .get(peer_addr.url("/blocks"))
+            .query(&[("from", from), ("to", from + MAX_BLOCKS_PER_REQUEST - 1)])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json::<Vec<Block>>()
+            .await
+            .map_err(|e| e.to_string())?;
+        if page.is_empty() {
+            break;
+        }
+        chain.extend(page);
+    }
+    verify_tail(manifest, &anchor_header, &chain, shared_key, pinned)?;
+
+    let mut balances: HashMap<Uuid, f64> = HashMap::with_capacity(manifest.total_accounts);
+    for index in 0..manifest.chunk_count {
+        let chunk = http_client
+            .get(peer_addr.url(&format!("/snapshot/{}/chunks/{}", manifest.height, index)))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json::<SnapshotChunk>()
+            .await
+            .map_err(|e| format!("chunk {}: {}", index, e))?;
+        if chunk.height != manifest.height || chunk.index != index {
+            return Err(format!("peer returned the wrong chunk for {}", index));
+        }
+        balances.extend(chunk.accounts);
+    }
+
+    if compute_state_root(&balances) != manifest.state_root {
+        return Err("downloaded state does not match the state root".to_string());
+    }
+
+    // Хвіст має лягти на снапшот раніше, ніж ми замінимо свій ланцюг і стан
+    replay_on_copy(balances.clone(), &chain[1..]).map_err(|e| e.to_string())?;
+
+    Ok((
+        chain,
+        StateSnapshot {
+            height: manifest.height,
+            block_hash: manifest.block_hash.clone(),
+            balances,
+        },
+    ))
+}
+
+// Блоки від снапшоту до вершини мають бути справжніми й іти один за одним, а перший —
+// бути саме тим блоком, на чий state_root спирається снапшот
+fn verify_tail(
+    manifest: &SnapshotManifest,
+    anchor_header: &BlockHeaderEntry,
+    chain: &[Block],
+    shared_key: &str,
+    pinned: &HashMap<String, String>,
+) -> Result<(), String> {
+    for (i, block) in chain.iter().enumerate() {
+        block
+            .validate(shared_key, pinned)
+            .map_err(|e| e.to_string())?;
+        let linked = match i.checked_sub(1) {
+            None => {
+                block.hash == anchor_header.hash
+                    && block.header.parent_hash == anchor_header.header.parent_hash
+            }
+            Some(parent) => block.header.parent_hash == chain[parent].hash,
+        };
+        if !linked || block.header.height != manifest.height + i as u64 {
+            return Err(format!(
+                "block #{} does not link to its parent",
+                block.header.height
+            ));
+        }
+    }
+    let Some(anchor) = chain.first() else {
+        return Err("peer did not return the snapshot block".to_string());
+    };
+    // Хеш заголовка піра ніхто не перераховував; хеш блоку перераховано в validate,
+    // тож зобов'язанням служить лише state_root самого блоку
+    if anchor.hash != manifest.block_hash || anchor.header.state_root != manifest.state_root {
+        return Err("snapshot state root does not match the block header".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::verify_tail;
+    use crate::blockchain::use_cases::create_genesis_block::genesis_block;
+    use crate::domain::app_state::testing::{child_block, transfer};
+    use crate::domain::block::Block;
+    use crate::domain::block_locator::BlockHeaderEntry;
+    use crate::domain::config::Config;
+    use crate::domain::state_snapshot::{SnapshotManifest, compute_state_root};
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn manifest(block: &Block, state_root: String) -> SnapshotManifest {
+        SnapshotManifest {
+            height: block.header.height,
+            block_hash: block.hash.clone(),
+            state_root,
+            total_accounts: 1,
+            chunk_size: 1,
+            chunk_count: 1,
+        }
+    }
+
+    #[test]
+    fn accepts_a_snapshot_matching_its_block() {
+        let config = Config::for_tests("v1", &["v1"]);
+        let genesis = genesis_block(&config);
+        let mut balances = HashMap::new();
+        let block = child_block(&genesis, "v1", vec![transfer(2, 10.0)], &mut balances);
+
+        let manifest = manifest(&block, block.header.state_root.clone());
+
+        assert!(
+            verify_tail(
+                &manifest,
+                &BlockHeaderEntry::from(&block),
+                &[block],
+                "test-key",
+                &config.consensus.validator_keys,
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_a_state_root_vouched_for_only_by_a_forged_header() {
+        let config = Config::for_tests("v1", &["v1"]);
+        let genesis = genesis_block(&config);
+        let mut balances = HashMap::new();
+        let block = child_block(&genesis, "v1", vec![transfer(2, 10.0)], &mut balances);
+        // Пір заявляє справжній хеш блоку, але з заголовком під свій підроблений стан
+        let forged_root = compute_state_root(&HashMap::from([(Uuid::from_u128(9), 1e9)]));
+        let mut forged = BlockHeaderEntry::from(&block);
+        forged.header.state_root = forged_root.clone();
+
+        let error = verify_tail(
+            &manifest(&block, forged_root),
+            &forged,
+            &[block],
+            "test-key",
+            &config.consensus.validator_keys,
+        )
+        .unwrap_err();
+
+        assert!(error.contains("state root"), "{}", error);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::blockchain::use_cases::create_genesis_block::genesis_block;
+use crate::domain::{
+    app_state::AppState,
+    blockchain_repository::BlockchainRepository,
+    error::HandshakeError,
+    handshake::{Handshake, PROTOCOL_VERSION},
+    mempool_repository::MempoolRepository,
+    metrics,
+    peer_address::PeerAddress,
+    user_state_repository::UserStateRepository,
+};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+pub enum HandshakeFailure {
+    Unreachable(String),
+    Refused(String),
+    Mismatch(HandshakeError),
+}
+
+// Те, що цей вузол повідомляє про себе іншим
+pub async fn local_handshake<B, M, U>(app_state: &AppState<B, M, U>) -> Handshake
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let (genesis_hash, best_height) = {
+        let blockchain = app_state.blockchain_repo.lock().await;
+        (
+            blockchain
+                .get_block_by_height(0)
+                .await
+                .map(|b| b.hash)
+                // Після швидкої синхронізації ланцюг починається зі снапшоту
+                .unwrap_or_else(|| genesis_block(&app_state.config).hash),
+            blockchain.height().await.unwrap_or(0),
+        )
+    };
+    let node = app_state.node.lock().await;
+    Handshake {
+        node_id: node.id.clone(),
+        chain_id: node.chain_id.clone(),
+        genesis_hash,
+        protocol_version: PROTOCOL_VERSION,
+        best_height,
+        identity_key: app_state.identity.public_key(),
+        validator_pubkey: Handshake::validator_key(
+            &app_state.config.keys.shared_key,
+            &node.id,
+            &app_state.identity.public_key(),
+        ),
+        listen_address: Some(app_state.config.node.advertise_address.clone()),
+        p2p_port: Some(app_state.config.node.p2p_port),
+    }
+}
+
+// Надсилає handshake одному піру; невдачі логуються й рахуються тут, а рішення,
+// чи тримати з'єднання, ухвалює виклик
+pub async fn handshake_peer<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    peer: &PeerAddress,
+    local: &Handshake,
+) -> Result<Handshake, HandshakeFailure>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let result = exchange(
+        &app_state.http_client,
+        peer,
+        local,
+        &app_state.config.keys.shared_key,
+        &app_state.config.consensus.validator_keys,
+    )
+    .await;
+    match &result {
+        Ok(_) => {}
+        Err(HandshakeFailure::Unreachable(e)) => {
+            if app_state.peers.lock().await.is_handshaken(peer) {
+                warn!(peer = %peer, error = %e, "Handshaken peer became unreachable");
+            } else {
+                debug!(peer = %peer, error = %e, "Peer is not reachable");
+            }
+        }
+        Err(HandshakeFailure::Refused(reason)) => {
+            warn!(peer = %peer, reason = %reason, "Peer refused our handshake");
+            app_state
+                .metrics
+                .inc(metrics::PEER_HANDSHAKE_FAILURES, &[("reason", "refused")]);
+        }
+        // Власна адреса, отримана від піра, — не привід для попередження
+        Err(HandshakeFailure::Mismatch(HandshakeError::SelfConnection)) => {
+            debug!(peer = %peer, "Address belongs to this node");
+        }
+        Err(HandshakeFailure::Mismatch(e)) => {
+            warn!(peer = %peer, reason = %e, "Peer is not on our network");
+            app_state
+                .metrics
+                .inc(metrics::PEER_HANDSHAKE_FAILURES, &[("reason", e.kind())]);
+        }
+    }
+    result
+}
+
+async fn exchange(
+    http_client: &Client,
+    peer: &PeerAddress,
+    local: &Handshake,
+    shared_key: &str,
+    pinned: &HashMap<String, String>,
+) -> Result<Handshake, HandshakeFailure> {
+    let response = http_client
+        .post(peer.url("/handshake"))
+        .json(local)
+        .send()
+        .await
+        .map_err(|e| HandshakeFailure::Unreachable(e.to_string()))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or_default();
+        let reason = body["error"]["message"]
+            .as_str()
+            .map(String::from)
+            .unwrap_or_else(|| status.to_string());
+        return Err(HandshakeFailure::Refused(reason));
+    }
+
+    let handshake: Handshake = response
+        .json()
+        .await
+        .map_err(|e| HandshakeFailure::Refused(format!("invalid handshake: {}", e)))?;
+    handshake
+        .check(local, shared_key, pinned)
+        .map_err(HandshakeFailure::Mismatch)?;
+    if let Some(expected) = &peer.node_id
+        && *expected != handshake.node_id
+    {
+        return Err(HandshakeFailure::Mismatch(HandshakeError::NodeId {
+            expected: expected.clone(),
+            actual: handshake.node_id,
+        }));
+    }
+    Ok(handshake)
+}
@@ -1,5 +1,16 @@
+pub mod accept_block;
+pub mod accept_vote;
 pub mod add_block_to_chain;
+pub mod apply_block_state;
+pub mod ban_peers;
 pub mod create_genesis_block;
 pub mod create_new_block;
+pub mod discover_peers;
+pub mod fast_sync;
+pub mod handshake_peers;
+pub mod peer_transport;
 pub mod pos_consensus_loop;
+pub mod restore_user_state;
+pub mod revalidate_mempool;
+pub mod submit_transaction;
 pub mod sync_chain_task;
@@ -0,0 +1,992 @@
+use crate::api::handlers::{MAX_BLOCKS_PER_REQUEST, MAX_HEADERS_PER_REQUEST};
+use crate::blockchain::use_cases::accept_block::accept_block;
+use crate::blockchain::use_cases::accept_vote::accept_vote;
+use crate::blockchain::use_cases::ban_peers::{penalize_peer, unix_now};
+use crate::blockchain::use_cases::discover_peers::shared_addresses;
+use crate::blockchain::use_cases::handshake_peers::local_handshake;
+use crate::blockchain::use_cases::submit_transaction::{
+    TransactionRejection, admit_announced_transaction,
+};
+use crate::domain::{
+    app_state::AppState,
+    block::Block,
+    block_locator::headers_after,
+    blockchain_repository::BlockchainRepository,
+    error::{FrameError, HandshakeError},
+    handshake::Handshake,
+    mempool_repository::MempoolRepository,
+    metrics,
+    node_identity::verify_signature,
+    peer_address::PeerAddress,
+    peer_links::PeerLink,
+    peer_message::{
+        Hello, MessageKind, PeerMessage, SyncQuery, SyncReply, SyncRequest, SyncResponse,
+    },
+    peer_score::Misbehaviour,
+    user_state_repository::UserStateRepository,
+};
+use crate::infrastructure::peer_codec::{read_message, write_message};
+use crate::infrastructure::secure_channel::{OpeningKey, SealingKey, establish, hello_payload};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
+use tokio::time::timeout;
+use tracing::{Instrument, debug, info, info_span, warn};
+
+// Скільки повідомлень може чекати на відправку одному піру; далі розсилка їх відкидає
+const SEND_QUEUE: usize = 256;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+const HELLO_TIMEOUT: Duration = Duration::from_secs(5);
+// Пір, що стільки не приймає даних, вважається відключеним
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+// Запас на id запиту й обгортку відповіді навколо масиву блоків
+const SYNC_RESPONSE_ENVELOPE: usize = 1024;
+
+pub async fn p2p_listener_task<B, M, U>(app_state: AppState<B, M, U>, listener: TcpListener)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    loop {
+        let (stream, remote) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(e) => {
+                warn!(error = %e, "Failed to accept P2P connection");
+                continue;
+            }
+        };
+        let app_state = app_state.clone();
+        tokio::spawn(async move {
+            if let Err(reason) = open_link(&app_state, stream, remote, None).await {
+                debug!(remote = %remote, reason = %reason, "Refused P2P connection");
+            }
+        });
+    }
+}
+
+// Відкриває P2P-з'єднання з піром, з яким щойно пройшов handshake по HTTP
+pub async fn connect_peer<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    address: &PeerAddress,
+    handshake: &Handshake,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    if app_state.links.lock().await.contains(&handshake.node_id) {
+        return;
+    }
+    let Some(p2p_port) = handshake.p2p_port else {
+        debug!(peer = %address, "Peer did not announce a P2P port");
+        return;
+    };
+    let connected = timeout(
+        CONNECT_TIMEOUT,
+        TcpStream::connect((address.host.as_str(), p2p_port)),
+    )
+    .await;
+    let stream = match connected {
+        Ok(Ok(stream)) => stream,
+        Ok(Err(e)) => {
+            warn!(peer = %address, p2p_port, error = %e, "Failed to open P2P connection");
+            return;
+        }
+        Err(_) => {
+            warn!(peer = %address, p2p_port, "Timed out opening P2P connection");
+            return;
+        }
+    };
+    let remote = match stream.peer_addr() {
+        Ok(remote) => remote,
+        Err(e) => {
+            warn!(peer = %address, error = %e, "P2P connection closed before hello");
+            return;
+        }
+    };
+    if let Err(reason) = open_link(app_state, stream, remote, Some(&handshake.node_id)).await {
+        warn!(peer = %address, reason = %reason, "P2P connection refused");
+    }
+}
+
+// Ставить повідомлення в чергу кожного підключеного піра й не чекає, поки його
+// надішлють: кожне з'єднання пише у свій сокет саме, тож повільний пір не затримує інших
+pub async fn broadcast<B, M, U>(app_state: &AppState<B, M, U>, message: PeerMessage)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    send_to_links(app_state, None, message).await;
+}
+
+// Розсилає власний блок чи голос; вузол запам'ятовує його хеш, щоб не обробити копію, яку повернуть сусіди
+pub async fn publish<B, M, U>(app_state: &AppState<B, M, U>, message: PeerMessage)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    if let Some(hash) = message.gossip_hash() {
+        app_state.seen.lock().await.insert(&hash, unix_now());
+    }
+    send_to_links(app_state, None, message).await;
+}
+
+// Передає повідомлення від from решті пірів, щоб воно дійшло й до вузлів, з якими автор не з'єднаний
+pub async fn relay<B, M, U>(app_state: &AppState<B, M, U>, from: &str, message: PeerMessage)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    app_state.metrics.inc(
+        metrics::GOSSIP_RELAYED,
+        &[("message", message.kind().as_str())],
+    );
+    send_to_links(app_state, Some(from), message).await;
+}
+
+// true, якщо блок чи голос із цим хешем вузол уже обробив; тоді його не варто й перевіряти
+pub async fn already_seen<B, M, U>(app_state: &AppState<B, M, U>, hash: &str, message: &str) -> bool
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    if !app_state.seen.lock().await.contains(hash, unix_now()) {
+        return false;
+    }
+    count_duplicate(app_state, hash, message);
+    true
+}
+
+// Запам'ятовує хеш уже перевіреного повідомлення, щоб підробка з тим самим хешем не
+// витіснила справжнє. false, якщо інша копія встигла пройти перевірку раніше
+pub async fn first_seen<B, M, U>(app_state: &AppState<B, M, U>, hash: &str, message: &str) -> bool
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    if app_state.seen.lock().await.insert(hash, unix_now()) {
+        return true;
+    }
+    count_duplicate(app_state, hash, message);
+    false
+}
+
+fn count_duplicate<B, M, U>(app_state: &AppState<B, M, U>, hash: &str, message: &str)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    debug!(hash = %hash, message, "Already seen, not handled again");
+    app_state
+        .metrics
+        .inc(metrics::GOSSIP_DUPLICATES, &[("message", message)]);
+}
+
+async fn send_to_links<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    except: Option<&str>,
+    message: PeerMessage,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let kind = message.kind().as_str();
+    let senders = app_state.links.lock().await.senders();
+    for (node_id, sender) in senders {
+        if except == Some(node_id.as_str()) {
+            continue;
+        }
+        let reason = match sender.try_send(message.clone()) {
+            Ok(()) => continue,
+            Err(TrySendError::Full(_)) => {
+                warn!(node_id = %node_id, message = kind, "Peer is not keeping up, message dropped");
+                "queue_full"
+            }
+            Err(TrySendError::Closed(_)) => "disconnected",
+        };
+        app_state.metrics.inc(
+            metrics::PEER_BROADCAST_FAILURES,
+            &[("message", kind), ("reason", reason)],
+        );
+    }
+}
+
+// Запит на синхронізацію до конкретного піра; відповідь приходить у його задачу читання
+pub async fn request<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    query: SyncQuery,
+) -> Result<SyncReply, String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let (id, reply, sender) = {
+        let mut links = app_state.links.lock().await;
+        let sender = links
+            .sender(node_id)
+            .ok_or_else(|| format!("no P2P connection to {}", node_id))?;
+        let (id, reply) = links.register_request(node_id);
+        (id, reply, sender)
+    };
+    let exchange = async {
+        sender
+            .send(PeerMessage::SyncRequest(SyncRequest { id, query }))
+            .await
+            .map_err(|_| format!("P2P connection to {} closed", node_id))?;
+        reply
+            .await
+            .map_err(|_| format!("P2P connection to {} closed", node_id))
+    };
+    match timeout(REQUEST_TIMEOUT, exchange).await {
+        Ok(result) => result,
+        Err(_) => {
+            app_state.links.lock().await.cancel_request(id);
+            Err(format!("{} did not answer in time", node_id))
+        }
+    }
+}
+
+// Домовляється з піром про ключі шифрування, обмінюється підписаними hello і, якщо пір
+// з нашої мережі й володіє названим ключем ідентичності, запускає задачі читання й запису.
+// expected — id вузла, до якого підключаємося ми; None для вхідних з'єднань
+async fn open_link<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    stream: TcpStream,
+    remote: SocketAddr,
+    expected: Option<&str>,
+) -> Result<(), String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let _ = stream.set_nodelay(true);
+    let (mut reader, mut writer) = stream.into_split();
+    let local = local_handshake(app_state).await;
+    let outbound = expected.is_some();
+
+    let session = timeout(HELLO_TIMEOUT, establish(&mut reader, &mut writer, outbound))
+        .await
+        .map_err(|_| "timed out agreeing on keys".to_string())?
+        .map_err(|e| e.to_string())?;
+    let (mut sealing, mut opening) = (session.sealing, session.opening);
+
+    // Обидві сторони надсилають hello одразу, не чекаючи на чуже
+    let signature = app_state
+        .identity
+        .sign(&hello_payload(&session.transcript, outbound));
+    let hello = PeerMessage::Hello(Hello {
+        handshake: local.clone(),
+        signature,
+    });
+    timeout(
+        HELLO_TIMEOUT,
+        write_message(&mut writer, &mut sealing, &hello),
+    )
+    .await
+    .map_err(|_| "timed out sending hello".to_string())?
+    .map_err(|e| e.to_string())?;
+    let hello = match timeout(HELLO_TIMEOUT, read_message(&mut reader, &mut opening)).await {
+        Ok(Ok(PeerMessage::Hello(hello))) => hello,
+        Ok(Ok(other)) => return Err(format!("expected hello, got {}", other.kind().as_str())),
+        Ok(Err(e)) => return Err(e.to_string()),
+        Err(_) => return Err("timed out waiting for hello".to_string()),
+    };
+    hello
+        .handshake
+        .check(
+            &local,
+            &app_state.config.keys.shared_key,
+            &app_state.config.consensus.validator_keys,
+        )
+        .map_err(|e| e.to_string())?;
+    // Підпис доводить, що на тому боці саме власник ключа з handshake, а не вузол,
+    // що переслав чужий handshake
+    if !verify_signature(
+        &hello.handshake.identity_key,
+        &hello_payload(&session.transcript, !outbound),
+        &hello.signature,
+    ) {
+        return Err(format!(
+            "hello of {} is not signed with its identity key",
+            hello.handshake.node_id
+        ));
+    }
+    let hello = hello.handshake;
+    if let Some(expected) = expected
+        && hello.node_id != expected
+    {
+        return Err(HandshakeError::NodeId {
+            expected: expected.to_string(),
+            actual: hello.node_id,
+        }
+        .to_string());
+    }
+    if app_state
+        .scores
+        .lock()
+        .await
+        .is_banned(&hello.node_id, unix_now())
+    {
+        return Err(format!("node {} is banned", hello.node_id));
+    }
+
+    let node_id = hello.node_id.clone();
+    let node = &app_state.config.node;
+    let (sender, queue) = mpsc::channel(SEND_QUEUE);
+    let mut links = app_state.links.lock().await;
+    if !outbound && !links.contains(&node_id) && links.inbound_count() >= node.max_inbound_peers {
+        app_state.metrics.inc(
+            metrics::PEER_HANDSHAKE_FAILURES,
+            &[("reason", "inbound_limit")],
+        );
+        return Err("connection limit reached".to_string());
+    }
+    // Ключі закріплено лише за валідаторами, тож id іншого вузла належить ключу, з яким
+    // він уже підключений: нове з'єднання з іншим ключем не витіснить справжнього
+    if links
+        .identity_key(&node_id)
+        .is_some_and(|key| !key.eq_ignore_ascii_case(&hello.identity_key))
+    {
+        app_state.metrics.inc(
+            metrics::PEER_HANDSHAKE_FAILURES,
+            &[("reason", "identity_key")],
+        );
+        return Err(format!(
+            "node {} is already connected with another identity key",
+            node_id
+        ));
+    }
+    // Задача читання запускається під замком, тож не може закрити з'єднання раніше, ніж його додано
+    let connection_id = links.next_connection_id();
+    let (shutdown, shutdown_signal) = oneshot::channel();
+    tokio::spawn(read_loop(
+        app_state.clone(),
+        node_id.clone(),
+        connection_id,
+        reader,
+        opening,
+        sender.clone(),
+        shutdown_signal,
+    ));
+    let link = PeerLink {
+        node_id: node_id.clone(),
+        connection_id,
+        identity_key: hello.identity_key.clone(),
+        outbound,
+        sender,
+        shutdown,
+    };
+    // Зайве з'єднання відкидається разом із сигналом, і задача читання завершується
+    if !links.insert(link, &local.node_id) {
+        debug!(node_id = %node_id, remote = %remote, "Already connected, dropping the duplicate P2P connection");
+        return Ok(());
+    }
+    app_state
+        .metrics
+        .set(metrics::PEER_LINKS, &[], links.len() as f64);
+    drop(links);
+    if !outbound {
+        record_inbound(app_state, hello).await;
+    }
+
+    info!(node_id = %node_id, remote = %remote, outbound, "P2P connection opened");
+    tokio::spawn(write_loop(
+        app_state.clone(),
+        node_id,
+        connection_id,
+        writer,
+        sealing,
+        queue,
+    ));
+    Ok(())
+}
+
+// Hello підписаний ключем вузла, тож лише після нього пір займає вхідний слот,
+// а оголошена ним адреса потрапляє в книгу адрес, щоб підключитися до нього у відповідь
+async fn record_inbound<B, M, U>(app_state: &AppState<B, M, U>, handshake: Handshake)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let node_id = handshake.node_id.clone();
+    let listen_address = handshake.listen_address.clone();
+    {
+        let mut registry = app_state.peers.lock().await;
+        if !registry.is_known(&node_id) {
+            info!(
+                node_id = %node_id,
+                best_height = handshake.best_height,
+                "Accepted inbound peer"
+            );
+        }
+        registry.record_inbound(handshake);
+    }
+    if let Some(mut address) = listen_address
+        && address.node_id.as_ref().is_none_or(|id| *id == node_id)
+    {
+        address.node_id = Some(node_id.clone());
+        if app_state
+            .address_book
+            .lock()
+            .await
+            .heard_from(&address, &node_id)
+        {
+            debug!(node_id = %node_id, address = %address, "Learned peer address from hello");
+        }
+    }
+}
+
+async fn read_loop<B, M, U>(
+    app_state: AppState<B, M, U>,
+    node_id: String,
+    connection_id: u64,
+    mut reader: OwnedReadHalf,
+    mut opening: OpeningKey,
+    replies: mpsc::Sender<PeerMessage>,
+    mut shutdown: oneshot::Receiver<()>,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Наступний кадр читається лише після обробки попереднього, тож пір,
+    // що надсилає швидше, ніж ми встигаємо, упирається в TCP-вікно
+    let reason = loop {
+        let read = tokio::select! {
+            read = read_message(&mut reader, &mut opening) => read,
+            // З'єднання вже прибрали з PeerLinks
+            _ = &mut shutdown => return,
+        };
+        match read {
+            Ok(message) => {
+                app_state.metrics.inc(
+                    metrics::PEER_MESSAGES,
+                    &[
+                        ("direction", "received"),
+                        ("message", message.kind().as_str()),
+                    ],
+                );
+                handle_message(&app_state, &node_id, &replies, message).await;
+            }
+            Err(e @ FrameError::Malformed { .. }) => {
+                debug!(node_id = %node_id, reason = %e, "Peer sent a malformed message");
+                penalize_peer(&app_state, &node_id, Misbehaviour::MalformedPayload).await;
+            }
+            Err(e @ FrameError::UnknownKind(_)) => {
+                penalize_peer(&app_state, &node_id, Misbehaviour::MalformedPayload).await;
+                break e.to_string();
+            }
+            Err(e @ FrameError::TooLarge { .. }) => {
+                penalize_peer(&app_state, &node_id, Misbehaviour::OversizedPayload).await;
+                break e.to_string();
+            }
+            Err(FrameError::Closed) => break "closed by peer".to_string(),
+            Err(e) => break e.to_string(),
+        }
+    };
+    close_link(&app_state, &node_id, connection_id, &reason).await;
+}
+
+async fn write_loop<B, M, U>(
+    app_state: AppState<B, M, U>,
+    node_id: String,
+    connection_id: u64,
+    mut writer: OwnedWriteHalf,
+    mut sealing: SealingKey,
+    mut queue: mpsc::Receiver<PeerMessage>,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Черга закривається, коли з'єднання прибирають з PeerLinks
+    while let Some(message) = queue.recv().await {
+        let reason = match timeout(
+            WRITE_TIMEOUT,
+            write_message(&mut writer, &mut sealing, &message),
+        )
+        .await
+        {
+            Ok(Ok(())) => {
+                app_state.metrics.inc(
+                    metrics::PEER_MESSAGES,
+                    &[("direction", "sent"), ("message", message.kind().as_str())],
+                );
+                continue;
+            }
+            // Кадр не надіслано, і з'єднання лишається цілим
+            Ok(Err(e @ FrameError::TooLarge { .. })) => {
+                warn!(node_id = %node_id, reason = %e, "Dropped an outgoing P2P message");
+                continue;
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => "write timed out".to_string(),
+        };
+        close_link(&app_state, &node_id, connection_id, &reason).await;
+        return;
+    }
+}
+
+async fn handle_message<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    replies: &mpsc::Sender<PeerMessage>,
+    message: PeerMessage,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    match message {
+        PeerMessage::Hello(_) => {
+            debug!(node_id = %node_id, "Peer repeated its hello");
+            penalize_peer(app_state, node_id, Misbehaviour::MalformedPayload).await;
+        }
+        PeerMessage::Block(gossip) => {
+            let span = info_span!(
+                "block",
+                height = gossip.item.header.height,
+                block_hash = %gossip.item.hash,
+                proposer = %gossip.item.header.proposer_id,
+                sender = %node_id
+            );
+            accept_block(app_state, node_id, gossip)
+                .instrument(span)
+                .await;
+        }
+        PeerMessage::Vote(gossip) => accept_vote(app_state, node_id, gossip).await,
+        PeerMessage::TxAnnounce(transaction) => {
+            let tx_id = transaction.id;
+            match admit_announced_transaction(
+                app_state.blockchain_repo.clone(),
+                app_state.user_state_repo.clone(),
+                app_state.mempool_repo.clone(),
+                &app_state.events,
+                transaction.clone(),
+            )
+            .await
+            {
+                // Мемпул сам відкидає повтори, тож окремий кеш для транзакцій не потрібен
+                Ok(()) => {
+                    debug!(tx_id = %tx_id, node_id = %node_id, "Announced transaction admitted");
+                    relay(app_state, node_id, PeerMessage::TxAnnounce(transaction)).await;
+                }
+                Err(TransactionRejection::AlreadyExists) => {}
+                // Баланси вузлів можуть розходитися до наступного блоку, тож це не порушення
+                Err(rejection) => {
+                    debug!(tx_id = %tx_id, node_id = %node_id, reason = %rejection, "Announced transaction refused")
+                }
+            }
+        }
+        PeerMessage::SyncRequest(request) => {
+            let reply = answer_sync_query(app_state, request.query).await;
+            let response = PeerMessage::SyncResponse(SyncResponse {
+                id: request.id,
+                reply,
+            });
+            // Чекаємо на місце в черзі: відповідь на запит не можна просто відкинути
+            let _ = replies.send(response).await;
+        }
+        PeerMessage::SyncResponse(response) => {
+            let completed =
+                app_state
+                    .links
+                    .lock()
+                    .await
+                    .complete_request(response.id, node_id, response.reply);
+            if !completed {
+                debug!(node_id = %node_id, request_id = response.id, "Sync response nobody waits for");
+            }
+        }
+    }
+}
+
+async fn answer_sync_query<B, M, U>(app_state: &AppState<B, M, U>, query: SyncQuery) -> SyncReply
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    match query {
+        SyncQuery::Headers { locator, from } => {
+            let repo = app_state.blockchain_repo.lock().await;
+            SyncReply::Headers {
+                headers: headers_after(&*repo, &locator, from, MAX_HEADERS_PER_REQUEST).await,
+            }
+        }
+        SyncQuery::Blocks { from, to } => SyncReply::Blocks {
+            blocks: fit_within(
+                app_state
+                    .blockchain_repo
+                    .lock()
+                    .await
+                    .get_range(
+                        from,
+                        to.min(from.saturating_add(MAX_BLOCKS_PER_REQUEST - 1)),
+                    )
+                    .await,
+                MessageKind::SyncResponse.max_body_bytes() - SYNC_RESPONSE_ENVELOPE,
+            ),
+        },
+        SyncQuery::Addresses => SyncReply::Addresses {
+            addresses: shared_addresses(app_state).await,
+        },
+    }
+}
+
+// 500 блоків по 2 МіБ не вмістяться в один кадр, тож відповідь обрізається за розміром,
+// а решту запитувач дозавантажує наступним запитом. Перший блок вміщується завжди,
+// бо блок не більший за власний ліміт кадру
+fn fit_within(blocks: Vec<Block>, budget: usize) -> Vec<Block> {
+    let mut used = 0;
+    let mut fitting = Vec::new();
+    for block in blocks {
+        // Плюс кома між елементами масиву
+        let size = serde_json::to_vec(&block).map_or(usize::MAX, |body| body.len() + 1);
+        used = size.saturating_add(used);
+        if used > budget {
+            break;
+        }
+        fitting.push(block);
+    }
+    fitting
+}
+
+async fn close_link<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    connection_id: u64,
+    reason: &str,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut links = app_state.links.lock().await;
+    if links.remove(node_id, Some(connection_id)) {
+        app_state
+            .metrics
+            .set(metrics::PEER_LINKS, &[], links.len() as f64);
+        drop(links);
+        info!(node_id = %node_id, reason = %reason, "P2P connection closed");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{answer_sync_query, fit_within, handle_message, publish};
+    use crate::blockchain::use_cases::ban_peers::unix_now;
+    use crate::domain::app_state::testing::{TestAppState, app_state, child_block, transfer};
+    use crate::domain::block::Block;
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::domain::chain_event::ChainEvent;
+    use crate::domain::config::Config;
+    use crate::domain::gossip::{GOSSIP_TTL, Gossip};
+    use crate::domain::node_identity::NodeIdentity;
+    use crate::domain::peer_links::PeerLink;
+    use crate::domain::peer_message::{PeerMessage, SyncQuery, SyncReply};
+    use crate::domain::user_state_repository::UserStateRepository;
+    use tokio::sync::broadcast::Receiver;
+    use tokio::sync::{mpsc, oneshot};
+
+    // Вузли в пам'яті, з'єднані каналами PeerLinks так, як їх з'єднує open_link;
+    // повідомлення з каналів обробляє той самий handle_message, що й задача читання
+    struct Network {
+        nodes: Vec<TestAppState>,
+        events: Vec<Receiver<ChainEvent>>,
+        // Від кого, кому і куди відправник кладе повідомлення для отримувача
+        wires: Vec<(usize, usize, mpsc::Receiver<PeerMessage>)>,
+        block_frames: usize,
+    }
+
+    impl Network {
+        async fn line(nodes: usize) -> Self {
+            Self::new(nodes, (1..nodes).map(|i| (i - 1, i)).collect()).await
+        }
+
+        async fn ring(nodes: usize) -> Self {
+            Self::new(nodes, (0..nodes).map(|i| (i, (i + 1) % nodes)).collect()).await
+        }
+
+        // Усі вузли — валідатори v1..vN, з'єднані лише вздовж `edges`
+        async fn new(nodes: usize, edges: Vec<(usize, usize)>) -> Self {
+            let ids: Vec<String> = (1..=nodes).map(|i| format!("v{}", i)).collect();
+            let validators: Vec<&str> = ids.iter().map(String::as_str).collect();
+            let mut network = Self {
+                nodes: Vec::new(),
+                events: Vec::new(),
+                wires: Vec::new(),
+                block_frames: 0,
+            };
+            for id in &ids {
+                let node = app_state(Config::for_tests(id, &validators)).await;
+                network.events.push(node.events.subscribe());
+                network.nodes.push(node);
+            }
+            for (a, b) in edges {
+                for (from, to) in [(a, b), (b, a)] {
+                    let (sender, receiver) = mpsc::channel(1024);
+                    let (shutdown, _) = oneshot::channel();
+                    let mut links = network.nodes[from].links.lock().await;
+                    let link = PeerLink {
+                        node_id: ids[to].clone(),
+                        connection_id: links.next_connection_id(),
+                        identity_key: NodeIdentity::for_tests(&ids[to]).public_key(),
+                        outbound: from < to,
+                        sender,
+                        shutdown,
+                    };
+                    assert!(links.insert(link, &ids[from]));
+                    drop(links);
+                    network.wires.push((from, to, receiver));
+                }
+            }
+            network
+        }
+
+        fn id(&self, node: usize) -> String {
+            self.nodes[node].config.node.id.clone()
+        }
+
+        // Доставляє повідомлення хвилями: усе, що вже лежить у каналах, а потім те, що
+        // вузли розіслали у відповідь, доки канали не спорожніють
+        async fn run(&mut self) {
+            let (replies, _) = mpsc::channel(1);
+            loop {
+                let mut wave = Vec::new();
+                for (from, to, receiver) in &mut self.wires {
+                    while let Ok(message) = receiver.try_recv() {
+                        wave.push((*from, *to, message));
+                    }
+                }
+                if wave.is_empty() {
+                    break;
+                }
+                for (from, to, message) in wave {
+                    if matches!(message, PeerMessage::Block(_)) {
+                        self.block_frames += 1;
+                    }
+                    handle_message(&self.nodes[to], &self.id(from), &replies, message).await;
+                }
+            }
+        }
+
+        // Блок `proposer` поверх генезису, як його розіслав би лідер слоту
+        async fn block(&self, proposer: usize) -> Block {
+            let node = &self.nodes[proposer];
+            let genesis = node
+                .blockchain_repo
+                .lock()
+                .await
+                .get_last_block()
+                .await
+                .unwrap();
+            let mut balances = node.user_state_repo.lock().await.get_balances().clone();
+            child_block(
+                &genesis,
+                &self.id(proposer),
+                vec![transfer(2, 10.0)],
+                &mut balances,
+            )
+        }
+
+        async fn tip(&self, node: usize) -> String {
+            let tip = self.nodes[node]
+                .blockchain_repo
+                .lock()
+                .await
+                .get_last_block()
+                .await;
+            tip.unwrap().hash
+        }
+
+        // Скільки разів вузол узявся обробляти блок, а не відкинув його як повтор
+        fn proposed(&mut self, node: usize) -> usize {
+            std::iter::from_fn(|| self.events[node].try_recv().ok())
+                .filter(|event| matches!(event, ChainEvent::BlockProposed { .. }))
+                .count()
+        }
+    }
+
+    #[tokio::test]
+    async fn address_query_lists_the_node_itself_first() {
+        let node = app_state(Config::for_tests("v1", &["v1"])).await;
+
+        match answer_sync_query(&node, SyncQuery::Addresses).await {
+            SyncReply::Addresses { addresses } => assert_eq!(
+                addresses,
+                vec![node.config.node.advertise_address.to_string()]
+            ),
+            _ => panic!("expected addresses"),
+        }
+    }
+
+    #[tokio::test]
+    async fn block_reply_stops_before_the_frame_limit() {
+        let node = app_state(Config::for_tests("v1", &["v1"])).await;
+        let mut parent = node
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let mut balances = node.user_state_repo.lock().await.get_balances().clone();
+        let mut blocks = Vec::new();
+        for _ in 0..3 {
+            parent = child_block(&parent, "v1", vec![transfer(2, 1.0)], &mut balances);
+            blocks.push(parent.clone());
+        }
+        let size = |block: &Block| serde_json::to_vec(block).unwrap().len() + 1;
+        let two = size(&blocks[0]) + size(&blocks[1]);
+
+        assert_eq!(fit_within(blocks.clone(), two).len(), 2);
+        assert_eq!(fit_within(blocks.clone(), two - 1).len(), 1);
+        assert_eq!(fit_within(blocks, usize::MAX).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn block_and_votes_cross_a_line_of_ttl_hops() {
+        let mut network = Network::line(GOSSIP_TTL as usize + 1).await;
+        let block = network.block(0).await;
+
+        publish(
+            &network.nodes[0],
+            PeerMessage::Block(Gossip::new(block.clone())),
+        )
+        .await;
+        network.run().await;
+
+        // Кожне ребро лінії блок проходить лише раз
+        assert_eq!(network.block_frames, GOSSIP_TTL as usize);
+        for node in 1..network.nodes.len() {
+            assert_eq!(network.tip(node).await, block.hash, "node {}", node);
+            assert_eq!(network.proposed(node), 1, "node {}", node);
+            // Голоси, пересилаючись в обидва боки, доводять блок до кворуму на кожному вузлі
+            assert_eq!(
+                network.nodes[node]
+                    .consensus_status
+                    .lock()
+                    .await
+                    .finalized_height,
+                Some(1),
+                "node {}",
+                node
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn block_reaches_every_node_of_a_ring_once() {
+        let mut network = Network::ring(10).await;
+        let block = network.block(3).await;
+
+        publish(
+            &network.nodes[3],
+            PeerMessage::Block(Gossip::new(block.clone())),
+        )
+        .await;
+        network.run().await;
+
+        // Дві хвилі зустрічаються на протилежному боці кільця, і там дублікати відкидаються
+        assert_eq!(network.block_frames, 11);
+        for node in (0..10).filter(|&node| node != 3) {
+            assert_eq!(network.tip(node).await, block.hash, "node {}", node);
+            assert_eq!(network.proposed(node), 1, "node {}", node);
+        }
+    }
+
+    #[tokio::test]
+    async fn ttl_limits_how_far_a_block_travels() {
+        let mut network = Network::line(GOSSIP_TTL as usize + 3).await;
+        let genesis = network.tip(0).await;
+        let block = network.block(0).await;
+
+        publish(
+            &network.nodes[0],
+            PeerMessage::Block(Gossip::new(block.clone())),
+        )
+        .await;
+        network.run().await;
+
+        for node in 1..=GOSSIP_TTL as usize {
+            assert_eq!(network.tip(node).await, block.hash, "node {}", node);
+        }
+        for node in GOSSIP_TTL as usize + 1..network.nodes.len() {
+            assert_eq!(network.tip(node).await, genesis, "node {}", node);
+            assert_eq!(network.proposed(node), 0, "node {}", node);
+        }
+    }
+
+    #[tokio::test]
+    async fn forged_copy_does_not_shadow_the_real_block() {
+        let mut network = Network::line(3).await;
+        let block = network.block(0).await;
+        // Той самий хеш, але підпис не сходиться
+        let mut forged = block.clone();
+        forged.signature = "00".repeat(32);
+
+        publish(&network.nodes[0], PeerMessage::Block(Gossip::new(forged))).await;
+        publish(
+            &network.nodes[0],
+            PeerMessage::Block(Gossip::new(block.clone())),
+        )
+        .await;
+        network.run().await;
+
+        // v2 відкинув підробку, не запам'ятавши її хеш, тож справжній блок прийняв і передав далі
+        assert_eq!(network.tip(1).await, block.hash);
+        assert_eq!(network.tip(2).await, block.hash);
+        let score = network.nodes[1].scores.lock().await.get("v1", unix_now());
+        assert_eq!(
+            score.and_then(|score| score.last_offence),
+            Some("invalid_signature")
+        );
+        assert!(
+            network.nodes[2]
+                .scores
+                .lock()
+                .await
+                .get("v2", unix_now())
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn block_forged_by_another_validator_is_neither_accepted_nor_relayed() {
+        let mut network = Network::line(3).await;
+        let genesis = network.tip(0).await;
+        // v1 має спільний ключ і видає блок за блок v3, але підписати його може лише своїм ключем
+        let mut forged = network.block(2).await;
+        forged.sign_as_proposer(&NodeIdentity::for_tests(&network.id(0)));
+
+        publish(&network.nodes[0], PeerMessage::Block(Gossip::new(forged))).await;
+        network.run().await;
+
+        assert_eq!(network.tip(1).await, genesis);
+        assert_eq!(network.tip(2).await, genesis);
+        assert_eq!(network.proposed(1), 0);
+        assert_eq!(network.proposed(2), 0);
+        let score = network.nodes[1].scores.lock().await.get("v1", unix_now());
+        assert_eq!(
+            score.and_then(|score| score.last_offence),
+            Some("forged_block")
+        );
+    }
+}
@@ -1,13 +1,13 @@
 use crate::{
-    blockchain::use_cases::create_new_block::create_new_block,
+    blockchain::use_cases::{create_new_block::create_new_block, peer_transport::publish},
     domain::{
-        app_state::AppState, block::Block, blockchain_repository::BlockchainRepository,
-        mempool_repository::MempoolRepository, transaction::Transaction,
-        user_state_repository::UserStateRepository,
+        app_state::AppState, blockchain_repository::BlockchainRepository, chain_event::ChainEvent,
+        gossip::Gossip, mempool_repository::MempoolRepository, metrics, peer_message::PeerMessage,
+        transaction::Transaction, user_state_repository::UserStateRepository,
     },
 };
-use reqwest::Client;
-use std::{collections::VecDeque, time::Duration};
+use std::collections::VecDeque;
+use tracing::{Instrument, debug, info, info_span, warn};
 
 pub async fn pos_consensus_loop<B, M, U>(app_state: AppState<B, M, U>)
 where
@@ -15,26 +15,20 @@ where
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    let slot_duration = Duration::from_secs(5);
+    let slot_duration = app_state.config.consensus.slot_duration;
     let mut current_slot: u64 = 0;
-    let http_client = Client::new();
 
-    // vec!["v1", "v2", "v3"],
-    let (my_id, peer_addresses, shared_key, mut validator_list) = {
+    let (my_id, shared_key, mut validator_list) = {
         let node = app_state.node.lock().await;
         (
             node.id.clone(),
-            node.peers.clone(),
-            app_state.shared_key.clone(),
+            app_state.config.keys.shared_key.clone(),
             node.validator_ids.clone(),
         )
     };
-    println!("[PoS ]: Id: {}, Validators: {:?}", my_id, validator_list);
     validator_list.sort();
-
     let total_validators = validator_list.len();
-
-    println!("[PoS ]: Id: {}, Validators: {:?}", my_id, validator_list);
+    info!(node_id = %my_id, validators = ?validator_list, "Starting consensus loop");
 
     loop {
         tokio::time::sleep(slot_duration).await;
@@ -42,76 +36,100 @@ where
 
         let leader_index = (current_slot - 1) as usize % total_validators;
         let leader_id: String = validator_list[leader_index].clone();
-        println!("[Slot {}]: Leader - {}", current_slot, leader_id);
+        {
+            let mut status = app_state.consensus_status.lock().await;
+            status.slot = current_slot;
+            status.leader = Some(leader_id.clone());
+        }
+        let span = info_span!("slot", slot = current_slot, leader = %leader_id);
 
         if my_id == leader_id {
-            println!(
-                "[Slot {}]: ✅ I'm the LEADER. Forming a block...",
-                current_slot
-            );
-            let transactions_deque: VecDeque<Transaction> = {
-                let mut mempool = app_state.mempool_repo.lock().await;
-                mempool.drain_transactions()
-            };
-
-            let transactions_vec: Vec<Transaction> = transactions_deque.into_iter().collect();
-            let mut user_state = app_state.user_state_repo.lock().await;
-            let mut valid_transactions: Vec<Transaction> = Vec::new();
+            lead_slot(&app_state, &my_id, &shared_key)
+                .instrument(span)
+                .await;
+        } else {
+            let _entered = span.enter();
+            debug!("Waiting for a block from the leader");
+        }
+    }
+}
 
-            for tx in transactions_vec {
-                if user_state.apply_transaction(&tx) {
-                    valid_transactions.push(tx);
-                } else {
-                    println!(
-                        "[Slot {}]: Transaction {} rejected (insufficient funds).",
-                        current_slot, tx.id
-                    );
-                }
-            }
+// Слот, у якому цей вузол — лідер: пакує мемпул у блок і розсилає його пірам
+async fn lead_slot<B, M, U>(app_state: &AppState<B, M, U>, my_id: &str, shared_key: &str)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    debug!("This node is the leader, forming a block");
+    let transactions_deque: VecDeque<Transaction> = {
+        let mut mempool = app_state.mempool_repo.lock().await;
+        mempool.drain_transactions()
+    };
 
-            if valid_transactions.is_empty() {
-                println!("[Slot {}]: Mempool is empty. Skipping slot.", current_slot);
-                continue;
-            }
-            println!(
-                "[Slot {}]: 📦 Packed {} valid transactions.",
-                current_slot,
-                valid_transactions.len()
-            );
-            let new_block: Block = create_new_block(
-                app_state.blockchain_repo.clone(),
-                valid_transactions,
-                &my_id,
-                &shared_key,
-            )
-            .await;
-            {
-                let mut pending_blocks = app_state.pending_blocks.lock().await;
-                pending_blocks.insert(new_block.hash.clone(), new_block.clone());
-            }
-            {
-                let mut vote_counts = app_state.vote_counts.lock().await;
-                let voters = vote_counts
-                    .entry(new_block.hash.clone())
-                    .or_insert_with(Vec::new);
-                voters.push(my_id.clone());
+    let transactions_vec: Vec<Transaction> = transactions_deque.into_iter().collect();
+    let mut user_state = app_state.user_state_repo.lock().await;
+    let mut valid_transactions: Vec<Transaction> = Vec::new();
 
-                println!("[PoS]: 🗳️  Я (Лідер) проголосував за свій блок.");
-            }
-            println!(
-                "[Slot {}]:  Broadcasting block #{} to peers...",
-                current_slot, new_block.header.height
-            );
-            for peer_addr in &peer_addresses {
-                let target_url = format!("http://localhost:{}/block", peer_addr);
-                println!("[Slot {}]: -> sending to {}", current_slot, target_url);
-                let _ = http_client.post(&target_url).json(&new_block).send().await;
+    for tx in transactions_vec {
+        match user_state.apply_transaction(&tx) {
+            Ok(()) => valid_transactions.push(tx),
+            Err(reason) => {
+                info!(tx_id = %tx.id, reason = %reason, "Transaction dropped from the block");
+                app_state.events.publish(ChainEvent::TransactionDropped {
+                    transaction_id: tx.id,
+                    reason: reason.to_string(),
+                });
             }
-        } else {
-            println!(
-                "[Slot {}]:  I'm a VALIDATOR. Waiting for block from {}.",
-                current_slot, leader_id
-            );
         }
     }
+
+    if valid_transactions.is_empty() {
+        debug!("Mempool is empty, skipping slot");
+        app_state
+            .metrics
+            .inc(metrics::SLOT_MISSES, &[("reason", "empty_mempool")]);
+        return;
+    }
+    let transactions = valid_transactions.len();
+    let state_root = user_state.state_root();
+    drop(user_state);
+    let Some(new_block) = create_new_block(
+        app_state.blockchain_repo.clone(),
+        valid_transactions,
+        my_id,
+        state_root,
+        shared_key,
+        &app_state.identity,
+    )
+    .await
+    else {
+        warn!("Local chain is empty, no block created");
+        app_state
+            .metrics
+            .inc(metrics::SLOT_MISSES, &[("reason", "empty_chain")]);
+        return;
+    };
+    info!(
+        height = new_block.header.height,
+        block_hash = %new_block.hash,
+        transactions,
+        "Block proposed"
+    );
+    app_state.metrics.block_proposed(&new_block.hash);
+    app_state.events.publish(ChainEvent::proposed(&new_block));
+    {
+        let mut pending_blocks = app_state.pending_blocks.lock().await;
+        pending_blocks.insert(new_block.hash.clone(), new_block.clone());
+    }
+    {
+        // Лідер голосує за власний блок
+        let mut vote_counts = app_state.vote_counts.lock().await;
+        let voters = vote_counts
+            .entry(new_block.hash.clone())
+            .or_insert_with(Vec::new);
+        voters.push(my_id.to_string());
+    }
+    debug!(height = new_block.header.height, "Sending block to peers");
+    publish(app_state, PeerMessage::Block(Gossip::new(new_block))).await;
 }
@@ -0,0 +1,57 @@
+use crate::domain::{block::Block, error::BlockError, user_state_repository::UserStateRepository};
+use crate::infrastructure::in_memory_user_state_repository::InMemoryUserStateRepository;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+// Відновлює стан з найближчого снапшоту на `chain` і доганяє лише наступні блоки;
+// без придатного снапшоту перебудовує стан з генезису
+pub async fn restore_user_state<U>(user_state: &mut U, chain: &[Block]) -> Result<(), BlockError>
+where
+    U: UserStateRepository + ?Sized,
+{
+    match user_state.restore_snapshot(chain) {
+        Some(height) => {
+            let tail: Vec<Block> = chain
+                .iter()
+                .filter(|b| b.header.height > height)
+                .cloned()
+                .collect();
+            info!(
+                height,
+                blocks = tail.len(),
+                "Restored snapshot, replaying blocks"
+            );
+            user_state.replay_blocks(&tail).await
+        }
+        None => {
+            // Блоків до снапшоту в ланцюгу немає, тож перебудова дала б хибні баланси
+            if let Some(first) = chain.first()
+                && first.header.height > 0
+            {
+                return Err(BlockError::MissingState {
+                    height: first.header.height,
+                });
+            }
+            info!(blocks = chain.len(), "No usable snapshot, rebuilding state");
+            user_state.rebuild_from_blocks(chain).await
+        }
+    }
+}
+
+// Баланси на вершині `chain`, пораховані на копії зі збереженого снапшоту; стан
+// `user_state` не змінюється
+pub async fn restore_on_copy<U>(
+    user_state: &U,
+    chain: &[Block],
+) -> Result<HashMap<Uuid, f64>, BlockError>
+where
+    U: UserStateRepository + ?Sized,
+{
+    let mut scratch = InMemoryUserStateRepository::with_snapshot_interval(u64::MAX);
+    if let Some(snapshot) = user_state.snapshot_on(chain) {
+        scratch.load_snapshots(vec![snapshot]);
+    }
+    restore_user_state(&mut scratch, chain).await?;
+    Ok(scratch.get_balances().clone())
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::domain::{
+    blockchain_repository::BlockchainRepository, chain_event::ChainEvent, error::TransactionError,
+    event_bus::EventBus, mempool_repository::MempoolRepository,
+    user_state_repository::UserStateRepository,
+};
+
+// Викидає з мемпулу транзакції, які вже є в ланцюгу або більше не покриваються балансом
+pub async fn revalidate_mempool<B, M, U>(
+    blockchain_repository: Arc<Mutex<B>>,
+    mempool_repository: Arc<Mutex<M>>,
+    user_state_repository: Arc<Mutex<U>>,
+    events: &EventBus,
+) where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let blockchain = blockchain_repository.lock().await;
+    let user_state = user_state_repository.lock().await;
+    let mut mempool = mempool_repository.lock().await;
+    revalidate_pending(&*blockchain, &*user_state, &mut *mempool, events).await;
+}
+
+// Те саме під уже взятими замками, напр. після реорганізації ланцюга
+pub async fn revalidate_pending<B, M, U>(
+    blockchain: &B,
+    user_state: &U,
+    mempool: &mut M,
+    events: &EventBus,
+) where
+    B: BlockchainRepository + ?Sized,
+    M: MempoolRepository + ?Sized,
+    U: UserStateRepository + ?Sized,
+{
+    let mut balances: HashMap<Uuid, f64> = user_state.get_balances().clone();
+    let pending = mempool.drain_transactions();
+    let total = pending.len();
+
+    for tx in pending {
+        if mempool.check_exists_by_id(&tx.id)
+            || blockchain.get_transaction_location(&tx.id).await.is_some()
+        {
+            continue;
+        }
+        let sender_balance = balances.get(&tx.from).copied().unwrap_or(0.0);
+        if sender_balance < tx.amount {
+            info!(tx_id = %tx.id, from = %tx.from, "Dropping transaction with insufficient funds");
+            let reason = TransactionError::InsufficientFunds {
+                address: tx.from,
+                balance: sender_balance,
+                amount: tx.amount,
+            };
+            events.publish(ChainEvent::TransactionDropped {
+                transaction_id: tx.id,
+                reason: reason.to_string(),
+            });
+            continue;
+        }
+        balances.insert(tx.from, sender_balance - tx.amount);
+        *balances.entry(tx.to).or_insert(0.0) += tx.amount;
+        mempool.add_transaction(tx);
+    }
+
+    info!(
+        kept = mempool.get_all_transactions().len(),
+        total, "Revalidated pending transactions"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::revalidate_mempool;
+    use crate::domain::app_state::testing::{app_state, transfer};
+    use crate::domain::chain_event::ChainEvent;
+    use crate::domain::config::Config;
+    use crate::domain::mempool_repository::MempoolRepository;
+
+    #[tokio::test]
+    async fn dropped_transactions_are_published() {
+        let app_state = app_state(Config::for_tests("v1", &["v1"])).await;
+        let mut events = app_state.events.subscribe();
+        let covered = transfer(2, 1.0);
+        let uncovered = transfer(3, f64::MAX);
+        {
+            let mut mempool = app_state.mempool_repo.lock().await;
+            mempool.add_transaction(covered.clone());
+            mempool.add_transaction(uncovered.clone());
+        }
+
+        revalidate_mempool(
+            app_state.blockchain_repo.clone(),
+            app_state.mempool_repo.clone(),
+            app_state.user_state_repo.clone(),
+            &app_state.events,
+        )
+        .await;
+
+        assert!(matches!(
+            events.try_recv(),
+            Ok(ChainEvent::TransactionDropped { transaction_id, .. }) if transaction_id == uncovered.id
+        ));
+        assert!(events.try_recv().is_err());
+        let mempool = app_state.mempool_repo.lock().await;
+        assert!(mempool.check_exists_by_id(&covered.id));
+        assert!(!mempool.check_exists_by_id(&uncovered.id));
+    }
+}
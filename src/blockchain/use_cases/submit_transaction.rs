@@ -0,0 +1,139 @@
+use std::fmt;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::domain::{
+    blockchain_repository::BlockchainRepository, chain_event::ChainEvent, error::TransactionError,
+    event_bus::EventBus, mempool_repository::MempoolRepository, transaction::Transaction,
+    user_state_repository::UserStateRepository,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionRejection {
+    Invalid(TransactionError),
+    SenderNotFound,
+    ReceiverNotFound,
+    AlreadyExists,
+}
+
+impl fmt::Display for TransactionRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionRejection::Invalid(e) => write!(f, "{}", e),
+            TransactionRejection::SenderNotFound => write!(f, "Sender not found"),
+            TransactionRejection::ReceiverNotFound => write!(f, "Receiver not found"),
+            TransactionRejection::AlreadyExists => write!(f, "Transaction already exists"),
+        }
+    }
+}
+
+impl TransactionRejection {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TransactionRejection::Invalid(e) => e.kind(),
+            TransactionRejection::SenderNotFound => "sender_not_found",
+            TransactionRejection::ReceiverNotFound => "receiver_not_found",
+            TransactionRejection::AlreadyExists => "already_exists",
+        }
+    }
+}
+
+impl From<TransactionError> for TransactionRejection {
+    fn from(e: TransactionError) -> Self {
+        TransactionRejection::Invalid(e)
+    }
+}
+
+// Перевіряє переказ за поточними балансами і додає його в мемпул
+pub async fn submit_transaction<M, U>(
+    user_state_repository: Arc<Mutex<U>>,
+    mempool_repository: Arc<Mutex<M>>,
+    events: &EventBus,
+    from: Uuid,
+    to: Uuid,
+    amount: f64,
+) -> Result<Transaction, TransactionRejection>
+where
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let transaction = Transaction::new(from, to, amount);
+    transaction.validate()?;
+
+    let user_state = user_state_repository.lock().await;
+    if !user_state.get_balances().contains_key(&from) {
+        return Err(TransactionRejection::SenderNotFound);
+    }
+    if !user_state.get_balances().contains_key(&to) {
+        return Err(TransactionRejection::ReceiverNotFound);
+    }
+    let balance = user_state.get_balance(&from);
+    if balance < amount {
+        return Err(TransactionError::InsufficientFunds {
+            address: from,
+            balance,
+            amount,
+        }
+        .into());
+    }
+
+    let mut mempool = mempool_repository.lock().await;
+    if mempool.check_exists_by_id(&transaction.id) {
+        return Err(TransactionRejection::AlreadyExists);
+    }
+    mempool.add_transaction(transaction.clone());
+    events.publish(ChainEvent::TransactionAdmitted {
+        transaction: transaction.clone(),
+    });
+    Ok(transaction)
+}
+
+// Транзакція, яку оголосив пір. Отримувач може бути новим рахунком, ще не відомим
+// цьому вузлу, тож перевіряються лише дублікати й покриття за нашими балансами
+pub async fn admit_announced_transaction<B, M, U>(
+    blockchain_repository: Arc<Mutex<B>>,
+    user_state_repository: Arc<Mutex<U>>,
+    mempool_repository: Arc<Mutex<M>>,
+    events: &EventBus,
+    transaction: Transaction,
+) -> Result<(), TransactionRejection>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    transaction.validate()?;
+    if blockchain_repository
+        .lock()
+        .await
+        .get_transaction_location(&transaction.id)
+        .await
+        .is_some()
+    {
+        return Err(TransactionRejection::AlreadyExists);
+    }
+
+    let user_state = user_state_repository.lock().await;
+    if !user_state.get_balances().contains_key(&transaction.from) {
+        return Err(TransactionRejection::SenderNotFound);
+    }
+    let balance = user_state.get_balance(&transaction.from);
+    if balance < transaction.amount {
+        return Err(TransactionError::InsufficientFunds {
+            address: transaction.from,
+            balance,
+            amount: transaction.amount,
+        }
+        .into());
+    }
+
+    let mut mempool = mempool_repository.lock().await;
+    if mempool.check_exists_by_id(&transaction.id) {
+        return Err(TransactionRejection::AlreadyExists);
+    }
+    mempool.add_transaction(transaction.clone());
+    events.publish(ChainEvent::TransactionAdmitted { transaction });
+    Ok(())
+}
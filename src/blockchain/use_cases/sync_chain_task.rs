@@ -1,57 +1,725 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use tokio::task::JoinSet;
+use tracing::{Instrument, debug, error, info, info_span, warn};
+use uuid::Uuid;
+
+use crate::blockchain::use_cases::add_block_to_chain::finalize_block;
+use crate::blockchain::use_cases::apply_block_state::{apply_block_state, replay_on_copy};
+use crate::blockchain::use_cases::ban_peers::penalize_peer;
+use crate::blockchain::use_cases::peer_transport::request;
+use crate::blockchain::use_cases::restore_user_state::{restore_on_copy, restore_user_state};
+use crate::blockchain::use_cases::revalidate_mempool::revalidate_pending;
 use crate::domain::{
-    app_state::AppState, block::Block, blockchain_repository::BlockchainRepository,
-    mempool_repository::MempoolRepository, user_state_repository::UserStateRepository,
+    app_state::AppState,
+    block::Block,
+    block_locator::{BlockHeaderEntry, block_locator},
+    blockchain_repository::BlockchainRepository,
+    chain_event::ChainEvent,
+    error::{BlockError, SyncError},
+    mempool_repository::MempoolRepository,
+    metrics,
+    peer_address::PeerAddress,
+    peer_message::{SyncQuery, SyncReply},
+    peer_score::Misbehaviour,
+    user_state_repository::UserStateRepository,
 };
 
-// Фонове завдання для синхронізації
+const SYNC_BATCH_SIZE: usize = 100;
+const MAX_PARALLEL_BATCHES: usize = 4;
+// Сторінок заголовків від одного піра за прохід; довший ланцюг наздоганяє наступний прохід
+const MAX_HEADER_PAGES: usize = 50;
+
+struct PeerHeaders {
+    peer: PeerAddress,
+    node_id: String,
+    headers: Vec<BlockHeaderEntry>,
+}
+
+impl PeerHeaders {
+    fn tip_height(&self) -> u64 {
+        self.headers.last().map_or(0, |h| h.header.height)
+    }
+
+    fn has(&self, entry: &BlockHeaderEntry) -> bool {
+        self.headers
+            .iter()
+            .any(|h| h.header.height == entry.header.height && h.hash == entry.hash)
+    }
+}
+
+// Фонове завдання для синхронізації: знаходить спільного предка через локатор блоків
+// і завантажує лише відсутні блоки паралельними пакетами з різних пірів.
+// Одночасно йде лише один прохід; запуск під час іншого нічого не робить
 pub async fn sync_chain_task<B, M, U>(app_state: AppState<B, M, U>)
 where
     B: BlockchainRepository + Send + Sync + 'static,
     M: MempoolRepository + Send + Sync + 'static,
     U: UserStateRepository + Send + Sync + 'static,
 {
-    println!("[Sync]: 🔄 Починаємо синхронізацію ланцюга...");
+    {
+        let mut status = app_state.sync_status.lock().await;
+        if status.syncing {
+            debug!("Chain sync already running");
+            return;
+        }
+        status.syncing = true;
+    }
+    let started_at = Instant::now();
+    let result = sync_with_peers(&app_state)
+        .instrument(info_span!("sync"))
+        .await;
+    app_state.metrics.inc(metrics::SYNC_RUNS, &[]);
+    app_state
+        .metrics
+        .observe(metrics::SYNC_DURATION, &[], started_at.elapsed());
 
-    let (peers, http_client) = {
-        (
-            app_state.node.lock().await.peers.clone(),
-            app_state.http_client.clone(),
-        )
-    };
+    let mut status = app_state.sync_status.lock().await;
+    status.syncing = false;
+    status.target_height = None;
+    // Невдалий прохід не оновлює last_synced_at, тож клієнт бачить, що ланцюг міг відстати
+    match result {
+        Ok(()) => {
+            info!("Chain sync finished");
+            status.last_synced_at = Some(
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs(),
+            );
+        }
+        Err(e) => warn!(reason = %e, "Chain sync failed"),
+    }
+}
 
-    let mut longest_chain: Vec<Block> = Vec::new();
+async fn sync_with_peers<B, M, U>(app_state: &AppState<B, M, U>) -> Result<(), SyncError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    info!("Starting chain sync");
 
-    for peer_addr in &peers {
-        let target_url = format!("http://{}/blocks", peer_addr);
+    let configured_peers = app_state.node.lock().await.peers.clone();
+    // Запити йдуть P2P-з'єднаннями, тож потрібні id вузлів за адресами
+    let peers: Vec<(PeerAddress, String)> = {
+        let registry = app_state.peers.lock().await;
+        configured_peers
+            .iter()
+            .filter_map(|address| {
+                registry
+                    .node_id_at(address)
+                    .map(|node_id| (address.clone(), node_id))
+            })
+            .collect()
+    };
+    let shared_key = app_state.config.keys.shared_key.clone();
 
-        match http_client.get(&target_url).send().await {
-            Ok(response) => {
-                if let Ok(peer_chain) = response.json::<Vec<Block>>().await {
-                    if peer_chain.len() > longest_chain.len() {
-                        longest_chain = peer_chain;
-                    }
-                }
+    let local_chain = app_state
+        .blockchain_repo
+        .lock()
+        .await
+        .get_all_blocks()
+        .await;
+    let local_height = local_chain.last().map_or(0, |b| b.header.height);
+    // Після швидкої синхронізації ланцюг починається з блоку снапшоту, а не з генезису
+    let first_height = local_chain.first().map_or(0, |b| b.header.height);
+    let locator = block_locator(&local_chain);
+
+    let mut sources: Vec<PeerHeaders> = Vec::new();
+    for (peer_addr, node_id) in &peers {
+        let fetched = fetch_headers(app_state, node_id, &locator).await;
+        let mut sync_status = app_state.sync_status.lock().await;
+        match fetched {
+            Ok(headers) if !headers.is_empty() => {
+                let peer = PeerHeaders {
+                    peer: peer_addr.clone(),
+                    node_id: node_id.clone(),
+                    headers,
+                };
+                sync_status.record_peer(peer_addr, Some(peer.tip_height()));
+                sources.push(peer);
+            }
+            // Порожня відповідь: у піра немає блоків після спільного предка
+            Ok(_) => sync_status.record_peer(peer_addr, None),
+            Err(e) => {
+                sync_status.mark_unreachable(peer_addr);
+                warn!(peer = %peer_addr, error = %e, "Failed to fetch headers from peer");
             }
-            Err(e) => println!("[Sync]: ⚠️ Не вдалося підключитися до {}: {}", peer_addr, e),
         }
     }
 
-    if longest_chain.is_empty() {
-        println!("[Sync]: ❌ Не вдалося знайти довший ланцюг у пірів.");
-        return;
+    let Some(best) = sources
+        .iter()
+        .filter(|s| s.tip_height() > local_height)
+        .max_by_key(|s| s.tip_height())
+    else {
+        info!(local_height, "No peer has a longer chain");
+        return Ok(());
+    };
+
+    let first = &best.headers[0];
+    let fork_height = match first.header.height.checked_sub(1) {
+        Some(height)
+            if height
+                .checked_sub(first_height)
+                .and_then(|position| local_chain.get(position as usize))
+                .is_some_and(|b| b.hash == first.header.parent_hash) =>
+        {
+            height
+        }
+        _ => {
+            return Err(SyncError::NoCommonAncestor {
+                node_id: best.node_id.clone(),
+            });
+        }
+    };
+    let headers_linked = best.headers.windows(2).all(|w| {
+        w[1].header.parent_hash == w[0].hash && w[1].header.height == w[0].header.height + 1
+    });
+    if !headers_linked {
+        return Err(SyncError::UnlinkedHeaders {
+            node_id: best.node_id.clone(),
+        });
     }
 
-    println!(
-        "[Sync]: 💾 Знайдено довший ланцюг (висота {}). Замінюємо локальний...",
-        longest_chain.len() - 1
+    let is_reorg = fork_height < local_height;
+    app_state.sync_status.lock().await.target_height = Some(best.tip_height());
+    info!(
+        peer = %best.peer,
+        target_height = best.tip_height(),
+        fork_height,
+        blocks = best.headers.len(),
+        "Found a longer chain, downloading blocks"
     );
 
+    let batches: Vec<Vec<BlockHeaderEntry>> = best
+        .headers
+        .chunks(SYNC_BATCH_SIZE)
+        .map(|c| c.to_vec())
+        .collect();
+
+    let mut downloads = JoinSet::new();
+    let mut ready: BTreeMap<usize, Vec<Block>> = BTreeMap::new();
+    let mut next_to_spawn = 0;
+    let mut next_to_apply = 0;
+    let mut reorg_blocks: Vec<Block> = Vec::new();
+
+    while next_to_apply < batches.len() {
+        while next_to_spawn < batches.len() && downloads.len() < MAX_PARALLEL_BATCHES {
+            let batch = batches[next_to_spawn].clone();
+            // Пакет віддає будь-який пір, чиї заголовки містять його останній блок
+            let last = batch.last().expect("batches are never empty");
+            let servers: Vec<&PeerHeaders> = sources.iter().filter(|s| s.has(last)).collect();
+            let server = servers[next_to_spawn % servers.len()];
+            downloads.spawn(download_batch(
+                app_state.clone(),
+                vec![
+                    (server.peer.clone(), server.node_id.clone()),
+                    (best.peer.clone(), best.node_id.clone()),
+                ],
+                batch,
+                shared_key.clone(),
+                next_to_spawn,
+            ));
+            next_to_spawn += 1;
+        }
+
+        let Some(joined) = downloads.join_next().await else {
+            break;
+        };
+        match joined {
+            Ok((index, Ok(blocks))) => {
+                ready.insert(index, blocks);
+            }
+            Ok((index, Err(reason))) => {
+                return Err(SyncError::Download {
+                    batch: index,
+                    reason,
+                });
+            }
+            Err(e) => {
+                error!(error = %e, "Download task panicked, stopping sync");
+                return Err(SyncError::Download {
+                    batch: next_to_apply,
+                    reason: e.to_string(),
+                });
+            }
+        }
+
+        while let Some(blocks) = ready.remove(&next_to_apply) {
+            if is_reorg {
+                reorg_blocks.extend(blocks);
+            } else {
+                append_blocks(app_state, blocks).await?;
+            }
+            next_to_apply += 1;
+        }
+    }
+
+    if is_reorg {
+        let mut new_chain = local_chain[..=(fork_height - first_height) as usize].to_vec();
+        new_chain.extend(reorg_blocks);
+        replace_chain(app_state, new_chain, fork_height, &best.node_id).await?;
+    }
+    Ok(())
+}
+
+async fn fetch_headers<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    locator: &[String],
+) -> Result<Vec<BlockHeaderEntry>, String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut headers = request_headers(app_state, node_id, locator.to_vec(), None).await?;
+
+    // Пір віддає заголовки сторінками, тож дочитуємо, поки не отримаємо порожню
+    // або не вичерпаємо ліміт сторінок
+    for _ in 1..MAX_HEADER_PAGES {
+        let Some(last) = headers.last() else {
+            break;
+        };
+        let from = last.header.height + 1;
+        let page = request_headers(app_state, node_id, Vec::new(), Some(from)).await?;
+        match page.first() {
+            None => break,
+            Some(first) if first.header.height != from => {
+                return Err(format!(
+                    "asked for headers from #{}, got #{}",
+                    from, first.header.height
+                ));
+            }
+            Some(_) => headers.extend(page),
+        }
+    }
+    Ok(headers)
+}
+
+async fn request_headers<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    locator: Vec<String>,
+    from: Option<u64>,
+) -> Result<Vec<BlockHeaderEntry>, String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    match request(app_state, node_id, SyncQuery::Headers { locator, from }).await? {
+        SyncReply::Headers { headers } => Ok(headers),
+        _ => Err("peer answered a header request with something else".to_string()),
+    }
+}
+
+async fn download_batch<B, M, U>(
+    app_state: AppState<B, M, U>,
+    peers: Vec<(PeerAddress, String)>,
+    expected: Vec<BlockHeaderEntry>,
+    shared_key: String,
+    index: usize,
+) -> (usize, Result<Vec<Block>, String>)
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut last_error = String::new();
+
+    for (peer_addr, node_id) in peers {
+        match download_from(&app_state, &node_id, &expected, &shared_key).await {
+            Ok(blocks) => return (index, Ok(blocks)),
+            Err(e) => last_error = format!("{}: {}", peer_addr, e),
+        }
+    }
+    (index, Err(last_error))
+}
+
+// Пір може віддати лише початок діапазону, якщо весь не вміщується в один кадр,
+// тож решта дозапитується, поки пір щоразу повертає хоч один блок
+async fn download_from<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    node_id: &str,
+    expected: &[BlockHeaderEntry],
+    shared_key: &str,
+) -> Result<Vec<Block>, String>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let to = expected[expected.len() - 1].header.height;
+    let mut blocks: Vec<Block> = Vec::with_capacity(expected.len());
+    while blocks.len() < expected.len() {
+        let rest = &expected[blocks.len()..];
+        let from = rest[0].header.height;
+        let part = match request(app_state, node_id, SyncQuery::Blocks { from, to }).await? {
+            SyncReply::Blocks { blocks } => blocks,
+            _ => return Err("peer answered a block request with something else".to_string()),
+        };
+        if part.is_empty() {
+            return Err(format!("peer returned no blocks from #{}", from));
+        }
+        verify_batch(
+            &part,
+            rest,
+            shared_key,
+            &app_state.config.consensus.validator_keys,
+        )?;
+        blocks.extend(part);
+    }
+    Ok(blocks)
+}
+
+// Блоки мають збігатися з початком очікуваних заголовків
+fn verify_batch(
+    blocks: &[Block],
+    expected: &[BlockHeaderEntry],
+    shared_key: &str,
+    pinned: &HashMap<String, String>,
+) -> Result<(), String> {
+    if blocks.len() > expected.len() {
+        return Err(format!(
+            "expected at most {} blocks, got {}",
+            expected.len(),
+            blocks.len()
+        ));
+    }
+    for (block, entry) in blocks.iter().zip(expected) {
+        if block.hash != entry.hash || block.header.parent_hash != entry.header.parent_hash {
+            return Err(format!(
+                "block #{} does not match its header",
+                entry.header.height
+            ));
+        }
+        block
+            .validate(shared_key, pinned)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Дописує блоки до вершини нашого ланцюга, перевіряючи кожен перед застосуванням
+async fn append_blocks<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    blocks: Vec<Block>,
+) -> Result<(), BlockError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    let mut blockchain = app_state.blockchain_repo.lock().await;
+    let mut user_state = app_state.user_state_repo.lock().await;
+    let mut mempool = app_state.mempool_repo.lock().await;
+
+    for block in blocks {
+        let last_block = blockchain
+            .get_last_block()
+            .await
+            .ok_or(BlockError::EmptyChain)?;
+        if block.header.parent_hash != last_block.hash {
+            return Err(BlockError::DoesNotExtendTip {
+                height: block.header.height,
+                tip_height: last_block.header.height,
+            });
+        }
+        let balances_before = user_state.get_balances().clone();
+        apply_block_state(&mut *user_state, &block)?;
+        if let Err(e) = blockchain.add_block(block.clone()).await {
+            user_state.reset_balances(balances_before);
+            return Err(e.into());
+        }
+        user_state.commit_block(&block);
+        mempool.remove_included(&block);
+        finalize_block(app_state, block).await;
+    }
+    Ok(())
+}
+
+// Переходить на довше відгалуження від `fork_height`. Спершу відгалуження відтворюється
+// на копії стану в точці розгалуження; якщо хоч один блок не лягає або корінь стану
+// не збігається, старий ланцюг і стан лишаються, а пір, що віддав відгалуження, штрафується
+async fn replace_chain<B, M, U>(
+    app_state: &AppState<B, M, U>,
+    new_chain: Vec<Block>,
+    fork_height: u64,
+    served_by: &str,
+) -> Result<(), SyncError>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
     let mut blockchain = app_state.blockchain_repo.lock().await;
     let mut user_state = app_state.user_state_repo.lock().await;
 
-    blockchain.replace_chain(longest_chain.clone()).await;
+    let old_chain = blockchain.get_all_blocks().await;
+    let old_tip_height = old_chain.last().map_or(0, |b| b.header.height);
+    let fork_position = new_chain
+        .iter()
+        .position(|b| b.header.height == fork_height)
+        .expect("new chain contains the fork block");
+    let still_forks_here = old_chain
+        .get(fork_position)
+        .is_some_and(|b| b.hash == new_chain[fork_position].hash);
+    if !still_forks_here || old_chain.len() >= new_chain.len() {
+        return Err(SyncError::ChainChanged);
+    }
 
-    user_state.rebuild_from_blocks(&longest_chain).await;
+    let (common, branch) = new_chain.split_at(fork_position + 1);
+    let fork_state = restore_on_copy(&*user_state, common).await?;
+    if let Err(e) = replay_on_copy(fork_state, branch) {
+        drop(user_state);
+        drop(blockchain);
+        if let Some(misbehaviour) = Misbehaviour::from_block_error(&e) {
+            penalize_peer(app_state, served_by, misbehaviour).await;
+        }
+        return Err(e.into());
+    }
 
-    println!("[Sync]: ✅ Синхронізацію завершено!");
+    info!(
+        fork_height,
+        replaced = old_tip_height - fork_height,
+        "Rolling back to the common ancestor"
+    );
+    blockchain.replace_chain(new_chain.clone()).await?;
+    // Відгалуження вже перевірено на копії, тож відновлення не має зупинитися
+    restore_user_state(&mut *user_state, &new_chain).await?;
+
+    let mut mempool = app_state.mempool_repo.lock().await;
+    let reinjected = reinject_orphaned_transactions(&mut *mempool, &old_chain, &new_chain);
+    for block in &new_chain {
+        mempool.remove_included(block);
+    }
+    if reinjected > 0 {
+        info!(
+            transactions = reinjected,
+            "Returned transactions from orphaned blocks to the mempool"
+        );
+    }
+    // Баланси нового відгалуження можуть уже не покривати решту мемпулу
+    revalidate_pending(&*blockchain, &*user_state, &mut *mempool, &app_state.events).await;
+    drop(mempool);
+    drop(user_state);
+    drop(blockchain);
+
+    let new_tip = &new_chain[new_chain.len() - 1];
+    app_state.events.publish(ChainEvent::ChainReorganized {
+        fork_height,
+        old_tip_height,
+        new_tip_height: new_tip.header.height,
+        new_tip_hash: new_tip.hash.clone(),
+    });
+    for block in branch {
+        finalize_block(app_state, block.clone()).await;
+    }
+    Ok(())
+}
+
+// Повертає в мемпул транзакції з блоків, які випали з ланцюга після заміни
+fn reinject_orphaned_transactions<M>(
+    mempool: &mut M,
+    old_chain: &[Block],
+    new_chain: &[Block],
+) -> usize
+where
+    M: MempoolRepository + ?Sized,
+{
+    let new_hashes: HashSet<&str> = new_chain.iter().map(|b| b.hash.as_str()).collect();
+    let new_tx_ids: HashSet<Uuid> = new_chain
+        .iter()
+        .flat_map(|b| b.transactions.iter().map(|t| t.id))
+        .collect();
+
+    let mut reinjected = 0;
+    for block in old_chain
+        .iter()
+        .filter(|b| !new_hashes.contains(b.hash.as_str()))
+    {
+        for tx in &block.transactions {
+            if new_tx_ids.contains(&tx.id) || mempool.check_exists_by_id(&tx.id) {
+                continue;
+            }
+            mempool.add_transaction(tx.clone());
+            reinjected += 1;
+        }
+    }
+    reinjected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_blocks, replace_chain, sync_chain_task};
+    use crate::blockchain::use_cases::ban_peers::unix_now;
+    use crate::domain::app_state::testing::{TestAppState, app_state, child_block, transfer};
+    use crate::domain::block::Block;
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::domain::config::Config;
+    use crate::domain::error::{BlockError, SyncError};
+    use crate::domain::mempool_repository::MempoolRepository;
+    use crate::domain::state_snapshot::compute_state_root;
+    use crate::domain::user_state_repository::UserStateRepository;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    // Вузол v1 з генезисом і балансами після нього
+    async fn node() -> (TestAppState, Block, HashMap<Uuid, f64>) {
+        let app_state = app_state(Config::for_tests("v1", &["v1", "v2"])).await;
+        let genesis = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_last_block()
+            .await
+            .unwrap();
+        let balances = app_state
+            .user_state_repo
+            .lock()
+            .await
+            .get_balances()
+            .clone();
+        (app_state, genesis, balances)
+    }
+
+    async fn tip_hash(app_state: &TestAppState) -> String {
+        let blockchain = app_state.blockchain_repo.lock().await;
+        blockchain.get_last_block().await.unwrap().hash
+    }
+
+    #[tokio::test]
+    async fn second_sync_does_not_start_while_one_runs() {
+        let (app_state, _, _) = node().await;
+        app_state.sync_status.lock().await.syncing = true;
+
+        sync_chain_task(app_state.clone()).await;
+
+        // Без пірів прохід завершився б успішно й скинув прапорець
+        let status = app_state.sync_status.lock().await;
+        assert!(status.syncing);
+        assert_eq!(status.last_synced_at, None);
+    }
+
+    #[tokio::test]
+    async fn refuses_a_branch_whose_state_root_is_wrong() {
+        let (app_state, genesis, balances) = node().await;
+        let local = child_block(
+            &genesis,
+            "v2",
+            vec![transfer(2, 10.0)],
+            &mut balances.clone(),
+        );
+        append_blocks(&app_state, vec![local.clone()])
+            .await
+            .unwrap();
+        let state_root = app_state.user_state_repo.lock().await.state_root();
+
+        let mut branch_balances = balances.clone();
+        let first = child_block(
+            &genesis,
+            "v2",
+            vec![transfer(3, 20.0)],
+            &mut branch_balances,
+        );
+        let valid = child_block(&first, "v2", vec![transfer(3, 5.0)], &mut branch_balances);
+        let forged = Block::new(
+            valid.index,
+            valid.timestamp,
+            valid.header.proposer_id.clone(),
+            valid.header.height,
+            valid.transactions.clone(),
+            valid.header.parent_hash.clone(),
+            compute_state_root(&balances),
+            "test-key".to_string(),
+        );
+        let result = replace_chain(&app_state, vec![genesis, first, forged], 0, "v2").await;
+
+        assert!(matches!(
+            result,
+            Err(SyncError::Block(BlockError::StateRootMismatch {
+                height: 2,
+                ..
+            }))
+        ));
+
+        assert_eq!(tip_hash(&app_state).await, local.hash);
+        let user_state = app_state.user_state_repo.lock().await;
+        assert_eq!(user_state.state_root(), state_root);
+        assert_eq!(user_state.get_balance(&Uuid::from_u128(3)), 0.0);
+        drop(user_state);
+        let score = app_state.scores.lock().await.get("v2", unix_now()).unwrap();
+        assert_eq!(score.last_offence, Some("invalid_state_root"));
+    }
+
+    #[tokio::test]
+    async fn reorg_returns_only_orphaned_transactions_to_the_mempool() {
+        let (app_state, genesis, balances) = node().await;
+        let orphaned = transfer(2, 10.0);
+        let shared = transfer(3, 5.0);
+        let local = child_block(
+            &genesis,
+            "v2",
+            vec![orphaned.clone(), shared.clone()],
+            &mut balances.clone(),
+        );
+        append_blocks(&app_state, vec![local]).await.unwrap();
+
+        let mut branch_balances = balances.clone();
+        let first = child_block(&genesis, "v2", vec![shared.clone()], &mut branch_balances);
+        let pending = transfer(4, 1.0);
+        app_state
+            .mempool_repo
+            .lock()
+            .await
+            .add_transaction(pending.clone());
+        let second = child_block(&first, "v2", vec![pending.clone()], &mut branch_balances);
+        replace_chain(&app_state, vec![genesis, first, second.clone()], 0, "v2")
+            .await
+            .unwrap();
+
+        assert_eq!(tip_hash(&app_state).await, second.hash);
+        assert_eq!(
+            app_state.user_state_repo.lock().await.state_root(),
+            second.header.state_root
+        );
+        // Транзакція з нового відгалуження не повертається, а включена в нього — прибирається
+        let mempool = app_state.mempool_repo.lock().await;
+        let ids: Vec<Uuid> = mempool
+            .get_all_transactions()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![orphaned.id]);
+    }
+
+    #[tokio::test]
+    async fn reports_a_reorg_that_no_longer_forks_from_our_chain() {
+        let (app_state, genesis, balances) = node().await;
+        let local = child_block(
+            &genesis,
+            "v2",
+            vec![transfer(2, 10.0)],
+            &mut balances.clone(),
+        );
+        append_blocks(&app_state, vec![local.clone()])
+            .await
+            .unwrap();
+
+        // Відгалуження не довше за наш ланцюг, тож заміна вже не має сенсу
+        let branch = child_block(
+            &genesis,
+            "v2",
+            vec![transfer(3, 5.0)],
+            &mut balances.clone(),
+        );
+        let result = replace_chain(&app_state, vec![genesis, branch], 0, "v2").await;
+
+        assert_eq!(result, Err(SyncError::ChainChanged));
+        assert_eq!(tip_hash(&app_state).await, local.hash);
+    }
 }
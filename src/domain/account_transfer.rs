@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountTransfer {
+    // Порядковий номер запису в історії рахунку, використовується як курсор
+    pub seq: usize,
+    pub transaction_id: Uuid,
+    pub block_height: u64,
+    pub block_hash: String,
+    pub direction: TransferDirection,
+    pub counterparty: Uuid,
+    pub amount: f64,
+    pub balance_after: f64,
+    pub timestamp: u64,
+}
@@ -0,0 +1,259 @@
+use crate::domain::error::StorageError;
+use crate::domain::peer_address::PeerAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Після невдалої спроби наступна відкладається на 5 с, 10 с, 20 с ... до 5 хв
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+// Адреси з обміну й вхідних handshake видаляються після стількох невдач поспіль
+const MAX_FAILURES: u32 = 10;
+const MAX_ENTRIES: usize = 1000;
+// Скільки адрес вузол віддає й приймає за один обмін
+pub const MAX_SHARED_ADDRESSES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressSource {
+    /// Listed in `--peers`; never evicted.
+    Config,
+    /// Listed in `--bootstrap`; never evicted.
+    Bootstrap,
+    /// Learned from another node's `GET /peers/addresses`.
+    Exchange,
+    /// Advertised by a node that handshaked with us.
+    Inbound,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressEntry {
+    pub address: PeerAddress,
+    pub source: AddressSource,
+    // Id вузла, що відповів за цією адресою востаннє
+    pub node_id: Option<String>,
+    // Unix-час останнього успішного handshake
+    pub last_seen: Option<u64>,
+    pub failures: u32,
+    pub next_attempt_at: u64,
+}
+
+impl AddressEntry {
+    fn evictable(&self) -> bool {
+        matches!(
+            self.source,
+            AddressSource::Exchange | AddressSource::Inbound
+        )
+    }
+}
+
+/// Every peer address this node knows about, keyed by `host:port`, with the
+/// outcome of the last connection attempts. Persisted so a restarted node can
+/// rejoin the network without its bootstrap nodes.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AddressBook {
+    entries: HashMap<String, AddressEntry>,
+    // Чи змінилася книга з останнього збереження
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl AddressBook {
+    // Додає адресу, якщо її ще немає; повертає true, якщо книга змінилася
+    pub fn add(&mut self, address: &PeerAddress, source: AddressSource) -> bool {
+        if let Some(entry) = self.entries.get_mut(&address.authority()) {
+            // Адреса з конфігурації важливіша за ту саму адресу, отриману від піра
+            if !entry.evictable()
+                || matches!(source, AddressSource::Exchange | AddressSource::Inbound)
+            {
+                return false;
+            }
+            entry.source = source;
+            self.changed = true;
+            return true;
+        }
+        if self.entries.len() >= MAX_ENTRIES && !self.evict_one() {
+            return false;
+        }
+        self.entries.insert(
+            address.authority(),
+            AddressEntry {
+                address: address.clone(),
+                source,
+                node_id: address.node_id.clone(),
+                last_seen: None,
+                failures: 0,
+                next_attempt_at: 0,
+            },
+        );
+        self.changed = true;
+        true
+    }
+
+    // Вузол за адресою сам щойно зв'язався з нами, тож пробуємо його одразу
+    pub fn heard_from(&mut self, address: &PeerAddress, node_id: &str) -> bool {
+        let added = self.add(address, AddressSource::Inbound);
+        let Some(entry) = self.entries.get_mut(&address.authority()) else {
+            return added;
+        };
+        let changed = entry.failures > 0 || entry.node_id.as_deref() != Some(node_id);
+        entry.failures = 0;
+        entry.next_attempt_at = 0;
+        entry.node_id = Some(node_id.to_string());
+        self.changed |= changed;
+        added || changed
+    }
+
+    pub fn record_success(&mut self, address: &PeerAddress, node_id: &str, now: u64) {
+        if let Some(entry) = self.entries.get_mut(&address.authority()) {
+            entry.node_id = Some(node_id.to_string());
+            entry.last_seen = Some(now);
+            entry.failures = 0;
+            entry.next_attempt_at = 0;
+            self.changed = true;
+        }
+    }
+
+    pub fn record_failure(&mut self, address: &PeerAddress, now: u64) {
+        let key = address.authority();
+        let Some(entry) = self.entries.get_mut(&key) else {
+            return;
+        };
+        entry.failures = entry.failures.saturating_add(1);
+        let backoff = INITIAL_BACKOFF
+            .saturating_mul(1 << (entry.failures - 1).min(16))
+            .min(MAX_BACKOFF);
+        entry.next_attempt_at = now + backoff.as_secs();
+        self.changed = true;
+        if entry.evictable() && entry.failures >= MAX_FAILURES {
+            self.entries.remove(&key);
+        }
+    }
+
+    pub fn remove(&mut self, address: &PeerAddress) {
+        self.changed |= self.entries.remove(&address.authority()).is_some();
+    }
+
+    // Повертає true один раз після кожної зміни, щоб зберігати книгу лише за потреби
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    // Адреси, які вже можна пробувати: спершу з конфігурації, далі ті, що відповідали нещодавно
+    pub fn due(&self, now: u64) -> Vec<AddressEntry> {
+        let mut due: Vec<AddressEntry> = self
+            .entries
+            .values()
+            .filter(|entry| entry.next_attempt_at <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|entry| {
+            (
+                entry.source != AddressSource::Config,
+                std::cmp::Reverse(entry.last_seen),
+                entry.failures,
+                entry.address.authority(),
+            )
+        });
+        due
+    }
+
+    // Те, чим ділимося з іншими: адреси, з якими handshake проходив і остання спроба вдалася
+    pub fn shareable(&self, limit: usize) -> Vec<PeerAddress> {
+        let mut good: Vec<&AddressEntry> = self
+            .entries
+            .values()
+            .filter(|entry| entry.last_seen.is_some() && entry.failures == 0)
+            .collect();
+        good.sort_by_key(|entry| std::cmp::Reverse(entry.last_seen));
+        good.into_iter()
+            .take(limit)
+            .map(|entry| match (&entry.address.node_id, &entry.node_id) {
+                (None, Some(node_id)) => PeerAddress {
+                    node_id: Some(node_id.clone()),
+                    ..entry.address.clone()
+                },
+                _ => entry.address.clone(),
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Звільняє місце, видаляючи найгіршу адресу з тих, що не з конфігурації
+    fn evict_one(&mut self) -> bool {
+        let worst = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.evictable())
+            .max_by_key(|(_, entry)| (entry.failures, std::cmp::Reverse(entry.last_seen)))
+            .map(|(key, _)| key.clone());
+        match worst {
+            Some(key) => {
+                self.entries.remove(&key);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Where the address book is kept between restarts.
+pub trait AddressBookStore: Send + Sync {
+    fn save(&self, book: &AddressBook) -> Result<(), StorageError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddressBook, AddressSource};
+    use crate::domain::peer_address::PeerAddress;
+
+    fn address(input: &str) -> PeerAddress {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn backs_off_and_evicts_failing_addresses() {
+        let mut book = AddressBook::default();
+        let learned = address("10.0.0.2:3002");
+        let configured = address("3001");
+        book.add(&learned, AddressSource::Exchange);
+        book.add(&configured, AddressSource::Config);
+
+        book.record_failure(&learned, 100);
+        assert_eq!(book.due(100).len(), 1);
+        assert_eq!(book.due(105).len(), 2);
+        book.record_failure(&learned, 105);
+        assert_eq!(book.due(114).len(), 1);
+
+        for _ in 0..10 {
+            book.record_failure(&learned, 200);
+            book.record_failure(&configured, 200);
+        }
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.due(200 + 300).len(), 1);
+    }
+
+    #[test]
+    fn prefers_configured_addresses_and_shares_only_live_ones() {
+        let mut book = AddressBook::default();
+        let learned = address("10.0.0.2:3002");
+        let configured = address("localhost:3001");
+        book.add(&learned, AddressSource::Exchange);
+        book.add(&configured, AddressSource::Config);
+        assert!(!book.add(&address("v1@localhost:3001"), AddressSource::Inbound));
+
+        let due = book.due(0);
+        assert_eq!(due[0].address, configured);
+        assert!(book.shareable(10).is_empty());
+
+        book.record_success(&configured, "v1", 50);
+        assert_eq!(book.shareable(10), vec![address("v1@localhost:3001")]);
+    }
+}
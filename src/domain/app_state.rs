@@ -3,10 +3,21 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+use crate::domain::address_book::{AddressBook, AddressBookStore};
 use crate::domain::block::Block;
 use crate::domain::blockchain_repository::BlockchainRepository;
+use crate::domain::config::Config;
+use crate::domain::consensus_status::ConsensusStatus;
+use crate::domain::event_bus::EventBus;
+use crate::domain::gossip::SeenMessages;
 use crate::domain::mempool_repository::MempoolRepository;
+use crate::domain::metrics::Metrics;
 use crate::domain::node::Node;
+use crate::domain::node_identity::NodeIdentity;
+use crate::domain::peer_links::PeerLinks;
+use crate::domain::peer_registry::PeerRegistry;
+use crate::domain::peer_score::PeerScores;
+use crate::domain::sync_status::SyncStatus;
 use crate::domain::user_state_repository::UserStateRepository;
 
 pub struct AppState<B, M, U>
@@ -19,10 +30,61 @@ where
     pub mempool_repo: Arc<Mutex<M>>,
     pub user_state_repo: Arc<Mutex<U>>,
     pub node: Arc<Mutex<Node>>,
-    pub shared_key: String,
+    pub config: Arc<Config>,
+    pub identity: Arc<NodeIdentity>,
     pub http_client: Client,
     pub vote_counts: Arc<Mutex<HashMap<String, Vec<String>>>>,
     pub pending_blocks: Arc<Mutex<HashMap<String, Block>>>,
+    pub events: EventBus,
+    pub sync_status: Arc<Mutex<SyncStatus>>,
+    pub consensus_status: Arc<Mutex<ConsensusStatus>>,
+    pub metrics: Arc<Metrics>,
+    pub peers: Arc<Mutex<PeerRegistry>>,
+    pub links: Arc<Mutex<PeerLinks>>,
+    pub seen: Arc<Mutex<SeenMessages>>,
+    pub address_book: Arc<Mutex<AddressBook>>,
+    pub scores: Arc<Mutex<PeerScores>>,
+    // None, якщо книга адрес живе лише в пам'яті
+    pub address_store: Option<Arc<dyn AddressBookStore>>,
+}
+
+impl<B, M, U> AppState<B, M, U>
+where
+    B: BlockchainRepository + Send + Sync + 'static,
+    M: MempoolRepository + Send + Sync + 'static,
+    U: UserStateRepository + Send + Sync + 'static,
+{
+    // Стан вузла без пірів і з порожньою книгою адрес у пам'яті
+    pub fn new(
+        config: Arc<Config>,
+        node: Node,
+        identity: NodeIdentity,
+        blockchain_repo: B,
+        mempool_repo: M,
+        user_state_repo: U,
+    ) -> Self {
+        Self {
+            blockchain_repo: Arc::new(Mutex::new(blockchain_repo)),
+            mempool_repo: Arc::new(Mutex::new(mempool_repo)),
+            user_state_repo: Arc::new(Mutex::new(user_state_repo)),
+            node: Arc::new(Mutex::new(node)),
+            config,
+            identity: Arc::new(identity),
+            http_client: Client::new(),
+            vote_counts: Arc::new(Mutex::new(HashMap::new())),
+            pending_blocks: Arc::new(Mutex::new(HashMap::new())),
+            events: EventBus::new(),
+            sync_status: Arc::new(Mutex::new(SyncStatus::default())),
+            consensus_status: Arc::new(Mutex::new(ConsensusStatus::default())),
+            metrics: Arc::new(Metrics::new()),
+            peers: Arc::new(Mutex::new(PeerRegistry::default())),
+            links: Arc::new(Mutex::new(PeerLinks::default())),
+            seen: Arc::new(Mutex::new(SeenMessages::default())),
+            address_book: Arc::new(Mutex::new(AddressBook::default())),
+            scores: Arc::new(Mutex::new(PeerScores::default())),
+            address_store: None,
+        }
+    }
 }
 
 impl<B, M, U> Clone for AppState<B, M, U>
@@ -37,10 +99,116 @@ where
             mempool_repo: Arc::clone(&self.mempool_repo),
             user_state_repo: Arc::clone(&self.user_state_repo),
             node: Arc::clone(&self.node),
-            shared_key: self.shared_key.clone(),
+            config: Arc::clone(&self.config),
+            identity: Arc::clone(&self.identity),
             http_client: self.http_client.clone(),
             vote_counts: Arc::clone(&self.vote_counts),
             pending_blocks: Arc::clone(&self.pending_blocks),
+            events: self.events.clone(),
+            sync_status: Arc::clone(&self.sync_status),
+            consensus_status: Arc::clone(&self.consensus_status),
+            metrics: Arc::clone(&self.metrics),
+            peers: Arc::clone(&self.peers),
+            links: Arc::clone(&self.links),
+            seen: Arc::clone(&self.seen),
+            address_book: Arc::clone(&self.address_book),
+            scores: Arc::clone(&self.scores),
+            address_store: self.address_store.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod testing {
+    use super::AppState;
+    use crate::blockchain::use_cases::create_genesis_block::create_genesis_block;
+    use crate::blockchain::use_cases::restore_user_state::restore_user_state;
+    use crate::domain::block::Block;
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::domain::config::Config;
+    use crate::domain::node::Node;
+    use crate::domain::node_identity::NodeIdentity;
+    use crate::domain::state_snapshot::compute_state_root;
+    use crate::domain::transaction::Transaction;
+    use crate::infrastructure::in_memory_blockchain_repository::InMemoryBlockchainRepository;
+    use crate::infrastructure::in_memory_user_state_repository::{
+        DEFAULT_SNAPSHOT_INTERVAL, InMemoryUserStateRepository,
+    };
+    use crate::infrastructure::mempool_repository::InMemoryMempoolRepository;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use uuid::Uuid;
+
+    // Рахунок крана з Config::for_tests
+    pub const FAUCET: Uuid = Uuid::from_u128(1);
+
+    pub type TestAppState = AppState<
+        InMemoryBlockchainRepository,
+        InMemoryMempoolRepository,
+        InMemoryUserStateRepository,
+    >;
+
+    // Вузол у пам'яті з генезисом і відновленим станом, як після старту сервера
+    pub async fn app_state(config: Config) -> TestAppState {
+        let node = Node::new(
+            config.node.id.clone(),
+            config.node.chain_id.clone(),
+            Vec::new(),
+            config.consensus.validators.clone(),
+        );
+        let identity = NodeIdentity::for_tests(&config.node.id);
+        let app_state = AppState::new(
+            Arc::new(config),
+            node,
+            identity,
+            InMemoryBlockchainRepository::new(),
+            InMemoryMempoolRepository::new(),
+            InMemoryUserStateRepository::with_snapshot_interval(DEFAULT_SNAPSHOT_INTERVAL),
+        );
+        create_genesis_block(app_state.blockchain_repo.clone(), &app_state.config).await;
+        let chain = app_state
+            .blockchain_repo
+            .lock()
+            .await
+            .get_all_blocks()
+            .await;
+        restore_user_state(&mut *app_state.user_state_repo.lock().await, &chain)
+            .await
+            .expect("genesis applies to an empty state");
+        app_state.consensus_status.lock().await.finalized_height =
+            chain.last().map(|b| b.header.height);
+        app_state
+    }
+
+    pub fn transfer(to: u128, amount: f64) -> Transaction {
+        Transaction::new(FAUCET, Uuid::from_u128(to), amount)
+    }
+
+    // Блок `proposer` поверх `parent`, підписаний спільним ключем з Config::for_tests
+    // і ключем ідентичності, закріпленим там за `proposer`;
+    // `balances` переходять у стан після нього
+    pub fn child_block(
+        parent: &Block,
+        proposer: &str,
+        transactions: Vec<Transaction>,
+        balances: &mut HashMap<Uuid, f64>,
+    ) -> Block {
+        for tx in &transactions {
+            *balances.entry(tx.from).or_default() -= tx.amount;
+            *balances.entry(tx.to).or_default() += tx.amount;
         }
+        let height = parent.header.height + 1;
+        let mut block = Block::new(
+            height as u32,
+            height,
+            proposer.to_string(),
+            height,
+            transactions,
+            parent.hash.clone(),
+            compute_state_root(balances),
+            "test-key".to_string(),
+        );
+        block.sign_as_proposer(&NodeIdentity::for_tests(proposer));
+        block
     }
 }
@@ -1,9 +1,11 @@
-use crate::domain::{block_header::BlockHeader, transaction::Transaction};
+use crate::domain::node_identity::{NodeIdentity, verify_signature};
+use crate::domain::{block_header::BlockHeader, error::BlockError, transaction::Transaction};
 use hex;
 use hmac::digest::KeyInit;
 use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -15,9 +17,13 @@ pub struct Block {
     pub transactions: Vec<Transaction>,
     pub hash: String,
     pub signature: String,
+    // Підпис лідера його ключем ідентичності; генезис не підписує ніхто
+    #[serde(default)]
+    pub proposer_signature: String,
 }
 
 impl Block {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         index: u32,
         timestamp: u64,
@@ -25,6 +31,7 @@ impl Block {
         height: u64,
         transactions: Vec<Transaction>,
         previous_hash: String,
+        state_root: String,
         shared_key: String,
     ) -> Self {
         let header = BlockHeader {
@@ -32,6 +39,7 @@ impl Block {
             parent_hash: previous_hash,
             proposer_id: proposer_id.clone(),
             tx_count: transactions.len(),
+            state_root,
         };
         let mut block = Self {
             index,
@@ -40,6 +48,7 @@ impl Block {
             transactions,
             signature: String::new(),
             hash: String::new(),
+            proposer_signature: String::new(),
         };
 
         block.hash = block.calculate_hash();
@@ -65,6 +74,48 @@ impl Block {
         mac.verify_slice(&received_bytes).is_ok()
     }
 
+    // Спільний ключ має кожен вузол мережі, тож від імені лідера блок підписується ще й
+    // його власним ключем ідентичності
+    pub fn sign_as_proposer(&mut self, identity: &NodeIdentity) {
+        self.proposer_signature = identity.sign(&self.proposer_payload());
+    }
+
+    // Чи блок підписаний ключем, закріпленим за його лідером
+    pub fn verify_proposer(&self, pinned: &HashMap<String, String>) -> bool {
+        pinned.get(&self.header.proposer_id).is_some_and(|key| {
+            verify_signature(key, &self.proposer_payload(), &self.proposer_signature)
+        })
+    }
+
+    // Перевіряє хеш і підписи, не торкаючись стану. Генезис кожен вузол будує сам
+    // з конфігурації, тож підпису лідера в нього немає
+    pub fn validate(
+        &self,
+        shared_key: &str,
+        pinned: &HashMap<String, String>,
+    ) -> Result<(), BlockError> {
+        let height = self.header.height;
+        if self.hash != self.calculate_hash() {
+            return Err(BlockError::InvalidHash { height });
+        }
+        if !self.verify_signature(shared_key) {
+            return Err(BlockError::InvalidSignature { height });
+        }
+        if height > 0 && !self.verify_proposer(pinned) {
+            return Err(BlockError::ForgedBlock {
+                height,
+                proposer_id: self.header.proposer_id.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn proposer_payload(&self) -> Vec<u8> {
+        let mut payload = b"rust-chain block".to_vec();
+        payload.extend_from_slice(self.hash.as_bytes());
+        payload
+    }
+
     pub fn calculate_hash(&self) -> String {
         let transactions_string = self
             .transactions
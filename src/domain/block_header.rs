@@ -6,4 +6,5 @@ pub struct BlockHeader {
     pub parent_hash: String,
     pub proposer_id: String,
     pub tx_count: usize,
+    pub state_root: String,
 }
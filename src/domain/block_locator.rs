@@ -0,0 +1,144 @@
+use crate::domain::{
+    block::Block, block_header::BlockHeader, blockchain_repository::BlockchainRepository,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeaderEntry {
+    pub hash: String,
+    pub header: BlockHeader,
+}
+
+impl From<&Block> for BlockHeaderEntry {
+    fn from(block: &Block) -> Self {
+        Self {
+            hash: block.hash.clone(),
+            header: block.header.clone(),
+        }
+    }
+}
+
+// Хеші від вершини до генезису: перші 10 підряд, далі з кроком, що подвоюється
+pub fn block_locator(chain: &[Block]) -> Vec<String> {
+    let mut locator = Vec::new();
+    let Some(mut index) = chain.len().checked_sub(1) else {
+        return locator;
+    };
+    let mut step = 1;
+    loop {
+        locator.push(chain[index].hash.clone());
+        if index == 0 {
+            break;
+        }
+        if locator.len() >= 10 {
+            step *= 2;
+        }
+        index = index.saturating_sub(step);
+    }
+    locator
+}
+
+// До `limit` заголовків після найновішого блоку з локатора, який є в нашому ланцюгу;
+// без локатора — починаючи з висоти `from`
+pub async fn headers_after<B>(
+    repo: &B,
+    locator: &[String],
+    from: Option<u64>,
+    limit: u64,
+) -> Vec<BlockHeaderEntry>
+where
+    B: BlockchainRepository + ?Sized,
+{
+    let from = if locator.is_empty() {
+        from.unwrap_or(0)
+    } else {
+        let mut fork_point = None;
+        for hash in locator.iter().filter(|h| !h.is_empty()) {
+            if let Some(block) = repo.get_block_by_hash(hash).await {
+                fork_point = Some(block.header.height);
+                break;
+            }
+        }
+        fork_point.map_or(0, |height| height + 1)
+    };
+    repo.get_range(from, from.saturating_add(limit - 1))
+        .await
+        .iter()
+        .map(BlockHeaderEntry::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{block_locator, headers_after};
+    use crate::domain::block::Block;
+    use crate::domain::blockchain_repository::BlockchainRepository;
+    use crate::infrastructure::in_memory_blockchain_repository::InMemoryBlockchainRepository;
+
+    fn chain(len: u64) -> Vec<Block> {
+        let mut blocks: Vec<Block> = Vec::new();
+        for height in 0..len {
+            let parent = blocks.last().map_or("0".to_string(), |b| b.hash.clone());
+            blocks.push(Block::new(
+                height as u32,
+                height,
+                "v1".to_string(),
+                height,
+                Vec::new(),
+                parent,
+                String::new(),
+                "key".to_string(),
+            ));
+        }
+        blocks
+    }
+
+    #[test]
+    fn locator_is_dense_near_the_tip_and_ends_at_genesis() {
+        let blocks = chain(100);
+        let heights: Vec<u64> = block_locator(&blocks)
+            .iter()
+            .map(|hash| blocks.iter().position(|b| b.hash == *hash).unwrap() as u64)
+            .collect();
+        assert_eq!(
+            heights,
+            vec![
+                99, 98, 97, 96, 95, 94, 93, 92, 91, 90, 88, 84, 76, 60, 28, 0
+            ]
+        );
+        assert!(block_locator(&[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn headers_start_after_the_newest_known_locator_hash() {
+        let blocks = chain(30);
+        let mut repo = InMemoryBlockchainRepository::new();
+        for block in blocks.iter().cloned() {
+            repo.add_block(block).await.unwrap();
+        }
+        let first_height = |headers: Vec<super::BlockHeaderEntry>| headers[0].header.height;
+
+        // Відгалуження пира після #15: його вершини в нас немає, а #15 і #10 є
+        let locator = vec![
+            "foreign tip".to_string(),
+            blocks[15].hash.clone(),
+            blocks[10].hash.clone(),
+        ];
+        let headers = headers_after(&repo, &locator, None, 5).await;
+        assert_eq!(headers.len(), 5);
+        assert_eq!(first_height(headers), 16);
+
+        // Жодного спільного блоку — від генезису; без локатора — з `from`
+        let unknown = vec!["a".to_string(), "b".to_string()];
+        assert_eq!(
+            first_height(headers_after(&repo, &unknown, None, 5).await),
+            0
+        );
+        assert_eq!(first_height(headers_after(&repo, &[], Some(7), 5).await), 7);
+        assert!(
+            headers_after(&repo, &[blocks[29].hash.clone()], None, 5)
+                .await
+                .is_empty()
+        );
+    }
+}
@@ -1,10 +1,30 @@
-use crate::domain::block::Block;
+use crate::domain::{
+    account_transfer::AccountTransfer, block::Block, error::StorageError,
+    transaction::TransactionLocation,
+};
 use async_trait::async_trait;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait BlockchainRepository: Send + Sync {
     async fn get_all_blocks(&self) -> Vec<Block>;
-    async fn add_block(&mut self, block: Block);
-    async fn get_last_block(&self) -> Block;
-    async fn replace_chain(&mut self, new_chain: Vec<Block>);
+    // Якщо зберегти не вдалося, ланцюг у пам'яті лишається без змін
+    async fn add_block(&mut self, block: Block) -> Result<(), StorageError>;
+    // None для порожнього ланцюга
+    async fn get_last_block(&self) -> Option<Block>;
+    async fn replace_chain(&mut self, new_chain: Vec<Block>) -> Result<(), StorageError>;
+    async fn get_block_by_height(&self, height: u64) -> Option<Block>;
+    async fn get_block_by_hash(&self, hash: &str) -> Option<Block>;
+    // Блоки з висотами від `from` до `to` включно
+    async fn get_range(&self, from: u64, to: u64) -> Vec<Block>;
+    // Висота вершини; None для порожнього ланцюга
+    async fn height(&self) -> Option<u64>;
+    async fn get_transaction_location(&self, transaction_id: &Uuid) -> Option<TransactionLocation>;
+    // Перекази рахунку від новіших до старіших, починаючи з записів перед курсором `before`
+    async fn get_account_transfers(
+        &self,
+        address: &Uuid,
+        before: Option<usize>,
+        limit: usize,
+    ) -> Vec<AccountTransfer>;
 }
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::domain::{block::Block, transaction::Transaction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventTopic {
+    Blocks,
+    Votes,
+    Mempool,
+    Chain,
+}
+
+impl EventTopic {
+    pub const ALL: [EventTopic; 4] = [
+        EventTopic::Blocks,
+        EventTopic::Votes,
+        EventTopic::Mempool,
+        EventTopic::Chain,
+    ];
+}
+
+impl FromStr for EventTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "blocks" => Ok(EventTopic::Blocks),
+            "votes" => Ok(EventTopic::Votes),
+            "mempool" => Ok(EventTopic::Mempool),
+            "chain" => Ok(EventTopic::Chain),
+            other => Err(format!("Unknown event topic: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChainEvent {
+    BlockProposed {
+        height: u64,
+        hash: String,
+        proposer_id: String,
+    },
+    VoteReceived {
+        block_hash: String,
+        voter_id: String,
+        votes: usize,
+    },
+    // Вузол перевірив блок лідера й додав його до ланцюга; кворуму ще може не бути
+    BlockAccepted {
+        block: Block,
+    },
+    BlockFinalized {
+        block: Block,
+    },
+    TransactionAdmitted {
+        transaction: Transaction,
+    },
+    TransactionDropped {
+        transaction_id: Uuid,
+        reason: String,
+    },
+    // Ланцюг відкотився до fork_height; нові блоки після нього приходять як block_finalized
+    ChainReorganized {
+        fork_height: u64,
+        old_tip_height: u64,
+        new_tip_height: u64,
+        new_tip_hash: String,
+    },
+}
+
+impl ChainEvent {
+    pub fn topic(&self) -> EventTopic {
+        match self {
+            ChainEvent::BlockProposed { .. }
+            | ChainEvent::BlockAccepted { .. }
+            | ChainEvent::BlockFinalized { .. } => EventTopic::Blocks,
+            ChainEvent::VoteReceived { .. } => EventTopic::Votes,
+            ChainEvent::TransactionAdmitted { .. } | ChainEvent::TransactionDropped { .. } => {
+                EventTopic::Mempool
+            }
+            ChainEvent::ChainReorganized { .. } => EventTopic::Chain,
+        }
+    }
+
+    pub fn proposed(block: &Block) -> Self {
+        ChainEvent::BlockProposed {
+            height: block.header.height,
+            hash: block.hash.clone(),
+            proposer_id: block.header.proposer_id.clone(),
+        }
+    }
+}
@@ -0,0 +1,300 @@
+use crate::domain::error::ConfigError;
+use crate::domain::node_identity::is_identity_key;
+use crate::domain::peer_address::PeerAddress;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::Level;
+use uuid::Uuid;
+
+pub const DEFAULT_CHAIN_ID: &str = "devnet";
+pub const DEFAULT_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+pub const DEFAULT_VALIDATORS: [&str; 3] = ["v1", "v2", "v3"];
+pub const DEFAULT_SLOT_DURATION: Duration = Duration::from_secs(5);
+pub const DEFAULT_GENESIS_AMOUNT: f64 = 1_000_000.0;
+pub const DEFAULT_MAX_OUTBOUND_PEERS: usize = 8;
+pub const DEFAULT_MAX_INBOUND_PEERS: usize = 16;
+// Без --p2p-port P2P-транспорт слухає на HTTP-порту + 1000
+pub const DEFAULT_P2P_PORT_OFFSET: u16 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// `2026-01-01T00:00:00.000000Z  INFO slot{slot=3}: Block proposed height=4`
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+/// Everything the node is started with, merged from the config file, the environment and the command line.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub node: NodeConfig,
+    pub consensus: ConsensusConfig,
+    pub genesis: GenesisConfig,
+    pub keys: KeysConfig,
+    pub storage: StorageConfig,
+    pub api: ApiConfig,
+    pub log: LogConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeConfig {
+    pub id: String,
+    pub chain_id: String,
+    pub bind_address: IpAddr,
+    pub port: u16,
+    // Порт P2P-транспорту для блоків, голосів, транзакцій і синхронізації
+    pub p2p_port: u16,
+    // Піри, до яких вузол підключається завжди
+    pub peers: Vec<PeerAddress>,
+    // Вузли, з яких на старті беруться адреси інших пірів
+    pub bootstrap: Vec<PeerAddress>,
+    pub advertise_address: PeerAddress,
+    pub max_outbound_peers: usize,
+    pub max_inbound_peers: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConsensusConfig {
+    pub validators: Vec<String>,
+    // Ключі ідентичності, які мають пред'являти названі вузли
+    pub validator_keys: HashMap<String, String>,
+    pub slot_duration: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct GenesisConfig {
+    pub sender_id: Uuid,
+    pub faucet_wallet_id: Uuid,
+    pub amount: f64,
+}
+
+#[derive(Clone)]
+pub struct KeysConfig {
+    pub shared_key: String,
+    // Без токена адмінські ендпоінти вимкнені
+    pub admin_token: Option<String>,
+}
+
+// Ключ не потрапляє в логи навіть через {:?}
+impl std::fmt::Debug for KeysConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeysConfig")
+            .field("shared_key", &"<redacted>")
+            .field(
+                "admin_token",
+                &self.admin_token.as_ref().map(|_| "<redacted>"),
+            )
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    pub chain_path: Option<PathBuf>,
+    pub mempool_path: Option<PathBuf>,
+    pub state_dir: Option<PathBuf>,
+    // Без файлу ключ ідентичності генерується заново на кожному запуску
+    pub identity_key_path: Option<PathBuf>,
+    pub address_book_path: Option<PathBuf>,
+    pub snapshot_interval: u64,
+    pub fast_sync: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiConfig {
+    // GET /blocks і GET /balances без параметрів віддають усе одразу, як раніше
+    pub legacy_unpaged_routes: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub level: Level,
+    pub format: LogFormat,
+}
+
+impl Config {
+    pub fn bind_socket(&self) -> SocketAddr {
+        SocketAddr::new(self.node.bind_address, self.node.port)
+    }
+
+    pub fn p2p_socket(&self) -> SocketAddr {
+        SocketAddr::new(self.node.bind_address, self.node.p2p_port)
+    }
+
+    // Перевіряє значення, які неможливо відкинути на етапі розбору
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.node.id.trim().is_empty() {
+            return Err(ConfigError::Missing { key: "node.id" });
+        }
+        if self.node.chain_id.trim().is_empty() {
+            return Err(ConfigError::Missing {
+                key: "node.chain_id",
+            });
+        }
+        let mut authorities = HashSet::new();
+        for peer in &self.node.peers {
+            if !authorities.insert(peer.authority()) {
+                return Err(invalid(
+                    "node.peers",
+                    &format!("{} is listed twice", peer.authority()),
+                ));
+            }
+        }
+        if self.node.p2p_port == self.node.port {
+            return Err(invalid("node.p2p_port", "must differ from node.port"));
+        }
+        if self.node.max_outbound_peers == 0 {
+            return Err(invalid("node.max_outbound_peers", "must be positive"));
+        }
+        if let Some(node_id) = &self.node.advertise_address.node_id
+            && *node_id != self.node.id
+        {
+            return Err(invalid(
+                "node.advertise_address",
+                &format!("names node {}, but this node is {}", node_id, self.node.id),
+            ));
+        }
+        if self.consensus.validators.is_empty() {
+            return Err(ConfigError::Missing {
+                key: "consensus.validators",
+            });
+        }
+        let mut seen = HashSet::new();
+        for validator in &self.consensus.validators {
+            if validator.trim().is_empty() {
+                return Err(invalid(
+                    "consensus.validators",
+                    "validator ids cannot be empty",
+                ));
+            }
+            if !seen.insert(validator) {
+                return Err(invalid(
+                    "consensus.validators",
+                    &format!("{} is listed twice", validator),
+                ));
+            }
+        }
+        // Без закріпленого ключа будь-хто зі спільним ключем міг би голосувати від імені валідатора
+        if let Some(validator) = self
+            .consensus
+            .validators
+            .iter()
+            .find(|validator| !self.consensus.validator_keys.contains_key(*validator))
+        {
+            return Err(invalid(
+                "consensus.validator_keys",
+                &format!("no identity key for validator {}", validator),
+            ));
+        }
+        for (node_id, key) in &self.consensus.validator_keys {
+            if !is_identity_key(key) {
+                return Err(invalid(
+                    "consensus.validator_keys",
+                    &format!("key of {} is not 64 hex digits", node_id),
+                ));
+            }
+        }
+        if self.consensus.slot_duration.is_zero() {
+            return Err(invalid("consensus.slot_duration_secs", "must be positive"));
+        }
+        if !self.genesis.amount.is_finite() || self.genesis.amount <= 0.0 {
+            return Err(invalid("genesis.amount", "must be a positive number"));
+        }
+        if self.genesis.sender_id == self.genesis.faucet_wallet_id {
+            return Err(invalid(
+                "genesis.faucet_wallet_id",
+                "must differ from genesis.sender_id",
+            ));
+        }
+        if self.keys.shared_key.is_empty() {
+            return Err(ConfigError::Missing {
+                key: "keys.shared_key",
+            });
+        }
+        if self
+            .keys
+            .admin_token
+            .as_ref()
+            .is_some_and(|token| token.is_empty())
+        {
+            return Err(invalid("keys.admin_token", "cannot be empty"));
+        }
+        if self.storage.snapshot_interval == 0 {
+            return Err(invalid("storage.snapshot_interval", "must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Config {
+    // Конфігурація вузла для тестів; нічого не пише на диск
+    pub fn for_tests(id: &str, validators: &[&str]) -> Self {
+        Config {
+            node: NodeConfig {
+                id: id.to_string(),
+                chain_id: DEFAULT_CHAIN_ID.to_string(),
+                bind_address: Ipv4Addr::LOCALHOST.into(),
+                port: 3001,
+                p2p_port: 4001,
+                peers: Vec::new(),
+                bootstrap: Vec::new(),
+                advertise_address: format!("{}@localhost:3001", id)
+                    .parse()
+                    .expect("valid peer address"),
+                max_outbound_peers: DEFAULT_MAX_OUTBOUND_PEERS,
+                max_inbound_peers: DEFAULT_MAX_INBOUND_PEERS,
+            },
+            consensus: ConsensusConfig {
+                validators: validators.iter().map(|v| v.to_string()).collect(),
+                validator_keys: validators
+                    .iter()
+                    .map(|v| {
+                        (
+                            v.to_string(),
+                            crate::domain::node_identity::NodeIdentity::for_tests(v).public_key(),
+                        )
+                    })
+                    .collect(),
+                slot_duration: DEFAULT_SLOT_DURATION,
+            },
+            genesis: GenesisConfig {
+                sender_id: Uuid::nil(),
+                faucet_wallet_id: Uuid::from_u128(1),
+                amount: DEFAULT_GENESIS_AMOUNT,
+            },
+            keys: KeysConfig {
+                shared_key: "test-key".to_string(),
+                admin_token: None,
+            },
+            storage: StorageConfig {
+                chain_path: None,
+                mempool_path: None,
+                state_dir: None,
+                identity_key_path: None,
+                address_book_path: None,
+                snapshot_interval: 100,
+                fast_sync: false,
+            },
+            api: ApiConfig {
+                legacy_unpaged_routes: false,
+            },
+            log: LogConfig {
+                level: Level::INFO,
+                format: LogFormat::Text,
+            },
+        }
+    }
+}
+
+fn invalid(key: &'static str, reason: &str) -> ConfigError {
+    ConfigError::Invalid {
+        key,
+        reason: reason.to_string(),
+    }
+}
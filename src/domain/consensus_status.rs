@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+// Поточний слот PoS-циклу; оновлюється на початку кожного слоту
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsensusStatus {
+    pub slot: u64,
+    pub leader: Option<String>,
+    // Найвищий блок, за який зібрано кворум; блоки вище вже в ланцюгу, але ще чекають голосів
+    pub finalized_height: Option<u64>,
+}
@@ -0,0 +1,403 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use uuid::Uuid;
+
+/// Why a transaction cannot be applied to the current balances.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionError {
+    SameAddress {
+        address: Uuid,
+    },
+    InvalidAmount {
+        amount: f64,
+    },
+    InsufficientFunds {
+        address: Uuid,
+        balance: f64,
+        amount: f64,
+    },
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::SameAddress { .. } => {
+                write!(f, "Sender and receiver addresses cannot be the same")
+            }
+            TransactionError::InvalidAmount { amount } => write!(
+                f,
+                "Transaction amount must be a positive finite number, got {}",
+                amount
+            ),
+            TransactionError::InsufficientFunds {
+                address,
+                balance,
+                amount,
+            } => write!(
+                f,
+                "Insufficient balance: {} has {}, needs {}",
+                address, balance, amount
+            ),
+        }
+    }
+}
+
+impl TransactionError {
+    // Коротка мітка причини для метрик
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TransactionError::SameAddress { .. } => "same_address",
+            TransactionError::InvalidAmount { .. } => "invalid_amount",
+            TransactionError::InsufficientFunds { .. } => "insufficient_funds",
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+/// Failure to persist chain data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageError {
+    Io(String),
+    Serialization(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(message) => write!(f, "I/O error: {}", message),
+            StorageError::Serialization(message) => write!(f, "Serialization error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        StorageError::Serialization(e.to_string())
+    }
+}
+
+/// Why a block cannot be appended to the chain.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockError {
+    InvalidHash {
+        height: u64,
+    },
+    InvalidSignature {
+        height: u64,
+    },
+    DoesNotExtendTip {
+        height: u64,
+        tip_height: u64,
+    },
+    InvalidTransaction {
+        height: u64,
+        transaction_id: Uuid,
+        reason: TransactionError,
+    },
+    StateRootMismatch {
+        height: u64,
+        expected: String,
+        actual: String,
+    },
+    UnknownProposer {
+        height: u64,
+        proposer_id: String,
+    },
+    // Блок не підписаний ключем, закріпленим за його лідером
+    ForgedBlock {
+        height: u64,
+        proposer_id: String,
+    },
+    EmptyChain,
+    // Ланцюг починається зі снапшоту, а самого снапшоту немає, тож стан нема з чого відновити
+    MissingState {
+        height: u64,
+    },
+    Storage(StorageError),
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockError::InvalidHash { height } => {
+                write!(f, "Block #{} has an invalid hash", height)
+            }
+            BlockError::InvalidSignature { height } => {
+                write!(f, "Block #{} has an invalid signature", height)
+            }
+            BlockError::DoesNotExtendTip { height, tip_height } => write!(
+                f,
+                "Block #{} does not extend the chain tip #{}",
+                height, tip_height
+            ),
+            BlockError::InvalidTransaction {
+                height,
+                transaction_id,
+                reason,
+            } => write!(
+                f,
+                "Block #{} contains invalid transaction {}: {}",
+                height, transaction_id, reason
+            ),
+            BlockError::StateRootMismatch {
+                height,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Block #{} declares state root {} but its transactions produce {}",
+                height, expected, actual
+            ),
+            BlockError::UnknownProposer {
+                height,
+                proposer_id,
+            } => write!(
+                f,
+                "Block #{} was proposed by {}, which is not a validator",
+                height, proposer_id
+            ),
+            BlockError::ForgedBlock {
+                height,
+                proposer_id,
+            } => write!(
+                f,
+                "Block #{} is not signed with the identity key of its proposer {}",
+                height, proposer_id
+            ),
+            BlockError::EmptyChain => write!(f, "Local chain is empty"),
+            BlockError::MissingState { height } => write!(
+                f,
+                "Chain starts at block #{} and no snapshot of the state there is stored",
+                height
+            ),
+            BlockError::Storage(e) => write!(f, "Failed to store block: {}", e),
+        }
+    }
+}
+
+impl BlockError {
+    // Коротка мітка причини для метрик
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BlockError::InvalidHash { .. } => "invalid_hash",
+            BlockError::InvalidSignature { .. } => "invalid_signature",
+            BlockError::DoesNotExtendTip { .. } => "does_not_extend_tip",
+            BlockError::InvalidTransaction { .. } => "invalid_transaction",
+            BlockError::StateRootMismatch { .. } => "state_root_mismatch",
+            BlockError::UnknownProposer { .. } => "unknown_proposer",
+            BlockError::ForgedBlock { .. } => "forged_block",
+            BlockError::EmptyChain => "empty_chain",
+            BlockError::MissingState { .. } => "missing_state",
+            BlockError::Storage(_) => "storage",
+        }
+    }
+}
+
+impl std::error::Error for BlockError {}
+
+impl From<StorageError> for BlockError {
+    fn from(e: StorageError) -> Self {
+        BlockError::Storage(e)
+    }
+}
+
+/// Why a chain sync stopped before reaching the best peer's tip.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncError {
+    NoCommonAncestor { node_id: String },
+    UnlinkedHeaders { node_id: String },
+    Download { batch: usize, reason: String },
+    // Поки блоки завантажувалися, локальний ланцюг змінився
+    ChainChanged,
+    Block(BlockError),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncError::NoCommonAncestor { node_id } => {
+                write!(f, "Chain of {} has no common ancestor with ours", node_id)
+            }
+            SyncError::UnlinkedHeaders { node_id } => {
+                write!(f, "Headers from {} do not form a chain", node_id)
+            }
+            SyncError::Download { batch, reason } => {
+                write!(f, "Failed to download batch {}: {}", batch, reason)
+            }
+            SyncError::ChainChanged => write!(f, "Local chain changed during sync"),
+            SyncError::Block(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<BlockError> for SyncError {
+    fn from(e: BlockError) -> Self {
+        SyncError::Block(e)
+    }
+}
+
+impl From<StorageError> for SyncError {
+    fn from(e: StorageError) -> Self {
+        SyncError::Block(BlockError::Storage(e))
+    }
+}
+
+/// Why the node configuration cannot be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    File { path: PathBuf, reason: String },
+    Missing { key: &'static str },
+    Invalid { key: &'static str, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, reason } => {
+                write!(f, "Cannot load config file {}: {}", path.display(), reason)
+            }
+            ConfigError::Missing { key } => write!(f, "{} is not set", key),
+            ConfigError::Invalid { key, reason } => write!(f, "{} is invalid: {}", key, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Why a peer address given on the command line or in the config file cannot be used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerAddressError {
+    pub address: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for PeerAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid peer address {:?}: {}",
+            self.address, self.reason
+        )
+    }
+}
+
+impl std::error::Error for PeerAddressError {}
+
+/// Why a peer's handshake does not match this node's network.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandshakeError {
+    ProtocolVersion { expected: u32, actual: u32 },
+    ChainId { expected: String, actual: String },
+    GenesisHash { expected: String, actual: String },
+    ValidatorKey { node_id: String },
+    NodeId { expected: String, actual: String },
+    IdentityKey { node_id: String },
+    SelfConnection,
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandshakeError::ProtocolVersion { expected, actual } => write!(
+                f,
+                "Protocol version {} is not supported, expected {}",
+                actual, expected
+            ),
+            HandshakeError::ChainId { expected, actual } => {
+                write!(f, "Chain id {} does not match {}", actual, expected)
+            }
+            HandshakeError::GenesisHash { expected, actual } => {
+                write!(f, "Genesis hash {} does not match {}", actual, expected)
+            }
+            HandshakeError::ValidatorKey { node_id } => {
+                write!(
+                    f,
+                    "Validator key of {} was not made with our signing key",
+                    node_id
+                )
+            }
+            HandshakeError::NodeId { expected, actual } => write!(
+                f,
+                "Peer answered as {}, but is configured as {}",
+                actual, expected
+            ),
+            HandshakeError::IdentityKey { node_id } => write!(
+                f,
+                "Identity key of {} does not match the key configured for it",
+                node_id
+            ),
+            HandshakeError::SelfConnection => write!(f, "Peer address points at this node"),
+        }
+    }
+}
+
+impl HandshakeError {
+    // Коротка мітка причини для метрик
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HandshakeError::ProtocolVersion { .. } => "protocol_version",
+            HandshakeError::ChainId { .. } => "chain_id",
+            HandshakeError::GenesisHash { .. } => "genesis_hash",
+            HandshakeError::ValidatorKey { .. } => "validator_key",
+            HandshakeError::NodeId { .. } => "node_id",
+            HandshakeError::IdentityKey { .. } => "identity_key",
+            HandshakeError::SelfConnection => "self_connection",
+        }
+    }
+}
+
+impl std::error::Error for HandshakeError {}
+
+/// Why a frame read from a peer connection cannot be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameError {
+    // Пір закрив з'єднання між кадрами
+    Closed,
+    Io(String),
+    UnknownKind(u8),
+    TooLarge {
+        kind: &'static str,
+        len: usize,
+        max: usize,
+    },
+    Malformed {
+        kind: &'static str,
+        reason: String,
+    },
+    // Кадр не пройшов перевірку тегу: змінений дорогою або зашифрований іншим ключем
+    Decrypt,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Closed => write!(f, "Connection closed"),
+            FrameError::Io(reason) => write!(f, "Connection failed: {}", reason),
+            FrameError::UnknownKind(kind) => write!(f, "Unknown message type {}", kind),
+            FrameError::TooLarge { kind, len, max } => write!(
+                f,
+                "{} message of {} bytes exceeds the limit of {} bytes",
+                kind, len, max
+            ),
+            FrameError::Malformed { kind, reason } => {
+                write!(f, "Malformed {} message: {}", kind, reason)
+            }
+            FrameError::Decrypt => write!(f, "Message failed authentication"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
@@ -0,0 +1,35 @@
+use tokio::sync::broadcast;
+
+use crate::domain::chain_event::ChainEvent;
+
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+
+/// In-process fan-out of chain events. Use cases publish to it and every
+/// `/events` connection holds its own receiver; a receiver that falls more
+/// than `EVENT_BUS_CAPACITY` events behind loses the oldest ones.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ChainEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: ChainEvent) {
+        // Помилка означає лише, що зараз ніхто не слухає
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
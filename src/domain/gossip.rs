@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+// Скільки переходів між вузлами може зробити блок чи голос від автора
+pub const GOSSIP_TTL: u8 = 8;
+// Скільки секунд пам'ятаємо хеш; повтор після цього знову пройде перевірку і буде відкинутий як застарілий
+const SEEN_FOR_SECS: u64 = 600;
+const MAX_SEEN: usize = 16 * 1024;
+
+/// A block or vote on its way across the network. Every node that relays it
+/// lowers `ttl`; at zero it is handled but not passed on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gossip<T> {
+    pub ttl: u8,
+    #[serde(flatten)]
+    pub item: T,
+}
+
+impl<T: Clone> Gossip<T> {
+    // Нове повідомлення від цього вузла
+    pub fn new(item: T) -> Self {
+        Self {
+            ttl: GOSSIP_TTL,
+            item,
+        }
+    }
+
+    // Копія для пересилання сусідам; None, коли переходів не лишилося.
+    // ttl понад GOSSIP_TTL обрізається, щоб пір не міг розіслати повідомлення далі, ніж дозволено
+    pub fn next_hop(&self) -> Option<Self> {
+        let ttl = self
+            .ttl
+            .min(GOSSIP_TTL)
+            .checked_sub(1)
+            .filter(|ttl| *ttl > 0)?;
+        Some(Self {
+            ttl,
+            item: self.item.clone(),
+        })
+    }
+}
+
+/// Hashes of blocks and votes this node has already handled, so that each one
+/// is processed and relayed once however many peers send it.
+#[derive(Debug, Default)]
+pub struct SeenMessages {
+    seen: HashMap<String, u64>,
+    // Хеші в порядку появи, щоб спершу забувати найстаріші
+    order: VecDeque<(u64, String)>,
+}
+
+impl SeenMessages {
+    // Чи хеш ще пам'ятається; нічого не запам'ятовує
+    pub fn contains(&self, hash: &str, now: u64) -> bool {
+        self.seen
+            .get(hash)
+            .is_some_and(|seen_at| seen_at + SEEN_FOR_SECS > now)
+    }
+
+    // true, якщо хеш побачено вперше
+    pub fn insert(&mut self, hash: &str, now: u64) -> bool {
+        self.expire(now);
+        if self.seen.contains_key(hash) {
+            return false;
+        }
+        if self.order.len() >= MAX_SEEN
+            && let Some((_, oldest)) = self.order.pop_front()
+        {
+            self.seen.remove(&oldest);
+        }
+        self.seen.insert(hash.to_string(), now);
+        self.order.push_back((now, hash.to_string()));
+        true
+    }
+
+    fn expire(&mut self, now: u64) {
+        while let Some((seen_at, _)) = self.order.front() {
+            if seen_at + SEEN_FOR_SECS > now {
+                break;
+            }
+            if let Some((_, hash)) = self.order.pop_front() {
+                self.seen.remove(&hash);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GOSSIP_TTL, Gossip, MAX_SEEN, SEEN_FOR_SECS, SeenMessages};
+
+    #[test]
+    fn next_hop_lowers_the_ttl_and_caps_an_inflated_one() {
+        let fresh = Gossip::new(());
+        assert_eq!(fresh.next_hop().map(|g| g.ttl), Some(GOSSIP_TTL - 1));
+
+        // Завищений ttl від піра не дає повідомленню піти далі
+        let inflated = Gossip {
+            ttl: u8::MAX,
+            item: (),
+        };
+        assert_eq!(inflated.next_hop().map(|g| g.ttl), Some(GOSSIP_TTL - 1));
+        assert!(Gossip { ttl: 1, item: () }.next_hop().is_none());
+        assert!(Gossip { ttl: 0, item: () }.next_hop().is_none());
+    }
+
+    #[test]
+    fn forgets_hashes_after_a_while_and_beyond_capacity() {
+        let mut seen = SeenMessages::default();
+        assert!(!seen.contains("a", 0));
+        assert!(seen.insert("a", 0));
+        assert!(seen.contains("a", SEEN_FOR_SECS - 1));
+        assert!(!seen.insert("a", SEEN_FOR_SECS - 1));
+        assert!(!seen.contains("a", SEEN_FOR_SECS));
+        assert!(seen.insert("a", SEEN_FOR_SECS));
+
+        for i in 0..MAX_SEEN + 1 {
+            seen.insert(&i.to_string(), SEEN_FOR_SECS);
+        }
+        // Найстаріші хеші витіснено, решта ще пам'ятається
+        assert!(seen.insert("0", SEEN_FOR_SECS));
+        assert!(!seen.insert(&MAX_SEEN.to_string(), SEEN_FOR_SECS));
+    }
+}
@@ -0,0 +1,194 @@
+use crate::domain::error::HandshakeError;
+use crate::domain::node_identity::is_identity_key;
+use crate::domain::peer_address::PeerAddress;
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Збільшується, коли змінюється формат повідомлень між вузлами
+pub const PROTOCOL_VERSION: u32 = 6;
+
+/// What two nodes tell each other before exchanging blocks and votes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Handshake {
+    pub node_id: String,
+    pub chain_id: String,
+    pub genesis_hash: String,
+    pub protocol_version: u32,
+    pub best_height: u64,
+    // Відкритий ключ Ed25519, яким вузол підписує кожне P2P-з'єднання
+    pub identity_key: String,
+    // Блоки підписуються спільним HMAC-ключем, а не парою ключів валідатора,
+    // тож тут HMAC(ключ, identity_key і node_id): доказ, що пір знає ключ підпису
+    // і сам назвав цей identity_key своїм
+    pub validator_pubkey: String,
+    // Адреса, за якою до вузла можна підключитися; вузли до версії з обміном адрес її не надсилають
+    #[serde(default)]
+    pub listen_address: Option<PeerAddress>,
+    // Порт P2P-транспорту на тому ж хості, що й listen_address
+    #[serde(default)]
+    pub p2p_port: Option<u16>,
+}
+
+impl Handshake {
+    pub fn validator_key(shared_key: &str, node_id: &str, identity_key: &str) -> String {
+        hex::encode(
+            validator_mac(shared_key, node_id, identity_key)
+                .finalize()
+                .into_bytes(),
+        )
+    }
+
+    // Чи validator_pubkey — це validator_key для цієї пари; порівняння за сталий час
+    pub fn vouches_for(
+        shared_key: &str,
+        node_id: &str,
+        identity_key: &str,
+        validator_pubkey: &str,
+    ) -> bool {
+        hex::decode(validator_pubkey).is_ok_and(|bytes| {
+            validator_mac(shared_key, node_id, identity_key)
+                .verify_slice(&bytes)
+                .is_ok()
+        })
+    }
+
+    // Перевіряє handshake піра відносно нашого. pinned — ключі, закріплені за вузлами в конфігурації
+    pub fn check(
+        &self,
+        local: &Handshake,
+        shared_key: &str,
+        pinned: &HashMap<String, String>,
+    ) -> Result<(), HandshakeError> {
+        if self.protocol_version != local.protocol_version {
+            return Err(HandshakeError::ProtocolVersion {
+                expected: local.protocol_version,
+                actual: self.protocol_version,
+            });
+        }
+        if self.chain_id != local.chain_id {
+            return Err(HandshakeError::ChainId {
+                expected: local.chain_id.clone(),
+                actual: self.chain_id.clone(),
+            });
+        }
+        if self.genesis_hash != local.genesis_hash {
+            return Err(HandshakeError::GenesisHash {
+                expected: local.genesis_hash.clone(),
+                actual: self.genesis_hash.clone(),
+            });
+        }
+        if self.node_id == local.node_id {
+            return Err(HandshakeError::SelfConnection);
+        }
+        let key_valid = is_identity_key(&self.identity_key)
+            && Self::vouches_for(
+                shared_key,
+                &self.node_id,
+                &self.identity_key,
+                &self.validator_pubkey,
+            );
+        if !key_valid {
+            return Err(HandshakeError::ValidatorKey {
+                node_id: self.node_id.clone(),
+            });
+        }
+        // Config::validate закріплює ключ за кожним валідатором. Id решти вузлів прив'язується
+        // до ключа їхнього P2P-з'єднання, доки воно відкрите
+        if let Some(expected) = pinned.get(&self.node_id)
+            && !expected.eq_ignore_ascii_case(&self.identity_key)
+        {
+            return Err(HandshakeError::IdentityKey {
+                node_id: self.node_id.clone(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn validator_mac(shared_key: &str, node_id: &str, identity_key: &str) -> HmacSha256 {
+    let mut mac = <HmacSha256 as KeyInit>::new_from_slice(shared_key.as_bytes())
+        .expect("HMAC new from slice failed");
+    mac.update(b"handshake:");
+    // Ключ фіксованої довжини йде першим, тож межа між ним і node_id однозначна
+    mac.update(identity_key.as_bytes());
+    mac.update(node_id.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handshake, PROTOCOL_VERSION};
+    use crate::domain::error::HandshakeError;
+    use crate::domain::node_identity::NodeIdentity;
+    use std::collections::HashMap;
+
+    const SHARED_KEY: &str = "test-key";
+
+    fn handshake(node_id: &str, identity: &NodeIdentity) -> Handshake {
+        let identity_key = identity.public_key();
+        Handshake {
+            node_id: node_id.to_string(),
+            chain_id: "devnet".to_string(),
+            genesis_hash: "00".repeat(32),
+            protocol_version: PROTOCOL_VERSION,
+            best_height: 0,
+            validator_pubkey: Handshake::validator_key(SHARED_KEY, node_id, &identity_key),
+            identity_key,
+            listen_address: None,
+            p2p_port: None,
+        }
+    }
+
+    fn pinned(validators: &[&str]) -> HashMap<String, String> {
+        validators
+            .iter()
+            .map(|v| (v.to_string(), NodeIdentity::for_tests(v).public_key()))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_validator_presenting_its_pinned_key() {
+        let local = handshake("v1", &NodeIdentity::for_tests("v1"));
+        let peer = handshake("v2", &NodeIdentity::for_tests("v2"));
+
+        assert!(
+            peer.check(&local, SHARED_KEY, &pinned(&["v1", "v2", "v3"]))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn rejects_a_validator_id_claimed_with_another_key() {
+        let local = handshake("v1", &NodeIdentity::for_tests("v1"));
+        // v3 знає спільний ключ, тож HMAC для чужого node_id він підробить
+        let forged = handshake("v2", &NodeIdentity::for_tests("v3"));
+
+        let error = forged
+            .check(&local, SHARED_KEY, &pinned(&["v1", "v2", "v3"]))
+            .unwrap_err();
+
+        assert!(
+            matches!(&error, HandshakeError::IdentityKey { node_id } if node_id == "v2"),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn rejects_a_key_not_vouched_for_with_the_shared_key() {
+        let local = handshake("v1", &NodeIdentity::for_tests("v1"));
+        let mut peer = handshake("v2", &NodeIdentity::for_tests("v2"));
+        peer.validator_pubkey = Handshake::validator_key("other-key", "v2", &peer.identity_key);
+
+        let error = peer
+            .check(&local, SHARED_KEY, &pinned(&["v1", "v2", "v3"]))
+            .unwrap_err();
+
+        assert!(matches!(error, HandshakeError::ValidatorKey { .. }));
+    }
+}
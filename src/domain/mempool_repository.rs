@@ -1,4 +1,4 @@
-use crate::domain::transaction::Transaction;
+use crate::domain::{block::Block, transaction::Transaction};
 use std::collections::VecDeque;
 use uuid::Uuid;
 
@@ -9,5 +9,7 @@ pub trait MempoolRepository: Send + Sync {
     fn add_transaction(&mut self, transaction: Transaction);
     fn get_all_transactions(&self) -> &VecDeque<Transaction>;
     fn check_exists_by_id(&self, transaction_id: &Uuid) -> bool;
+    fn get_transaction_by_id(&self, transaction_id: &Uuid) -> Option<Transaction>;
     fn drain_transactions(&mut self) -> VecDeque<Transaction>;
+    fn remove_included(&mut self, block: &Block);
 }
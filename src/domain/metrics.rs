@@ -0,0 +1,322 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const BLOCKS_PROPOSED: &str = "blocks_proposed_total";
+pub const BLOCKS_ACCEPTED: &str = "blocks_accepted_total";
+pub const BLOCKS_REJECTED: &str = "blocks_rejected_total";
+pub const VOTES_RECEIVED: &str = "votes_received_total";
+pub const QUORUM_LATENCY: &str = "quorum_latency_seconds";
+pub const SLOT_MISSES: &str = "slot_misses_total";
+pub const MEMPOOL_SIZE: &str = "mempool_size";
+pub const MEMPOOL_REJECTIONS: &str = "mempool_rejections_total";
+pub const SYNC_RUNS: &str = "sync_runs_total";
+pub const SYNC_DURATION: &str = "sync_duration_seconds";
+pub const HTTP_REQUEST_DURATION: &str = "http_request_duration_seconds";
+pub const PEER_BROADCAST_FAILURES: &str = "peer_broadcast_failures_total";
+pub const PEER_MESSAGES: &str = "peer_messages_total";
+pub const PEER_LINKS: &str = "peer_links";
+pub const GOSSIP_RELAYED: &str = "gossip_relayed_total";
+pub const GOSSIP_DUPLICATES: &str = "gossip_duplicates_total";
+pub const PEER_HANDSHAKE_FAILURES: &str = "peer_handshake_failures_total";
+pub const PEER_PENALTIES: &str = "peer_penalties_total";
+pub const PEER_BANS: &str = "peer_bans_total";
+pub const PEERS_CONNECTED: &str = "peers_connected";
+pub const ADDRESS_BOOK_SIZE: &str = "address_book_size";
+
+const BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+            Kind::Histogram => "histogram",
+        }
+    }
+}
+
+// Порядок тут визначає порядок у виводі /metrics
+const FAMILIES: [(&str, Kind, &str); 21] = [
+    (
+        BLOCKS_PROPOSED,
+        Kind::Counter,
+        "Blocks proposed by this node as slot leader.",
+    ),
+    (
+        BLOCKS_ACCEPTED,
+        Kind::Counter,
+        "Blocks from other leaders accepted into the chain.",
+    ),
+    (
+        BLOCKS_REJECTED,
+        Kind::Counter,
+        "Blocks from other leaders rejected, by reason.",
+    ),
+    (
+        VOTES_RECEIVED,
+        Kind::Counter,
+        "Votes received, by decision.",
+    ),
+    (
+        QUORUM_LATENCY,
+        Kind::Histogram,
+        "Time from proposing a block to collecting a quorum of votes.",
+    ),
+    (
+        SLOT_MISSES,
+        Kind::Counter,
+        "Leader slots in which this node produced no block, by reason.",
+    ),
+    (
+        MEMPOOL_SIZE,
+        Kind::Gauge,
+        "Transactions waiting in the mempool.",
+    ),
+    (
+        MEMPOOL_REJECTIONS,
+        Kind::Counter,
+        "Transactions refused admission to the mempool, by reason.",
+    ),
+    (SYNC_RUNS, Kind::Counter, "Chain synchronisation runs."),
+    (
+        SYNC_DURATION,
+        Kind::Histogram,
+        "Duration of chain synchronisation runs.",
+    ),
+    (
+        HTTP_REQUEST_DURATION,
+        Kind::Histogram,
+        "HTTP request latency, by route, method and status.",
+    ),
+    (
+        PEER_BROADCAST_FAILURES,
+        Kind::Counter,
+        "Messages not queued for a peer, by message and reason.",
+    ),
+    (
+        PEER_MESSAGES,
+        Kind::Counter,
+        "Messages sent and received over the P2P transport, by direction and message.",
+    ),
+    (PEER_LINKS, Kind::Gauge, "Open P2P connections."),
+    (
+        GOSSIP_RELAYED,
+        Kind::Counter,
+        "Blocks, votes and transactions passed on to other peers, by message.",
+    ),
+    (
+        GOSSIP_DUPLICATES,
+        Kind::Counter,
+        "Blocks and votes dropped because they were already seen, by message.",
+    ),
+    (
+        PEER_HANDSHAKE_FAILURES,
+        Kind::Counter,
+        "Handshakes refused by or with peers, by reason.",
+    ),
+    (
+        PEER_PENALTIES,
+        Kind::Counter,
+        "Score penalties given to peers for misbehaviour, by reason.",
+    ),
+    (
+        PEER_BANS,
+        Kind::Counter,
+        "Peers banned, by source: score or admin.",
+    ),
+    (
+        PEERS_CONNECTED,
+        Kind::Gauge,
+        "Peers with a live handshake, by direction.",
+    ),
+    (
+        ADDRESS_BOOK_SIZE,
+        Kind::Gauge,
+        "Peer addresses in the address book.",
+    ),
+];
+
+// Мітки серії — пари ім'я=значення в тому порядку, в якому їх передали
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Process-wide metrics rendered in the Prometheus text format by `GET /metrics`.
+pub struct Metrics {
+    values: Mutex<BTreeMap<(&'static str, Labels), f64>>,
+    histograms: Mutex<BTreeMap<(&'static str, Labels), Histogram>>,
+    // Коли лідер запропонував блок, що ще чекає на кворум
+    proposed_at: Mutex<HashMap<String, Instant>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let metrics = Self {
+            values: Mutex::new(BTreeMap::new()),
+            histograms: Mutex::new(BTreeMap::new()),
+            proposed_at: Mutex::new(HashMap::new()),
+        };
+        // Лічильники без міток видно з нулем ще до першої події
+        for name in [BLOCKS_PROPOSED, BLOCKS_ACCEPTED, SYNC_RUNS] {
+            metrics.add(name, &[], 0.0);
+        }
+        metrics
+    }
+
+    pub fn inc(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        self.add(name, labels, 1.0);
+    }
+
+    pub fn set(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.values
+            .lock()
+            .unwrap()
+            .insert((name, to_labels(labels)), value);
+    }
+
+    pub fn observe(&self, name: &'static str, labels: &[(&'static str, &str)], value: Duration) {
+        self.histograms
+            .lock()
+            .unwrap()
+            .entry((name, to_labels(labels)))
+            .or_default()
+            .observe(value.as_secs_f64());
+    }
+
+    pub fn block_proposed(&self, hash: &str) {
+        self.inc(BLOCKS_PROPOSED, &[]);
+        self.proposed_at
+            .lock()
+            .unwrap()
+            .insert(hash.to_string(), Instant::now());
+    }
+
+    // Враховує лише блоки, запропоновані цим вузлом
+    pub fn quorum_reached(&self, hash: &str) {
+        let proposed_at = self.proposed_at.lock().unwrap().remove(hash);
+        if let Some(proposed_at) = proposed_at {
+            self.observe(QUORUM_LATENCY, &[], proposed_at.elapsed());
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let values = self.values.lock().unwrap();
+        let histograms = self.histograms.lock().unwrap();
+        let mut out = String::new();
+        for (family, kind, help) in FAMILIES {
+            let _ = writeln!(out, "# HELP {} {}", family, help);
+            let _ = writeln!(out, "# TYPE {} {}", family, kind.as_str());
+            if kind == Kind::Histogram {
+                for ((_, labels), histogram) in histograms.iter().filter(|((n, _), _)| *n == family)
+                {
+                    for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+                        let mut bucket_labels = labels.clone();
+                        bucket_labels.push(("le", bound.to_string()));
+                        let _ = writeln!(
+                            out,
+                            "{}_bucket{} {}",
+                            family,
+                            format_labels(&bucket_labels),
+                            count
+                        );
+                    }
+                    let mut inf_labels = labels.clone();
+                    inf_labels.push(("le", "+Inf".to_string()));
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{} {}",
+                        family,
+                        format_labels(&inf_labels),
+                        histogram.count
+                    );
+                    let _ = writeln!(
+                        out,
+                        "{}_sum{} {}",
+                        family,
+                        format_labels(labels),
+                        histogram.sum
+                    );
+                    let _ = writeln!(
+                        out,
+                        "{}_count{} {}",
+                        family,
+                        format_labels(labels),
+                        histogram.count
+                    );
+                }
+            } else {
+                for ((_, labels), value) in values.iter().filter(|((n, _), _)| *n == family) {
+                    let _ = writeln!(out, "{}{} {}", family, format_labels(labels), value);
+                }
+            }
+        }
+        out
+    }
+
+    fn add(&self, name: &'static str, labels: &[(&'static str, &str)], delta: f64) {
+        *self
+            .values
+            .lock()
+            .unwrap()
+            .entry((name, to_labels(labels)))
+            .or_insert(0.0) += delta;
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect()
+}
+
+fn format_labels(labels: &[(&'static str, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
@@ -1,9 +1,28 @@
+pub mod account_transfer;
+pub mod address_book;
 pub mod app_state;
 pub mod block;
 pub mod block_header;
+pub mod block_locator;
 pub mod blockchain_repository;
+pub mod chain_event;
+pub mod config;
+pub mod consensus_status;
+pub mod error;
+pub mod event_bus;
+pub mod gossip;
+pub mod handshake;
 pub mod mempool_repository;
+pub mod metrics;
 pub mod node;
+pub mod node_identity;
+pub mod peer_address;
+pub mod peer_links;
+pub mod peer_message;
+pub mod peer_registry;
+pub mod peer_score;
+pub mod state_snapshot;
+pub mod sync_status;
 pub mod transaction;
 pub mod user_state_repository;
 pub mod vote;
@@ -1,15 +1,29 @@
+use crate::domain::peer_address::PeerAddress;
+
 pub struct Node {
     pub id: String,
-    pub peers: Vec<String>,
+    pub chain_id: String,
+    pub peers: Vec<PeerAddress>,
     pub validator_ids: Vec<String>,
 }
 
 impl Node {
-    pub fn new(id: String, peers: Vec<String>, validator_ids: Vec<String>) -> Self {
+    pub fn new(
+        id: String,
+        chain_id: String,
+        peers: Vec<PeerAddress>,
+        validator_ids: Vec<String>,
+    ) -> Self {
         Node {
             id,
+            chain_id,
             peers,
             validator_ids,
         }
     }
+
+    // Кількість голосів, потрібна для додавання блоку
+    pub fn quorum(&self) -> usize {
+        self.validator_ids.len() / 2 + 1
+    }
 }
@@ -0,0 +1,84 @@
+use ring::rand::SystemRandom;
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+
+// Довжина відкритого ключа Ed25519 у hex
+pub const IDENTITY_KEY_HEX_LEN: usize = 64;
+
+/// The node's long-term Ed25519 key. Peers learn its public half from the
+/// handshake, and every P2P connection is signed with it.
+pub struct NodeIdentity {
+    key_pair: Ed25519KeyPair,
+    pkcs8: Vec<u8>,
+}
+
+impl NodeIdentity {
+    pub fn generate() -> Self {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .expect("Failed to generate an identity key");
+        Self::from_pkcs8(pkcs8.as_ref()).expect("a freshly generated key is valid")
+    }
+
+    pub fn from_pkcs8(pkcs8: &[u8]) -> Result<Self, String> {
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8).map_err(|e| e.to_string())?;
+        Ok(Self {
+            key_pair,
+            pkcs8: pkcs8.to_vec(),
+        })
+    }
+
+    // Для збереження у файл ключа
+    pub fn pkcs8(&self) -> &[u8] {
+        &self.pkcs8
+    }
+
+    pub fn public_key(&self) -> String {
+        hex::encode(self.key_pair.public_key().as_ref())
+    }
+
+    pub fn sign(&self, message: &[u8]) -> String {
+        hex::encode(self.key_pair.sign(message).as_ref())
+    }
+}
+
+#[cfg(test)]
+impl NodeIdentity {
+    // Той самий ключ для того самого node_id, тож Config::for_tests може закріпити його заздалегідь
+    pub fn for_tests(node_id: &str) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let seed = Sha256::digest(format!("test identity {}", node_id));
+        let public_key = Ed25519KeyPair::from_seed_unchecked(&seed)
+            .expect("any 32 bytes are a seed")
+            .public_key()
+            .as_ref()
+            .to_vec();
+        // PKCS#8 v2 у тому ж вигляді, що й generate_pkcs8
+        let mut pkcs8 = hex::decode("3053020101300506032b657004220420").expect("valid hex");
+        pkcs8.extend_from_slice(&seed);
+        pkcs8.extend_from_slice(&[0xa1, 0x23, 0x03, 0x21, 0x00]);
+        pkcs8.extend_from_slice(&public_key);
+        Self::from_pkcs8(&pkcs8).expect("a well-formed PKCS#8 document")
+    }
+}
+
+// Ключ приватний, тож у логах лише відкрита частина
+impl std::fmt::Debug for NodeIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NodeIdentity")
+            .field("public_key", &self.public_key())
+            .finish()
+    }
+}
+
+pub fn is_identity_key(key: &str) -> bool {
+    key.len() == IDENTITY_KEY_HEX_LEN && key.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+pub fn verify_signature(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let (Ok(public_key), Ok(signature)) = (hex::decode(public_key), hex::decode(signature)) else {
+        return false;
+    };
+    UnparsedPublicKey::new(&ED25519, public_key)
+        .verify(message, &signature)
+        .is_ok()
+}
@@ -0,0 +1,246 @@
+use crate::domain::error::PeerAddressError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scheme {
+    Http,
+    Https,
+}
+
+impl Scheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            Scheme::Http => "http",
+            Scheme::Https => "https",
+        }
+    }
+
+    fn default_port(self) -> u16 {
+        match self {
+            Scheme::Http => 80,
+            Scheme::Https => 443,
+        }
+    }
+}
+
+/// Where a peer is reached: `[http|https://][node_id@]host[:port]`.
+///
+/// A bare port such as `3002` means `localhost:3002`, as in the original `--peers` syntax.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PeerAddress {
+    pub scheme: Scheme,
+    pub host: String,
+    pub port: u16,
+    pub node_id: Option<String>,
+}
+
+impl PeerAddress {
+    pub fn local(node_id: &str, port: u16) -> Self {
+        PeerAddress {
+            scheme: Scheme::Http,
+            host: "localhost".to_string(),
+            port,
+            node_id: Some(node_id.to_string()),
+        }
+    }
+
+    // Повний URL ендпоінта піра, наприклад url("/handshake")
+    pub fn url(&self, path: &str) -> String {
+        format!("{}://{}{}", self.scheme.as_str(), self.authority(), path)
+    }
+
+    // host:port без схеми та id вузла; IPv6 береться в дужки
+    pub fn authority(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]:{}", self.host, self.port)
+        } else {
+            format!("{}:{}", self.host, self.port)
+        }
+    }
+}
+
+impl fmt::Display for PeerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.scheme != Scheme::Http {
+            write!(f, "{}://", self.scheme.as_str())?;
+        }
+        if let Some(node_id) = &self.node_id {
+            write!(f, "{}@", node_id)?;
+        }
+        write!(f, "{}", self.authority())
+    }
+}
+
+impl FromStr for PeerAddress {
+    type Err = PeerAddressError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let fail = |reason| PeerAddressError {
+            address: input.to_string(),
+            reason,
+        };
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(fail("address is empty"));
+        }
+
+        // Старий формат --peers 3002
+        if input.bytes().all(|b| b.is_ascii_digit()) {
+            let port = parse_port(input).ok_or_else(|| fail("port must be 1-65535"))?;
+            return Ok(PeerAddress {
+                scheme: Scheme::Http,
+                host: "localhost".to_string(),
+                port,
+                node_id: None,
+            });
+        }
+
+        let (scheme, rest) = match input.split_once("://") {
+            Some(("http", rest)) => (Some(Scheme::Http), rest),
+            Some(("https", rest)) => (Some(Scheme::Https), rest),
+            Some(_) => return Err(fail("scheme must be http or https")),
+            None => (None, input),
+        };
+        let rest = rest.strip_suffix('/').unwrap_or(rest);
+        if rest.contains('/') {
+            return Err(fail("address must not contain a path"));
+        }
+
+        let (node_id, rest) = match rest.split_once('@') {
+            Some((node_id, rest)) => {
+                if node_id.is_empty()
+                    || !node_id
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c))
+                {
+                    return Err(fail(
+                        "node id may only contain letters, digits, '-', '_' and '.'",
+                    ));
+                }
+                (Some(node_id.to_string()), rest)
+            }
+            None => (None, rest),
+        };
+
+        let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+            let (host, after) = bracketed
+                .split_once(']')
+                .ok_or_else(|| fail("unclosed '[' in IPv6 address"))?;
+            if host.parse::<Ipv6Addr>().is_err() {
+                return Err(fail("invalid IPv6 address"));
+            }
+            match after {
+                "" => (host, None),
+                _ => match after.strip_prefix(':') {
+                    Some(port) => (host, Some(port)),
+                    None => return Err(fail("unexpected text after IPv6 address")),
+                },
+            }
+        } else {
+            let (host, port) = match rest.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (rest, None),
+            };
+            let labels_valid = host.split('.').all(|label| {
+                !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+            if !labels_valid {
+                return Err(fail("host must be an IP address or a DNS name"));
+            }
+            (host, port)
+        };
+
+        let port = match (port, scheme) {
+            (Some(port), _) => parse_port(port).ok_or_else(|| fail("port must be 1-65535"))?,
+            (None, Some(scheme)) => scheme.default_port(),
+            (None, None) => return Err(fail("port is missing")),
+        };
+
+        Ok(PeerAddress {
+            scheme: scheme.unwrap_or(Scheme::Http),
+            host: host.to_ascii_lowercase(),
+            port,
+            node_id,
+        })
+    }
+}
+
+fn parse_port(text: &str) -> Option<u16> {
+    text.parse::<u16>().ok().filter(|port| *port != 0)
+}
+
+impl TryFrom<String> for PeerAddress {
+    type Error = PeerAddressError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PeerAddress> for String {
+    fn from(address: PeerAddress) -> Self {
+        address.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PeerAddress, Scheme};
+
+    fn parse(input: &str) -> PeerAddress {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn bare_port_means_localhost() {
+        let address = parse("3002");
+        assert_eq!(address.url("/block"), "http://localhost:3002/block");
+        assert_eq!(address.to_string(), "localhost:3002");
+    }
+
+    #[test]
+    fn parses_every_part() {
+        let address = parse("https://v2@Node-2.example.com:8443/");
+        assert_eq!(address.scheme, Scheme::Https);
+        assert_eq!(address.node_id.as_deref(), Some("v2"));
+        assert_eq!(address.host, "node-2.example.com");
+        assert_eq!(address.port, 8443);
+        assert_eq!(address.url("/vote"), "https://node-2.example.com:8443/vote");
+        assert_eq!(address.to_string(), "https://v2@node-2.example.com:8443");
+        assert_eq!(parse(&address.to_string()), address);
+    }
+
+    #[test]
+    fn parses_ip_addresses() {
+        assert_eq!(parse("10.0.0.7:3001").url("/"), "http://10.0.0.7:3001/");
+        assert_eq!(
+            parse("[::1]:3001").url("/headers"),
+            "http://[::1]:3001/headers"
+        );
+        assert_eq!(parse("https://[::1]").port, 443);
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        for input in [
+            "",
+            "0",
+            "70000",
+            "node.example.com",
+            "ftp://host:1",
+            "host:1/path",
+            "@host:1",
+            "bad host:1",
+            "host..com:1",
+            "[::1",
+            "[nope]:1",
+            "host:port",
+        ] {
+            assert!(input.parse::<PeerAddress>().is_err(), "{:?} parsed", input);
+        }
+    }
+}
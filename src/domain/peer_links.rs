@@ -0,0 +1,171 @@
+use crate::domain::peer_message::{PeerMessage, SyncReply};
+use std::collections::HashMap;
+use tokio::sync::{mpsc, oneshot};
+
+/// An open P2P connection to a peer that sent a valid hello.
+pub struct PeerLink {
+    pub node_id: String,
+    pub connection_id: u64,
+    // Ключ, яким пір підписав hello
+    pub identity_key: String,
+    // Чи підключилися ми самі
+    pub outbound: bool,
+    pub sender: mpsc::Sender<PeerMessage>,
+    // Задача читання завершується, щойно з'єднання прибирають звідси; переривати її
+    // посеред обробки повідомлення не можна, тож вона чекає на цей сигнал між кадрами
+    pub shutdown: oneshot::Sender<()>,
+}
+
+struct PendingRequest {
+    node_id: String,
+    reply: oneshot::Sender<SyncReply>,
+}
+
+/// Open P2P connections by node id, at most one per peer, and the sync
+/// requests still waiting for an answer.
+#[derive(Default)]
+pub struct PeerLinks {
+    links: HashMap<String, PeerLink>,
+    next_connection_id: u64,
+    next_request_id: u64,
+    pending: HashMap<u64, PendingRequest>,
+}
+
+impl PeerLinks {
+    pub fn next_connection_id(&mut self) -> u64 {
+        self.next_connection_id += 1;
+        self.next_connection_id
+    }
+
+    // Якщо два вузли підключилися один до одного одночасно, обидва лишають з'єднання,
+    // відкрите вузлом з меншим id. Повертає false, якщо нове з'єднання зайве
+    pub fn insert(&mut self, link: PeerLink, local_id: &str) -> bool {
+        if let Some(existing) = self.links.get(&link.node_id)
+            && existing.outbound != link.outbound
+        {
+            let keep_outbound = local_id < link.node_id.as_str();
+            if existing.outbound == keep_outbound {
+                return false;
+            }
+        }
+        if let Some(replaced) = self.links.insert(link.node_id.clone(), link) {
+            let _ = replaced.shutdown.send(());
+        }
+        true
+    }
+
+    // connection_id захищає нове з'єднання від задач, що закривають старе
+    pub fn remove(&mut self, node_id: &str, connection_id: Option<u64>) -> bool {
+        let matches = self
+            .links
+            .get(node_id)
+            .is_some_and(|link| connection_id.is_none_or(|id| link.connection_id == id));
+        if !matches {
+            return false;
+        }
+        if let Some(link) = self.links.remove(node_id) {
+            let _ = link.shutdown.send(());
+        }
+        // Відповідей від закритого з'єднання вже не буде
+        self.pending.retain(|_, request| request.node_id != node_id);
+        true
+    }
+
+    pub fn contains(&self, node_id: &str) -> bool {
+        self.links.contains_key(node_id)
+    }
+
+    pub fn identity_key(&self, node_id: &str) -> Option<&str> {
+        self.links
+            .get(node_id)
+            .map(|link| link.identity_key.as_str())
+    }
+
+    pub fn sender(&self, node_id: &str) -> Option<mpsc::Sender<PeerMessage>> {
+        self.links.get(node_id).map(|link| link.sender.clone())
+    }
+
+    pub fn senders(&self) -> Vec<(String, mpsc::Sender<PeerMessage>)> {
+        self.links
+            .values()
+            .map(|link| (link.node_id.clone(), link.sender.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    // Вхідні й вихідні з'єднання мають окремі ліміти
+    pub fn inbound_count(&self) -> usize {
+        self.links.values().filter(|link| !link.outbound).count()
+    }
+
+    pub fn inbound_ids(&self) -> Vec<String> {
+        self.links
+            .values()
+            .filter(|link| !link.outbound)
+            .map(|link| link.node_id.clone())
+            .collect()
+    }
+
+    pub fn register_request(&mut self, node_id: &str) -> (u64, oneshot::Receiver<SyncReply>) {
+        self.next_request_id += 1;
+        let (reply, receiver) = oneshot::channel();
+        self.pending.insert(
+            self.next_request_id,
+            PendingRequest {
+                node_id: node_id.to_string(),
+                reply,
+            },
+        );
+        (self.next_request_id, receiver)
+    }
+
+    // Відповідь приймається лише від піра, якому надіслали запит
+    pub fn complete_request(&mut self, id: u64, node_id: &str, reply: SyncReply) -> bool {
+        match self.pending.remove(&id) {
+            Some(request) if request.node_id == node_id => {
+                let _ = request.reply.send(reply);
+                true
+            }
+            Some(request) => {
+                self.pending.insert(id, request);
+                false
+            }
+            None => false,
+        }
+    }
+
+    pub fn cancel_request(&mut self, id: u64) {
+        self.pending.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PeerLink, PeerLinks};
+    use tokio::sync::{mpsc, oneshot};
+
+    fn link(node_id: &str, outbound: bool) -> PeerLink {
+        PeerLink {
+            node_id: node_id.to_string(),
+            connection_id: 0,
+            identity_key: String::new(),
+            outbound,
+            sender: mpsc::channel(1).0,
+            shutdown: oneshot::channel().0,
+        }
+    }
+
+    #[test]
+    fn counts_only_inbound_links_as_inbound() {
+        let mut links = PeerLinks::default();
+        links.insert(link("v2", true), "v1");
+        links.insert(link("v3", true), "v1");
+        links.insert(link("v4", false), "v1");
+
+        assert_eq!(links.len(), 3);
+        assert_eq!(links.inbound_count(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+use crate::domain::{
+    block::Block, block_locator::BlockHeaderEntry, gossip::Gossip, handshake::Handshake,
+    transaction::Transaction, vote::Vote,
+};
+use serde::{Deserialize, Serialize};
+
+/// Everything validators send each other over the P2P transport. Each message
+/// travels in its own frame, tagged with its [`MessageKind`].
+#[derive(Debug, Clone)]
+pub enum PeerMessage {
+    /// The first frame in both directions; nothing else is accepted before it.
+    Hello(Hello),
+    /// Blocks and votes are relayed beyond the node that created them.
+    Block(Gossip<Block>),
+    Vote(Gossip<Vote>),
+    /// A transaction admitted to the sender's mempool.
+    TxAnnounce(Transaction),
+    SyncRequest(SyncRequest),
+    SyncResponse(SyncResponse),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    Hello,
+    Block,
+    Vote,
+    TxAnnounce,
+    SyncRequest,
+    SyncResponse,
+}
+
+impl MessageKind {
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(MessageKind::Hello),
+            1 => Some(MessageKind::Block),
+            2 => Some(MessageKind::Vote),
+            3 => Some(MessageKind::TxAnnounce),
+            4 => Some(MessageKind::SyncRequest),
+            5 => Some(MessageKind::SyncResponse),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte(self) -> u8 {
+        match self {
+            MessageKind::Hello => 0,
+            MessageKind::Block => 1,
+            MessageKind::Vote => 2,
+            MessageKind::TxAnnounce => 3,
+            MessageKind::SyncRequest => 4,
+            MessageKind::SyncResponse => 5,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MessageKind::Hello => "hello",
+            MessageKind::Block => "block",
+            MessageKind::Vote => "vote",
+            MessageKind::TxAnnounce => "tx_announce",
+            MessageKind::SyncRequest => "sync_request",
+            MessageKind::SyncResponse => "sync_response",
+        }
+    }
+
+    // Найбільше тіло кадру цього типу; більший кадр — порушення з боку піра
+    pub fn max_body_bytes(self) -> usize {
+        match self {
+            MessageKind::Hello => 16 * 1024,
+            MessageKind::Block => 2 * 1024 * 1024,
+            MessageKind::Vote | MessageKind::TxAnnounce => 4 * 1024,
+            MessageKind::SyncRequest => 64 * 1024,
+            MessageKind::SyncResponse => 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl PeerMessage {
+    // Хеш, під яким блок чи голос потрапляє в кеш побачених; повтори транзакцій відсіює мемпул
+    pub fn gossip_hash(&self) -> Option<String> {
+        match self {
+            PeerMessage::Block(gossip) => Some(gossip.item.hash.clone()),
+            PeerMessage::Vote(gossip) => Some(gossip.item.id()),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            PeerMessage::Hello(_) => MessageKind::Hello,
+            PeerMessage::Block(_) => MessageKind::Block,
+            PeerMessage::Vote(_) => MessageKind::Vote,
+            PeerMessage::TxAnnounce(_) => MessageKind::TxAnnounce,
+            PeerMessage::SyncRequest(_) => MessageKind::SyncRequest,
+            PeerMessage::SyncResponse(_) => MessageKind::SyncResponse,
+        }
+    }
+}
+
+/// A handshake signed with the sender's identity key. The signature covers
+/// the keys of this connection, so it cannot be replayed on another one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hello {
+    #[serde(flatten)]
+    pub handshake: Handshake,
+    pub signature: String,
+}
+
+/// Asks a peer for part of its chain; answered by a [`SyncResponse`] with the same id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncRequest {
+    pub id: u64,
+    #[serde(flatten)]
+    pub query: SyncQuery,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncQuery {
+    /// Headers after the newest locator hash the peer knows, or from `from` without a locator.
+    Headers {
+        #[serde(default)]
+        locator: Vec<String>,
+        #[serde(default)]
+        from: Option<u64>,
+    },
+    /// Blocks with heights from `from` to `to` inclusive.
+    Blocks { from: u64, to: u64 },
+    /// Peer addresses the node knows, for discovery.
+    Addresses,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncResponse {
+    pub id: u64,
+    #[serde(flatten)]
+    pub reply: SyncReply,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncReply {
+    Headers { headers: Vec<BlockHeaderEntry> },
+    Blocks { blocks: Vec<Block> },
+    Addresses { addresses: Vec<String> },
+}
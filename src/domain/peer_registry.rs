@@ -0,0 +1,130 @@
+use crate::domain::handshake::Handshake;
+use crate::domain::peer_address::PeerAddress;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct PeerInfo {
+    pub handshake: Handshake,
+    // Відома лише для пірів, до яких ми підключилися самі
+    pub address: Option<PeerAddress>,
+    pub inbound: bool,
+    pub outbound: bool,
+    pub last_handshake_at: u64,
+}
+
+/// Peers that completed a handshake, by node id.
+#[derive(Debug, Default)]
+pub struct PeerRegistry {
+    peers: HashMap<String, PeerInfo>,
+}
+
+impl PeerRegistry {
+    // Пір сам відкрив до нас P2P-з'єднання й підписав hello
+    pub fn record_inbound(&mut self, handshake: Handshake) {
+        self.entry(handshake).inbound = true;
+    }
+
+    // Ми надіслали handshake на address і отримали сумісну відповідь
+    pub fn record_outbound(&mut self, address: &PeerAddress, handshake: Handshake) {
+        self.forget_address(address);
+        let entry = self.entry(handshake);
+        entry.outbound = true;
+        entry.address = Some(address.clone());
+    }
+
+    // За адресою більше немає сумісного піра
+    pub fn forget_address(&mut self, address: &PeerAddress) {
+        for info in self.peers.values_mut() {
+            if info.address.as_ref() == Some(address) {
+                info.address = None;
+                info.outbound = false;
+            }
+        }
+        self.peers.retain(|_, info| info.inbound || info.outbound);
+    }
+
+    // Вхідним пір лишається, поки відкрите його вхідне P2P-з'єднання
+    pub fn retain_inbound(&mut self, connected: &[String]) {
+        for info in self.peers.values_mut() {
+            if info.inbound && !connected.contains(&info.handshake.node_id) {
+                info.inbound = false;
+            }
+        }
+        self.peers.retain(|_, info| info.inbound || info.outbound);
+    }
+
+    pub fn remove(&mut self, node_id: &str) -> Option<PeerInfo> {
+        self.peers.remove(node_id)
+    }
+
+    pub fn is_known(&self, node_id: &str) -> bool {
+        self.peers.contains_key(node_id)
+    }
+
+    // Чи є вже вихідне з'єднання з цим вузлом, можливо за іншою адресою
+    pub fn is_outbound(&self, node_id: &str) -> bool {
+        self.peers.get(node_id).is_some_and(|info| info.outbound)
+    }
+
+    pub fn inbound_count(&self) -> usize {
+        self.peers.values().filter(|info| info.inbound).count()
+    }
+
+    pub fn outbound_count(&self) -> usize {
+        self.peers.values().filter(|info| info.outbound).count()
+    }
+
+    // Адреси зі списку, з якими handshake пройшов; лише їм надсилаємо блоки й голоси
+    pub fn handshaken(&self, addresses: &[PeerAddress]) -> Vec<PeerAddress> {
+        addresses
+            .iter()
+            .filter(|address| self.is_handshaken(address))
+            .cloned()
+            .collect()
+    }
+
+    // Id вузла, до якого ми підключилися за цією адресою
+    pub fn node_id_at(&self, address: &PeerAddress) -> Option<String> {
+        self.peers
+            .values()
+            .find(|info| info.outbound && info.address.as_ref() == Some(address))
+            .map(|info| info.handshake.node_id.clone())
+    }
+
+    pub fn is_handshaken(&self, address: &PeerAddress) -> bool {
+        self.peers
+            .values()
+            .any(|info| info.outbound && info.address.as_ref() == Some(address))
+    }
+
+    pub fn all(&self) -> Vec<PeerInfo> {
+        let mut peers: Vec<PeerInfo> = self.peers.values().cloned().collect();
+        peers.sort_by(|a, b| a.handshake.node_id.cmp(&b.handshake.node_id));
+        peers
+    }
+
+    fn entry(&mut self, handshake: Handshake) -> &mut PeerInfo {
+        let last_handshake_at = now();
+        let entry = self
+            .peers
+            .entry(handshake.node_id.clone())
+            .or_insert_with(|| PeerInfo {
+                handshake: handshake.clone(),
+                address: None,
+                inbound: false,
+                outbound: false,
+                last_handshake_at,
+            });
+        entry.handshake = handshake;
+        entry.last_handshake_at = last_handshake_at;
+        entry
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
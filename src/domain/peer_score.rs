@@ -0,0 +1,234 @@
+use crate::domain::error::BlockError;
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Пір банується, коли його рахунок опускається до цього значення
+pub const BAN_THRESHOLD: i32 = -100;
+pub const DEFAULT_BAN_DURATION: Duration = Duration::from_secs(600);
+// Рахунок поступово повертається до нуля, щоб рідкісні помилки не накопичувалися
+const RECOVERY_PER_MINUTE: i32 = 10;
+
+/// Something a peer sent that an honest node would not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misbehaviour {
+    InvalidHash,
+    InvalidSignature,
+    InvalidTransactions,
+    InvalidStateRoot,
+    UnknownProposer,
+    InvalidVote,
+    ForgedVote,
+    ForgedBlock,
+    MalformedPayload,
+    OversizedPayload,
+}
+
+impl Misbehaviour {
+    // Помилки блоку, у яких винен відправник; решта (застарілий блок, форк) — нормальна робота мережі
+    pub fn from_block_error(e: &BlockError) -> Option<Self> {
+        match e {
+            BlockError::InvalidHash { .. } => Some(Misbehaviour::InvalidHash),
+            BlockError::InvalidSignature { .. } => Some(Misbehaviour::InvalidSignature),
+            BlockError::InvalidTransaction { .. } => Some(Misbehaviour::InvalidTransactions),
+            BlockError::StateRootMismatch { .. } => Some(Misbehaviour::InvalidStateRoot),
+            BlockError::UnknownProposer { .. } => Some(Misbehaviour::UnknownProposer),
+            BlockError::ForgedBlock { .. } => Some(Misbehaviour::ForgedBlock),
+            BlockError::DoesNotExtendTip { .. }
+            | BlockError::EmptyChain
+            | BlockError::MissingState { .. }
+            | BlockError::Storage(_) => None,
+        }
+    }
+
+    pub fn penalty(self) -> i32 {
+        match self {
+            Misbehaviour::InvalidSignature
+            | Misbehaviour::ForgedVote
+            | Misbehaviour::ForgedBlock => 50,
+            Misbehaviour::InvalidHash
+            | Misbehaviour::InvalidTransactions
+            | Misbehaviour::InvalidStateRoot
+            | Misbehaviour::UnknownProposer
+            | Misbehaviour::OversizedPayload => 25,
+            Misbehaviour::InvalidVote | Misbehaviour::MalformedPayload => 10,
+        }
+    }
+
+    pub fn kind(self) -> &'static str {
+        match self {
+            Misbehaviour::InvalidHash => "invalid_hash",
+            Misbehaviour::InvalidSignature => "invalid_signature",
+            Misbehaviour::InvalidTransactions => "invalid_transactions",
+            Misbehaviour::InvalidStateRoot => "invalid_state_root",
+            Misbehaviour::UnknownProposer => "unknown_proposer",
+            Misbehaviour::InvalidVote => "invalid_vote",
+            Misbehaviour::ForgedVote => "forged_vote",
+            Misbehaviour::ForgedBlock => "forged_block",
+            Misbehaviour::MalformedPayload => "malformed_payload",
+            Misbehaviour::OversizedPayload => "oversized_payload",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PeerScore {
+    pub score: i32,
+    pub offences: u32,
+    pub last_offence: Option<&'static str>,
+    pub banned_until: Option<u64>,
+    pub ban_reason: Option<String>,
+    updated_at: u64,
+}
+
+impl PeerScore {
+    pub fn is_banned(&self, now: u64) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+
+    // Відновлює рахунок за час, що минув, і знімає прострочений бан
+    fn refresh(&mut self, now: u64) {
+        if self.banned_until.is_some_and(|until| until <= now) {
+            self.banned_until = None;
+            self.ban_reason = None;
+            self.score = 0;
+        }
+        let minutes = now.saturating_sub(self.updated_at) / 60;
+        if minutes > 0 {
+            let recovered = i32::try_from(minutes)
+                .unwrap_or(i32::MAX)
+                .saturating_mul(RECOVERY_PER_MINUTE);
+            self.score = self.score.saturating_add(recovered).min(0);
+            self.updated_at += minutes * 60;
+        }
+    }
+}
+
+/// Misbehaviour score and ban state of every peer that has misbehaved or been banned, by node id.
+#[derive(Debug, Default)]
+pub struct PeerScores {
+    peers: HashMap<String, PeerScore>,
+}
+
+impl PeerScores {
+    // Знімає бали; повертає момент закінчення бану, якщо пір щойно його отримав
+    pub fn penalize(&mut self, node_id: &str, misbehaviour: Misbehaviour, now: u64) -> Option<u64> {
+        let peer = self.entry(node_id, now);
+        peer.score = peer.score.saturating_sub(misbehaviour.penalty());
+        peer.offences += 1;
+        peer.last_offence = Some(misbehaviour.kind());
+        if peer.score > BAN_THRESHOLD || peer.is_banned(now) {
+            return None;
+        }
+        let until = now + DEFAULT_BAN_DURATION.as_secs();
+        peer.banned_until = Some(until);
+        peer.ban_reason = Some(format!("score fell to {}", peer.score));
+        Some(until)
+    }
+
+    pub fn ban(&mut self, node_id: &str, duration: Duration, reason: String, now: u64) -> u64 {
+        let peer = self.entry(node_id, now);
+        let until = now + duration.as_secs();
+        peer.banned_until = Some(until);
+        peer.ban_reason = Some(reason);
+        until
+    }
+
+    // Повертає false, якщо пір не був забанений
+    pub fn unban(&mut self, node_id: &str, now: u64) -> bool {
+        let Some(peer) = self.peers.get_mut(node_id) else {
+            return false;
+        };
+        peer.refresh(now);
+        if !peer.is_banned(now) {
+            return false;
+        }
+        peer.banned_until = None;
+        peer.ban_reason = None;
+        peer.score = 0;
+        true
+    }
+
+    pub fn is_banned(&self, node_id: &str, now: u64) -> bool {
+        self.peers
+            .get(node_id)
+            .is_some_and(|peer| peer.is_banned(now))
+    }
+
+    pub fn get(&mut self, node_id: &str, now: u64) -> Option<PeerScore> {
+        let peer = self.peers.get_mut(node_id)?;
+        peer.refresh(now);
+        Some(peer.clone())
+    }
+
+    // Усі піри з ненульовим рахунком або баном, за id
+    pub fn all(&mut self, now: u64) -> Vec<(String, PeerScore)> {
+        for peer in self.peers.values_mut() {
+            peer.refresh(now);
+        }
+        self.peers
+            .retain(|_, peer| peer.score < 0 || peer.banned_until.is_some());
+        let mut peers: Vec<(String, PeerScore)> = self
+            .peers
+            .iter()
+            .map(|(node_id, peer)| (node_id.clone(), peer.clone()))
+            .collect();
+        peers.sort_by(|a, b| a.0.cmp(&b.0));
+        peers
+    }
+
+    fn entry(&mut self, node_id: &str, now: u64) -> &mut PeerScore {
+        let peer = self
+            .peers
+            .entry(node_id.to_string())
+            .or_insert_with(|| PeerScore {
+                updated_at: now,
+                ..PeerScore::default()
+            });
+        peer.refresh(now);
+        peer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BAN_THRESHOLD, DEFAULT_BAN_DURATION, Misbehaviour, PeerScores};
+    use std::time::Duration;
+
+    #[test]
+    fn bans_once_the_score_crosses_the_threshold() {
+        let mut scores = PeerScores::default();
+        assert_eq!(
+            scores.penalize("v2", Misbehaviour::InvalidSignature, 0),
+            None
+        );
+        assert!(!scores.is_banned("v2", 0));
+        let until = scores.penalize("v2", Misbehaviour::InvalidSignature, 1);
+        assert_eq!(until, Some(1 + DEFAULT_BAN_DURATION.as_secs()));
+        assert!(scores.is_banned("v2", 2));
+        assert_eq!(scores.get("v2", 2).unwrap().score, BAN_THRESHOLD);
+
+        // Після бану пір починає з чистого рахунку
+        let after = until.unwrap();
+        assert!(!scores.is_banned("v2", after));
+        assert_eq!(scores.get("v2", after).unwrap().score, 0);
+    }
+
+    #[test]
+    fn score_recovers_over_time() {
+        let mut scores = PeerScores::default();
+        scores.penalize("v3", Misbehaviour::OversizedPayload, 0);
+        assert_eq!(scores.get("v3", 59).unwrap().score, -25);
+        assert_eq!(scores.get("v3", 120).unwrap().score, -5);
+        assert!(scores.all(180).is_empty());
+    }
+
+    #[test]
+    fn admin_bans_and_unbans() {
+        let mut scores = PeerScores::default();
+        assert!(!scores.unban("v4", 0));
+        scores.ban("v4", Duration::from_secs(30), "manual".to_string(), 0);
+        assert!(scores.is_banned("v4", 29));
+        assert!(scores.unban("v4", 10));
+        assert!(!scores.is_banned("v4", 11));
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const SNAPSHOT_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub height: u64,
+    pub block_hash: String,
+    pub balances: HashMap<Uuid, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub height: u64,
+    pub block_hash: String,
+    pub state_root: String,
+    pub total_accounts: usize,
+    pub chunk_size: usize,
+    pub chunk_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotChunk {
+    pub height: u64,
+    pub index: usize,
+    pub accounts: Vec<(Uuid, f64)>,
+}
+
+impl StateSnapshot {
+    pub fn manifest(&self) -> SnapshotManifest {
+        let total_accounts = self.balances.len();
+        SnapshotManifest {
+            height: self.height,
+            block_hash: self.block_hash.clone(),
+            state_root: compute_state_root(&self.balances),
+            total_accounts,
+            chunk_size: SNAPSHOT_CHUNK_SIZE,
+            chunk_count: total_accounts.div_ceil(SNAPSHOT_CHUNK_SIZE),
+        }
+    }
+
+    pub fn chunk(&self, index: usize) -> Option<SnapshotChunk> {
+        let accounts = sorted_accounts(&self.balances);
+        let start = index.checked_mul(SNAPSHOT_CHUNK_SIZE)?;
+        if start >= accounts.len() {
+            return None;
+        }
+        let end = (start + SNAPSHOT_CHUNK_SIZE).min(accounts.len());
+        Some(SnapshotChunk {
+            height: self.height,
+            index,
+            accounts: accounts[start..end].to_vec(),
+        })
+    }
+}
+
+fn sorted_accounts(balances: &HashMap<Uuid, f64>) -> Vec<(Uuid, f64)> {
+    let mut accounts: Vec<(Uuid, f64)> = balances.iter().map(|(a, b)| (*a, *b)).collect();
+    accounts.sort_by_key(|(address, _)| *address);
+    accounts
+}
+
+// Детермінований корінь стану: SHA-256 від відсортованих за адресою пар "адреса:баланс"
+pub fn compute_state_root(balances: &HashMap<Uuid, f64>) -> String {
+    let mut hasher = Sha256::new();
+    for (address, balance) in sorted_accounts(balances) {
+        hasher.update(address.as_bytes());
+        hasher.update(balance.to_bits().to_be_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
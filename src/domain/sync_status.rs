@@ -0,0 +1,52 @@
+use crate::domain::peer_address::PeerAddress;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub syncing: bool,
+    // Висота найдовшого ланцюга піра, з якого йде поточна синхронізація
+    pub target_height: Option<u64>,
+    pub last_synced_at: Option<u64>,
+    // Що ми востаннє дізналися про кожного піра під час синхронізації чи розсилки блоків;
+    // ключ — PeerAddress у текстовому вигляді
+    pub peers: HashMap<String, PeerStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub height: Option<u64>,
+    pub reachable: bool,
+    pub updated_at: u64,
+}
+
+impl SyncStatus {
+    // Пір відповів; height — None, якщо з відповіді висоту не визначити
+    pub fn record_peer(&mut self, peer: &PeerAddress, height: Option<u64>) {
+        let entry = self.peer_entry(peer);
+        entry.reachable = true;
+        if height.is_some() {
+            entry.height = height;
+        }
+    }
+
+    // Остання відома висота піра зберігається
+    pub fn mark_unreachable(&mut self, peer: &PeerAddress) {
+        self.peer_entry(peer).reachable = false;
+    }
+
+    fn peer_entry(&mut self, peer: &PeerAddress) -> &mut PeerStatus {
+        let updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let entry = self.peers.entry(peer.to_string()).or_insert(PeerStatus {
+            height: None,
+            reachable: false,
+            updated_at,
+        });
+        entry.updated_at = updated_at;
+        entry
+    }
+}
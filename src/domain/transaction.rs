@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+use crate::domain::error::TransactionError;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Transaction {
     pub id: Uuid,
@@ -26,10 +28,24 @@ impl Transaction {
             timestamp,
         }
     }
-    // pub fn is_valid(&self) -> bool {
-    //     return self.from != self.to
-    //         && !self.amount.is_nan()
-    //         && !self.amount.is_infinite()
-    //         && self.amount > 0.0;
-    // }
+
+    // Перевірки, що не залежать від балансів
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if self.from == self.to {
+            return Err(TransactionError::SameAddress { address: self.from });
+        }
+        if !self.amount.is_finite() || self.amount <= 0.0 {
+            return Err(TransactionError::InvalidAmount {
+                amount: self.amount,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionLocation {
+    pub block_height: u64,
+    pub block_hash: String,
+    pub position: usize,
 }
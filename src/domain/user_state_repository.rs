@@ -1,4 +1,9 @@
-use crate::domain::{block::Block, transaction::Transaction};
+use crate::domain::{
+    block::Block,
+    error::{BlockError, TransactionError},
+    state_snapshot::{StateSnapshot, compute_state_root},
+    transaction::Transaction,
+};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -7,7 +12,23 @@ use uuid::Uuid;
 pub trait UserStateRepository: Send + Sync {
     fn get_balances(&self) -> &HashMap<Uuid, f64>;
     fn get_balance(&self, address: &Uuid) -> f64;
-    fn set_balance(&mut self, address: Uuid, balance: f64);
-    fn apply_transaction(&mut self, transaction: &Transaction) -> bool;
-    async fn rebuild_from_blocks(&mut self, blocks: &Vec<Block>);
+    fn apply_transaction(&mut self, transaction: &Transaction) -> Result<(), TransactionError>;
+    // Повертає баланси до попереднього значення, якщо блок відхилено після застосування
+    fn reset_balances(&mut self, balances: HashMap<Uuid, f64>);
+    fn state_root(&self) -> String {
+        compute_state_root(self.get_balances())
+    }
+    // Викликається після того, як блок потрапив у ланцюг; знімає снапшот, коли настає його висота
+    fn commit_block(&mut self, block: &Block);
+    // Відновлює найсвіжіший снапшот, що лежить на `chain`, і повертає його висоту
+    fn restore_snapshot(&mut self, chain: &[Block]) -> Option<u64>;
+    // Той самий снапшот, але без зміни стану — для перевірки відгалуження на копії
+    fn snapshot_on(&self, chain: &[Block]) -> Option<StateSnapshot>;
+    fn latest_snapshot(&self) -> Option<StateSnapshot>;
+    // Замінює стан снапшотом, отриманим від пірів під час швидкої синхронізації
+    fn install_snapshot(&mut self, snapshot: StateSnapshot);
+    // Застосовує блоки вже з ланцюга; на першому невалідному блоці зупиняється,
+    // залишаючи стан після попереднього
+    async fn replay_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError>;
+    async fn rebuild_from_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError>;
 }
@@ -1,8 +1,125 @@
+use crate::domain::handshake::Handshake;
+use crate::domain::node_identity::{NodeIdentity, is_identity_key, verify_signature};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 
+/// A validator's decision on a block. Votes are relayed by other nodes, so
+/// each carries the voter's identity key, vouched for with the shared key as
+/// in the handshake, and is signed with it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vote {
     pub block_hash: String,
     pub voter_id: String,
     pub decision: String,
+    pub identity_key: String,
+    pub validator_pubkey: String,
+    pub signature: String,
+}
+
+impl Vote {
+    pub fn signed(
+        identity: &NodeIdentity,
+        shared_key: &str,
+        voter_id: &str,
+        block_hash: String,
+        decision: &str,
+    ) -> Self {
+        let identity_key = identity.public_key();
+        let mut vote = Self {
+            block_hash,
+            voter_id: voter_id.to_string(),
+            decision: decision.to_string(),
+            validator_pubkey: Handshake::validator_key(shared_key, voter_id, &identity_key),
+            identity_key,
+            signature: String::new(),
+        };
+        vote.signature = identity.sign(&vote.payload());
+        vote
+    }
+
+    // Хеш підписаного вмісту; під ним голос потрапляє в кеш побачених повідомлень
+    pub fn id(&self) -> String {
+        hex::encode(Sha256::digest(self.payload()))
+    }
+
+    // Чи голос справді від voter_id, хоч би через скількох пірів він пройшов.
+    // Голосують лише валідатори, а за кожним закріплено ключ ідентичності
+    pub fn verify(&self, shared_key: &str, pinned: &HashMap<String, String>) -> bool {
+        Handshake::vouches_for(
+            shared_key,
+            &self.voter_id,
+            &self.identity_key,
+            &self.validator_pubkey,
+        ) && pinned
+            .get(&self.voter_id)
+            .is_some_and(|expected| expected.eq_ignore_ascii_case(&self.identity_key))
+            && is_identity_key(&self.identity_key)
+            && verify_signature(&self.identity_key, &self.payload(), &self.signature)
+    }
+
+    fn payload(&self) -> Vec<u8> {
+        let mut payload = b"rust-chain vote".to_vec();
+        // Нульовий байт після кожного поля, щоб межі між ними були однозначні
+        for field in [
+            &self.block_hash,
+            &self.voter_id,
+            &self.decision,
+            &self.identity_key,
+        ] {
+            payload.extend_from_slice(field.as_bytes());
+            payload.push(0);
+        }
+        payload
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vote;
+    use crate::domain::node_identity::NodeIdentity;
+    use std::collections::HashMap;
+
+    const SHARED_KEY: &str = "test-key";
+
+    fn pinned(validators: &[&str]) -> HashMap<String, String> {
+        validators
+            .iter()
+            .map(|v| (v.to_string(), NodeIdentity::for_tests(v).public_key()))
+            .collect()
+    }
+
+    fn vote(identity: &NodeIdentity, voter_id: &str) -> Vote {
+        Vote::signed(identity, SHARED_KEY, voter_id, "ab".repeat(32), "ACK")
+    }
+
+    #[test]
+    fn accepts_a_vote_signed_with_the_voters_pinned_key() {
+        let vote = vote(&NodeIdentity::for_tests("v2"), "v2");
+
+        assert!(vote.verify(SHARED_KEY, &pinned(&["v1", "v2", "v3"])));
+    }
+
+    #[test]
+    fn rejects_a_vote_forged_for_another_validator() {
+        // Підпис і HMAC справжні, але ключ — v3, а не закріплений за v2
+        let forged = vote(&NodeIdentity::for_tests("v3"), "v2");
+
+        assert!(!forged.verify(SHARED_KEY, &pinned(&["v1", "v2", "v3"])));
+    }
+
+    #[test]
+    fn rejects_a_vote_from_a_node_that_is_not_a_validator() {
+        let vote = vote(&NodeIdentity::for_tests("n4"), "n4");
+
+        assert!(!vote.verify(SHARED_KEY, &pinned(&["v1", "v2", "v3"])));
+    }
+
+    #[test]
+    fn rejects_a_tampered_decision() {
+        let mut vote = vote(&NodeIdentity::for_tests("v2"), "v2");
+        vote.decision = "NACK".to_string();
+
+        assert!(!vote.verify(SHARED_KEY, &pinned(&["v1", "v2", "v3"])));
+    }
 }
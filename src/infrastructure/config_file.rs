@@ -0,0 +1,97 @@
+use crate::domain::error::ConfigError;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// Reads a TOML node config file straight into its serde representation.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|e| ConfigError::File {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+    toml::from_str(&text).map_err(|e| ConfigError::File {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::load;
+    use crate::domain::error::ConfigError;
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    struct Sample {
+        node: Node,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    struct Node {
+        id: String,
+        port: u16,
+    }
+
+    fn write(name: &str, text: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("config-file-{}-{}.toml", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_tables_into_the_target_type() {
+        let path = write(
+            "valid",
+            "# вузол\n[node]\nid = \"v1\" # коментар\nport = 3_001\n",
+        );
+
+        let sample: Sample = load(&path).unwrap();
+
+        assert_eq!(
+            sample,
+            Sample {
+                node: Node {
+                    id: "v1".to_string(),
+                    port: 3001,
+                },
+            }
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reports_syntax_and_schema_errors_with_the_file() {
+        for (name, text, expected) in [
+            (
+                "duplicate",
+                "[node]\nid = \"v1\"\nid = \"v2\"\nport = 1\n",
+                "line 3",
+            ),
+            (
+                "unknown",
+                "[node]\nid = \"v1\"\nport = 1\nname = \"x\"\n",
+                "name",
+            ),
+            ("type", "[node]\nid = \"v1\"\nport = \"3001\"\n", "port"),
+        ] {
+            let path = write(name, text);
+
+            let error = load::<Sample>(&path).unwrap_err();
+
+            let ConfigError::File {
+                path: reported,
+                reason,
+            } = error
+            else {
+                panic!("{}: unexpected error {:?}", name, error);
+            };
+            assert_eq!(reported, path);
+            assert!(reason.contains(expected), "{}: {}", name, reason);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
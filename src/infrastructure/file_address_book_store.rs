@@ -0,0 +1,46 @@
+use crate::domain::address_book::{AddressBook, AddressBookStore};
+use crate::domain::error::StorageError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// Keeps the address book in a single JSON file, rewritten atomically on every save.
+pub struct FileAddressBookStore {
+    path: PathBuf,
+}
+
+impl FileAddressBookStore {
+    /// Reads the book saved at `path`; a missing or unreadable file gives an empty book.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<(Self, AddressBook)> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let book = match fs::read(&path) {
+            Ok(data) => match serde_json::from_slice::<AddressBook>(&data) {
+                Ok(book) => {
+                    info!(addresses = book.len(), path = %path.display(), "Loaded address book");
+                    book
+                }
+                Err(e) => {
+                    // Книгу можна відновити з bootstrap-вузлів, тож не зупиняємо запуск
+                    warn!(path = %path.display(), error = %e, "Ignoring corrupt address book");
+                    AddressBook::default()
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => AddressBook::default(),
+            Err(e) => return Err(e),
+        };
+        Ok((Self { path }, book))
+    }
+}
+
+impl AddressBookStore for FileAddressBookStore {
+    fn save(&self, book: &AddressBook) -> Result<(), StorageError> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec(book)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
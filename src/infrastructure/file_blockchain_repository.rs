@@ -0,0 +1,129 @@
+use crate::domain::{
+    account_transfer::AccountTransfer, block::Block, blockchain_repository::BlockchainRepository,
+    error::StorageError, transaction::TransactionLocation,
+};
+use crate::infrastructure::in_memory_blockchain_repository::InMemoryBlockchainRepository;
+use async_trait::async_trait;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Chain stored as one JSON-encoded block per line. Blocks are appended on
+/// commit and the whole file is rewritten on `replace_chain`.
+pub struct FileBlockchainRepository {
+    inner: InMemoryBlockchainRepository,
+    path: PathBuf,
+    file: File,
+}
+
+impl FileBlockchainRepository {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut blocks = Vec::new();
+
+        match File::open(&path) {
+            Ok(file) => {
+                for line in BufReader::new(file).lines() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<Block>(&line) {
+                        Ok(block) => blocks.push(block),
+                        Err(e) => {
+                            // Обірваний запис у кінці файлу: зупиняємось на останньому цілому блоці
+                            warn!(error = %e, "Truncating chain at corrupt entry");
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        write_blocks(&path, &blocks)?;
+        let file = OpenOptions::new().append(true).create(true).open(&path)?;
+
+        info!(blocks = blocks.len(), path = %path.display(), "Loaded chain");
+
+        Ok(Self {
+            inner: InMemoryBlockchainRepository::from_blocks(blocks),
+            path,
+            file,
+        })
+    }
+}
+
+#[async_trait]
+impl BlockchainRepository for FileBlockchainRepository {
+    async fn get_all_blocks(&self) -> Vec<Block> {
+        self.inner.get_all_blocks().await
+    }
+
+    async fn add_block(&mut self, block: Block) -> Result<(), StorageError> {
+        let line = serde_json::to_string(&block)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.sync_data()?;
+        self.inner.push_block(block);
+        Ok(())
+    }
+
+    async fn get_last_block(&self) -> Option<Block> {
+        self.inner.get_last_block().await
+    }
+    async fn replace_chain(&mut self, new_chain: Vec<Block>) -> Result<(), StorageError> {
+        write_blocks(&self.path, &new_chain)?;
+        self.file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)?;
+        self.inner.set_blocks(new_chain);
+        Ok(())
+    }
+
+    async fn get_block_by_height(&self, height: u64) -> Option<Block> {
+        self.inner.get_block_by_height(height).await
+    }
+
+    async fn get_block_by_hash(&self, hash: &str) -> Option<Block> {
+        self.inner.get_block_by_hash(hash).await
+    }
+
+    async fn get_range(&self, from: u64, to: u64) -> Vec<Block> {
+        self.inner.get_range(from, to).await
+    }
+
+    async fn height(&self) -> Option<u64> {
+        self.inner.height().await
+    }
+
+    async fn get_transaction_location(&self, transaction_id: &Uuid) -> Option<TransactionLocation> {
+        self.inner.get_transaction_location(transaction_id).await
+    }
+
+    async fn get_account_transfers(
+        &self,
+        address: &Uuid,
+        before: Option<usize>,
+        limit: usize,
+    ) -> Vec<AccountTransfer> {
+        self.inner
+            .get_account_transfers(address, before, limit)
+            .await
+    }
+}
+
+fn write_blocks(path: &Path, blocks: &[Block]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut tmp = File::create(&tmp_path)?;
+        for block in blocks {
+            writeln!(tmp, "{}", serde_json::to_string(block)?)?;
+        }
+        tmp.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,194 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::domain::{
+    block::Block, mempool_repository::MempoolRepository, transaction::Transaction,
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum JournalEntry {
+    Add { transaction: Transaction },
+    Remove { ids: Vec<Uuid> },
+}
+
+/// Mempool that appends every addition and removal to a JSON-lines journal,
+/// so pending transactions survive a node restart.
+pub struct FileMempoolRepository {
+    transactions: VecDeque<Transaction>,
+    path: PathBuf,
+    journal: File,
+}
+
+impl FileMempoolRepository {
+    /// Replays the journal at `path` (if any) and compacts it down to the
+    /// transactions that are still pending.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let transactions = Self::replay(&path)?;
+
+        Self::compact(&path, &transactions)?;
+        let journal = OpenOptions::new().append(true).create(true).open(&path)?;
+
+        info!(
+            transactions = transactions.len(),
+            path = %path.display(),
+            "Loaded mempool journal"
+        );
+
+        Ok(Self {
+            transactions,
+            path,
+            journal,
+        })
+    }
+
+    fn replay(path: &Path) -> io::Result<VecDeque<Transaction>> {
+        let mut transactions = VecDeque::new();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(transactions),
+            Err(e) => return Err(e),
+        };
+
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A torn write at the tail of the journal is skipped rather than
+            // refusing to start.
+            match serde_json::from_str::<JournalEntry>(&line) {
+                Ok(JournalEntry::Add { transaction }) => {
+                    if !transactions.iter().any(|t| t.id == transaction.id) {
+                        transactions.push_back(transaction);
+                    }
+                }
+                Ok(JournalEntry::Remove { ids }) => {
+                    let ids: HashSet<Uuid> = ids.into_iter().collect();
+                    transactions.retain(|t| !ids.contains(&t.id));
+                }
+                Err(e) => warn!(error = %e, "Skipping corrupt mempool journal entry"),
+            }
+        }
+        Ok(transactions)
+    }
+
+    fn compact(path: &Path, transactions: &VecDeque<Transaction>) -> io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut tmp = File::create(&tmp_path)?;
+            for transaction in transactions {
+                let entry = JournalEntry::Add {
+                    transaction: transaction.clone(),
+                };
+                writeln!(tmp, "{}", serde_json::to_string(&entry)?)?;
+            }
+            tmp.sync_all()?;
+        }
+        fs::rename(&tmp_path, path)
+    }
+
+    fn append(&mut self, entry: &JournalEntry) {
+        let result = serde_json::to_string(entry)
+            .map_err(io::Error::from)
+            .and_then(|line| writeln!(self.journal, "{}", line))
+            .and_then(|_| self.journal.flush());
+        if let Err(e) = result {
+            error!(path = %self.path.display(), error = %e, "Failed to write mempool journal");
+        }
+    }
+}
+
+impl MempoolRepository for FileMempoolRepository {
+    fn add_transaction(&mut self, transaction: Transaction) {
+        self.append(&JournalEntry::Add {
+            transaction: transaction.clone(),
+        });
+        self.transactions.push_back(transaction);
+    }
+    fn get_all_transactions(&self) -> &VecDeque<Transaction> {
+        &self.transactions
+    }
+
+    fn check_exists_by_id(&self, transaction_id: &Uuid) -> bool {
+        self.transactions.iter().any(|t| t.id == *transaction_id)
+    }
+    fn get_transaction_by_id(&self, transaction_id: &Uuid) -> Option<Transaction> {
+        self.transactions
+            .iter()
+            .find(|t| t.id == *transaction_id)
+            .cloned()
+    }
+    fn drain_transactions(&mut self) -> VecDeque<Transaction> {
+        let drained: VecDeque<Transaction> = self.transactions.drain(..).collect();
+        if !drained.is_empty() {
+            self.append(&JournalEntry::Remove {
+                ids: drained.iter().map(|t| t.id).collect(),
+            });
+        }
+        drained
+    }
+    fn remove_included(&mut self, block: &Block) {
+        let included: HashSet<Uuid> = block.transactions.iter().map(|t| t.id).collect();
+        let ids: Vec<Uuid> = self
+            .transactions
+            .iter()
+            .filter(|t| included.contains(&t.id))
+            .map(|t| t.id)
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+        self.transactions.retain(|t| !included.contains(&t.id));
+        self.append(&JournalEntry::Remove { ids });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileMempoolRepository;
+    use crate::domain::{
+        block::Block, mempool_repository::MempoolRepository, transaction::Transaction,
+    };
+    use std::fs;
+    use uuid::Uuid;
+
+    #[test]
+    fn pruned_transactions_stay_pruned_after_a_restart() {
+        let path = std::env::temp_dir().join(format!("mempool-{}.jsonl", Uuid::new_v4()));
+        let included = Transaction::new(Uuid::from_u128(1), Uuid::from_u128(2), 1.0);
+        let pending = Transaction::new(Uuid::from_u128(1), Uuid::from_u128(3), 2.0);
+        let block = Block::new(
+            1,
+            0,
+            "v1".to_string(),
+            1,
+            vec![included.clone()],
+            "0".to_string(),
+            String::new(),
+            "key".to_string(),
+        );
+
+        let mut mempool = FileMempoolRepository::open(&path).unwrap();
+        mempool.add_transaction(included.clone());
+        mempool.add_transaction(pending.clone());
+        mempool.remove_included(&block);
+        drop(mempool);
+
+        let reopened = FileMempoolRepository::open(&path).unwrap();
+        let ids: Vec<Uuid> = reopened
+            .get_all_transactions()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(ids, vec![pending.id]);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::domain::block::Block;
+use crate::domain::error::{BlockError, TransactionError};
+use crate::domain::state_snapshot::StateSnapshot;
+use crate::domain::transaction::Transaction;
+use crate::domain::user_state_repository::UserStateRepository;
+use crate::infrastructure::in_memory_user_state_repository::InMemoryUserStateRepository;
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::slice;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+const TIP_FILE: &str = "state.json";
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// User state that persists the balances at the chain tip on every commit,
+/// plus periodic snapshots used to roll back past a fork.
+///
+/// Layout of `dir`:
+/// - `state.json` — balances after the last committed block;
+/// - `snapshots/<height>.json` — balances at every `snapshot_interval`-th height.
+pub struct FileUserStateRepository {
+    inner: InMemoryUserStateRepository,
+    dir: PathBuf,
+    tip: Option<StateSnapshot>,
+}
+
+impl FileUserStateRepository {
+    pub fn open(dir: impl AsRef<Path>, snapshot_interval: u64) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(dir.join(SNAPSHOTS_DIR))?;
+
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(dir.join(SNAPSHOTS_DIR))? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                match read_snapshot(&path) {
+                    Ok(snapshot) => snapshots.push(snapshot),
+                    Err(e) => warn!(
+                        path = %path.display(),
+                        error = %e,
+                        "Skipping unreadable snapshot"
+                    ),
+                }
+            }
+        }
+        snapshots.sort_by_key(|s| s.height);
+
+        let tip = match read_snapshot(&dir.join(TIP_FILE)) {
+            Ok(tip) => Some(tip),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => {
+                warn!(file = TIP_FILE, error = %e, "Ignoring unreadable state tip");
+                None
+            }
+        };
+
+        info!(
+            snapshots = snapshots.len(),
+            dir = %dir.display(),
+            tip_height = ?tip.as_ref().map(|t| t.height),
+            "Opened state directory"
+        );
+
+        let mut inner = InMemoryUserStateRepository::with_snapshot_interval(snapshot_interval);
+        inner.load_snapshots(snapshots);
+
+        let repo = Self { inner, dir, tip };
+        repo.prune_snapshot_files();
+        Ok(repo)
+    }
+
+    fn snapshot_path(&self, height: u64) -> PathBuf {
+        self.dir
+            .join(SNAPSHOTS_DIR)
+            .join(format!("{:012}.json", height))
+    }
+
+    fn write_tip(&mut self, block: &Block) {
+        let tip = StateSnapshot {
+            height: block.header.height,
+            block_hash: block.hash.clone(),
+            balances: self.inner.get_balances().clone(),
+        };
+        if let Err(e) = write_snapshot(&self.dir.join(TIP_FILE), &tip) {
+            error!(height = tip.height, error = %e, "Failed to persist state");
+        }
+        self.tip = Some(tip);
+    }
+
+    fn write_snapshot_if_due(&self, block: &Block) {
+        let height = block.header.height;
+        if !self.inner.is_snapshot_height(height) {
+            return;
+        }
+        let Some(snapshot) = self.inner.snapshots().iter().find(|s| s.height == height) else {
+            return;
+        };
+        match write_snapshot(&self.snapshot_path(height), snapshot) {
+            Ok(()) => info!(height, "Snapshot saved"),
+            Err(e) => error!(height, error = %e, "Failed to save snapshot"),
+        }
+        self.prune_snapshot_files();
+    }
+
+    fn tip_on_chain(&self, chain: &[Block]) -> bool {
+        self.tip.as_ref().is_some_and(|tip| {
+            chain
+                .iter()
+                .find(|b| b.header.height == tip.height)
+                .is_some_and(|b| b.hash == tip.block_hash)
+        })
+    }
+
+    // Видаляє з диска снапшоти, яких більше немає в пам'яті
+    fn prune_snapshot_files(&self) {
+        let kept: HashSet<PathBuf> = self
+            .inner
+            .snapshots()
+            .iter()
+            .map(|s| self.snapshot_path(s.height))
+            .collect();
+        let Ok(entries) = fs::read_dir(self.dir.join(SNAPSHOTS_DIR)) else {
+            return;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if !kept.contains(&path) {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl UserStateRepository for FileUserStateRepository {
+    fn get_balances(&self) -> &HashMap<Uuid, f64> {
+        self.inner.get_balances()
+    }
+
+    fn get_balance(&self, address: &Uuid) -> f64 {
+        self.inner.get_balance(address)
+    }
+
+    fn apply_transaction(&mut self, transaction: &Transaction) -> Result<(), TransactionError> {
+        self.inner.apply_transaction(transaction)
+    }
+
+    fn reset_balances(&mut self, balances: HashMap<Uuid, f64>) {
+        self.inner.reset_balances(balances);
+    }
+
+    fn commit_block(&mut self, block: &Block) {
+        self.inner.commit_block(block);
+        self.write_snapshot_if_due(block);
+        self.write_tip(block);
+    }
+
+    fn restore_snapshot(&mut self, chain: &[Block]) -> Option<u64> {
+        let from_snapshots = self.inner.restore_snapshot(chain);
+
+        let tip_on_chain = self.tip_on_chain(chain);
+        let restored = match self.tip.clone() {
+            Some(tip) if tip_on_chain && from_snapshots.is_none_or(|h| tip.height >= h) => {
+                self.inner.restore(&tip);
+                Some(tip.height)
+            }
+            _ => from_snapshots,
+        };
+
+        self.prune_snapshot_files();
+        restored
+    }
+
+    fn snapshot_on(&self, chain: &[Block]) -> Option<StateSnapshot> {
+        let from_snapshots = self.inner.snapshot_on(chain);
+        match &self.tip {
+            Some(tip)
+                if self.tip_on_chain(chain)
+                    && from_snapshots
+                        .as_ref()
+                        .is_none_or(|s| tip.height >= s.height) =>
+            {
+                Some(tip.clone())
+            }
+            _ => from_snapshots,
+        }
+    }
+
+    fn latest_snapshot(&self) -> Option<StateSnapshot> {
+        self.inner.latest_snapshot()
+    }
+
+    fn install_snapshot(&mut self, snapshot: StateSnapshot) {
+        let height = snapshot.height;
+        if let Err(e) = write_snapshot(&self.snapshot_path(height), &snapshot) {
+            error!(height, error = %e, "Failed to save snapshot");
+        }
+        if let Err(e) = write_snapshot(&self.dir.join(TIP_FILE), &snapshot) {
+            error!(height, error = %e, "Failed to persist state");
+        }
+        self.tip = Some(snapshot.clone());
+        self.inner.install_snapshot(snapshot);
+        self.prune_snapshot_files();
+    }
+
+    async fn replay_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError> {
+        let mut applied = None;
+        let mut result = Ok(());
+        for block in blocks {
+            if let Err(e) = self.inner.replay_blocks(slice::from_ref(block)).await {
+                result = Err(e);
+                break;
+            }
+            self.write_snapshot_if_due(block);
+            applied = Some(block);
+        }
+        if let Some(last) = applied {
+            self.write_tip(last);
+        }
+        result
+    }
+
+    async fn rebuild_from_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError> {
+        self.inner.rebuild_from_blocks(&[]).await?;
+        self.tip = None;
+        self.prune_snapshot_files();
+        self.replay_blocks(blocks).await
+    }
+}
+
+fn read_snapshot(path: &Path) -> io::Result<StateSnapshot> {
+    let data = fs::read(path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+fn write_snapshot(path: &Path, snapshot: &StateSnapshot) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(snapshot)?)?;
+    fs::rename(&tmp_path, path)
+}
@@ -0,0 +1,47 @@
+use crate::domain::node_identity::NodeIdentity;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use tracing::info;
+
+/// Reads the identity key saved at `path`, or generates one and saves it there.
+/// The file holds the PKCS#8 document in hex.
+pub fn load_or_create(path: impl AsRef<Path>) -> io::Result<NodeIdentity> {
+    let path = path.as_ref();
+    match fs::read_to_string(path) {
+        Ok(text) => {
+            // Зіпсований ключ не замінюємо мовчки: піри, що закріпили старий, відмовлять новому
+            let pkcs8 = hex::decode(text.trim()).map_err(|e| invalid_key(path, &e.to_string()))?;
+            NodeIdentity::from_pkcs8(&pkcs8).map_err(|e| invalid_key(path, &e))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                fs::create_dir_all(dir)?;
+            }
+            let identity = NodeIdentity::generate();
+            write_private(path, hex::encode(identity.pkcs8()).as_bytes())?;
+            info!(path = %path.display(), "Generated a new identity key");
+            Ok(identity)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn invalid_key(path: &Path, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{} is not an identity key: {}", path.display(), reason),
+    )
+}
+
+// Приватний ключ читає лише власник файлу
+fn write_private(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(data)
+}
@@ -1,13 +1,92 @@
-use crate::domain::{block::Block, blockchain_repository::BlockchainRepository};
+use crate::domain::{
+    account_transfer::{AccountTransfer, TransferDirection},
+    block::Block,
+    blockchain_repository::BlockchainRepository,
+    error::StorageError,
+    transaction::{Transaction, TransactionLocation},
+};
 use async_trait::async_trait;
+use std::collections::HashMap;
+use uuid::Uuid;
 
 pub struct InMemoryBlockchainRepository {
     blocks: Vec<Block>,
+    hash_index: HashMap<String, usize>,
+    // id транзакції -> (позиція блоку в ланцюгу, позиція транзакції в блоці)
+    tx_index: HashMap<Uuid, (usize, usize)>,
+    address_index: HashMap<Uuid, Vec<AccountTransfer>>,
 }
 
 impl InMemoryBlockchainRepository {
     pub fn new() -> Self {
-        Self { blocks: vec![] }
+        Self {
+            blocks: vec![],
+            hash_index: HashMap::new(),
+            tx_index: HashMap::new(),
+            address_index: HashMap::new(),
+        }
+    }
+
+    pub fn from_blocks(blocks: Vec<Block>) -> Self {
+        let mut repo = Self::new();
+        repo.set_blocks(blocks);
+        repo
+    }
+
+    pub fn set_blocks(&mut self, blocks: Vec<Block>) {
+        self.blocks.clear();
+        self.hash_index.clear();
+        self.tx_index.clear();
+        self.address_index.clear();
+        for block in blocks {
+            self.push_block(block);
+        }
+    }
+
+    pub fn push_block(&mut self, block: Block) {
+        let block_position = self.blocks.len();
+        self.hash_index.insert(block.hash.clone(), block_position);
+        for (tx_position, tx) in block.transactions.iter().enumerate() {
+            self.tx_index.insert(tx.id, (block_position, tx_position));
+            // Транзакції генезис-блоку лише створюють монети, тож у відправника запису немає
+            if block.header.height > 0 {
+                self.record_transfer(&block, tx, TransferDirection::Outgoing);
+            }
+            self.record_transfer(&block, tx, TransferDirection::Incoming);
+        }
+        self.blocks.push(block);
+    }
+
+    // Баланси змінюються лише транзакціями в блоках, тож баланс після переказу
+    // рахується від попереднього запису в історії рахунку
+    fn record_transfer(&mut self, block: &Block, tx: &Transaction, direction: TransferDirection) {
+        let (address, counterparty, delta) = match direction {
+            TransferDirection::Incoming => (tx.to, tx.from, tx.amount),
+            TransferDirection::Outgoing => (tx.from, tx.to, -tx.amount),
+        };
+        let history = self.address_index.entry(address).or_default();
+        let balance_before = history.last().map_or(0.0, |t| t.balance_after);
+        history.push(AccountTransfer {
+            seq: history.len(),
+            transaction_id: tx.id,
+            block_height: block.header.height,
+            block_hash: block.hash.clone(),
+            direction,
+            counterparty,
+            amount: tx.amount,
+            balance_after: balance_before + delta,
+            timestamp: tx.timestamp,
+        });
+    }
+
+    // Висоти в ланцюгу йдуть підряд, тож позиція блоку обчислюється від першого блоку
+    fn position_of_height(&self, height: u64) -> Option<usize> {
+        let first = self.blocks.first()?.header.height;
+        let position = usize::try_from(height.checked_sub(first)?).ok()?;
+        self.blocks
+            .get(position)
+            .filter(|b| b.header.height == height)
+            .map(|_| position)
     }
 }
 
@@ -17,14 +96,69 @@ impl BlockchainRepository for InMemoryBlockchainRepository {
         self.blocks.clone()
     }
 
-    async fn add_block(&mut self, block: Block) {
-        self.blocks.push(block);
+    async fn add_block(&mut self, block: Block) -> Result<(), StorageError> {
+        self.push_block(block);
+        Ok(())
     }
 
-    async fn get_last_block(&self) -> Block {
-        self.blocks.last().cloned().unwrap()
+    async fn get_last_block(&self) -> Option<Block> {
+        self.blocks.last().cloned()
     }
-    async fn replace_chain(&mut self, new_chain: Vec<Block>) {
-        self.blocks = new_chain;
+    async fn replace_chain(&mut self, new_chain: Vec<Block>) -> Result<(), StorageError> {
+        self.set_blocks(new_chain);
+        Ok(())
+    }
+
+    async fn get_block_by_height(&self, height: u64) -> Option<Block> {
+        self.position_of_height(height)
+            .map(|position| self.blocks[position].clone())
+    }
+
+    async fn get_block_by_hash(&self, hash: &str) -> Option<Block> {
+        self.hash_index
+            .get(hash)
+            .map(|&position| self.blocks[position].clone())
+    }
+
+    async fn get_range(&self, from: u64, to: u64) -> Vec<Block> {
+        let (Some(first), Some(last)) = (self.blocks.first(), self.blocks.last()) else {
+            return Vec::new();
+        };
+        let from = from.max(first.header.height);
+        let to = to.min(last.header.height);
+        if from > to {
+            return Vec::new();
+        }
+        match (self.position_of_height(from), self.position_of_height(to)) {
+            (Some(start), Some(end)) => self.blocks[start..=end].to_vec(),
+            _ => Vec::new(),
+        }
+    }
+
+    async fn height(&self) -> Option<u64> {
+        self.blocks.last().map(|b| b.header.height)
+    }
+
+    async fn get_transaction_location(&self, transaction_id: &Uuid) -> Option<TransactionLocation> {
+        let &(block_position, position) = self.tx_index.get(transaction_id)?;
+        let block = &self.blocks[block_position];
+        Some(TransactionLocation {
+            block_height: block.header.height,
+            block_hash: block.hash.clone(),
+            position,
+        })
+    }
+
+    async fn get_account_transfers(
+        &self,
+        address: &Uuid,
+        before: Option<usize>,
+        limit: usize,
+    ) -> Vec<AccountTransfer> {
+        let Some(history) = self.address_index.get(address) else {
+            return Vec::new();
+        };
+        let end = before.map_or(history.len(), |seq| seq.min(history.len()));
+        history[..end].iter().rev().take(limit).cloned().collect()
     }
 }
@@ -1,22 +1,104 @@
 use crate::domain::block::Block;
+use crate::domain::error::{BlockError, TransactionError};
+use crate::domain::state_snapshot::StateSnapshot;
 use crate::domain::transaction::Transaction;
 use crate::domain::user_state_repository::UserStateRepository;
 use async_trait::async_trait;
-use std::collections::HashMap;
-use std::env;
+use std::collections::{HashMap, VecDeque};
 use uuid::Uuid;
 
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 100;
+const SNAPSHOTS_TO_KEEP: usize = 5;
+
 pub struct InMemoryUserStateRepository {
     balances: HashMap<Uuid, f64>,
+    snapshots: VecDeque<StateSnapshot>,
+    snapshot_interval: u64,
 }
 
 impl InMemoryUserStateRepository {
-    pub fn new() -> Self {
+    pub fn with_snapshot_interval(snapshot_interval: u64) -> Self {
         Self {
             balances: HashMap::new(),
+            snapshots: VecDeque::new(),
+            snapshot_interval: snapshot_interval.max(1),
+        }
+    }
+
+    pub fn snapshots(&self) -> &VecDeque<StateSnapshot> {
+        &self.snapshots
+    }
+
+    // Приймає снапшоти, прочитані з диска; вони мають бути впорядковані за висотою
+    pub fn load_snapshots(&mut self, snapshots: Vec<StateSnapshot>) {
+        self.snapshots = snapshots.into();
+        while self.snapshots.len() > SNAPSHOTS_TO_KEEP {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn is_snapshot_height(&self, height: u64) -> bool {
+        height.is_multiple_of(self.snapshot_interval)
+    }
+
+    fn current_snapshot(&self, block: &Block) -> StateSnapshot {
+        StateSnapshot {
+            height: block.header.height,
+            block_hash: block.hash.clone(),
+            balances: self.balances.clone(),
+        }
+    }
+
+    // Повертає стан до снапшоту і забуває всі новіші снапшоти
+    pub fn restore(&mut self, snapshot: &StateSnapshot) {
+        self.balances = snapshot.balances.clone();
+        self.snapshots.retain(|s| s.height <= snapshot.height);
+    }
+
+    // Застосовує транзакції блоку з ланцюга. Генезис-блок лише карбує монети;
+    // якщо транзакція невалідна, змінені баланси повертаються до стану перед блоком
+    fn apply_block(&mut self, block: &Block) -> Result<(), BlockError> {
+        if block.header.height == 0 {
+            for tx in &block.transactions {
+                *self.balances.entry(tx.to).or_insert(0.0) += tx.amount;
+            }
+            return Ok(());
         }
+
+        let mut touched: HashMap<Uuid, Option<f64>> = HashMap::new();
+        for tx in &block.transactions {
+            for address in [tx.from, tx.to] {
+                touched
+                    .entry(address)
+                    .or_insert_with(|| self.balances.get(&address).copied());
+            }
+            if let Err(reason) = self.apply_transaction(tx) {
+                for (address, balance) in touched {
+                    match balance {
+                        Some(balance) => self.balances.insert(address, balance),
+                        None => self.balances.remove(&address),
+                    };
+                }
+                return Err(BlockError::InvalidTransaction {
+                    height: block.header.height,
+                    transaction_id: tx.id,
+                    reason,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn find_snapshot_on_chain(&self, chain: &[Block]) -> Option<usize> {
+        self.snapshots.iter().rposition(|snapshot| {
+            chain
+                .iter()
+                .find(|b| b.header.height == snapshot.height)
+                .is_some_and(|b| b.hash == snapshot.block_hash)
+        })
     }
 }
+
 #[async_trait]
 impl UserStateRepository for InMemoryUserStateRepository {
     fn get_balances(&self) -> &HashMap<Uuid, f64> {
@@ -27,62 +109,75 @@ impl UserStateRepository for InMemoryUserStateRepository {
         *self.balances.get(address).unwrap_or(&0.0)
     }
 
-    fn set_balance(&mut self, address: Uuid, balance: f64) {
-        self.balances.insert(address, balance);
-    }
-
-    fn apply_transaction(&mut self, transaction: &Transaction) -> bool {
+    fn apply_transaction(&mut self, transaction: &Transaction) -> Result<(), TransactionError> {
+        transaction.validate()?;
         let sender_balance = self.get_balance(&transaction.from);
-        println!("Sender balance: {}", sender_balance);
-        println!("Transaction amount: {}", transaction.amount);
         if sender_balance < transaction.amount {
-            println!("Insufficient balance for sender {}", transaction.from);
-            return false;
+            return Err(TransactionError::InsufficientFunds {
+                address: transaction.from,
+                balance: sender_balance,
+                amount: transaction.amount,
+            });
         }
 
-        let new_sender_balance = sender_balance - transaction.amount;
-        self.balances.insert(transaction.from, new_sender_balance);
-
+        self.balances
+            .insert(transaction.from, sender_balance - transaction.amount);
         let receiver_balance = self.get_balance(&transaction.to);
-        let new_receiver_balance = receiver_balance + transaction.amount;
-        self.balances.insert(transaction.to, new_receiver_balance);
+        self.balances
+            .insert(transaction.to, receiver_balance + transaction.amount);
+        Ok(())
+    }
 
-        println!(
-            "Transaction applied: {} -> {}",
-            transaction.from, transaction.to
-        );
-        true
+    fn reset_balances(&mut self, balances: HashMap<Uuid, f64>) {
+        self.balances = balances;
     }
-    async fn rebuild_from_blocks(&mut self, blocks: &Vec<Block>) {
-        self.balances.clear();
 
-        let genesis_sender_str = env::var("GENESIS_SENDER_ID").expect("GENESIS_SENDER_ID");
-        let genesis_sender_id = Uuid::parse_str(&genesis_sender_str).unwrap();
+    fn commit_block(&mut self, block: &Block) {
+        if !self.is_snapshot_height(block.header.height) {
+            return;
+        }
+        let snapshot = self.current_snapshot(block);
+        self.snapshots.retain(|s| s.height < snapshot.height);
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > SNAPSHOTS_TO_KEEP {
+            self.snapshots.pop_front();
+        }
+    }
 
-        for block in blocks.iter() {
-            for tx in &block.transactions {
-                if tx.from == genesis_sender_id {
-                    let receiver_balance = self.get_balance(&tx.to);
-                    let new_receiver_balance = receiver_balance + tx.amount;
-                    self.balances.insert(tx.to, new_receiver_balance);
-
-                    println!("GENESIS Transaction: {} added to {}", tx.amount, tx.to);
-                } else {
-                    let sender_balance = self.get_balance(&tx.from);
-
-                    if sender_balance < tx.amount {
-                        println!("REBUILD FAILED: Insufficient funds for {}", tx.from);
-                        continue;
-                    }
-
-                    let new_sender_balance = sender_balance - tx.amount;
-                    self.balances.insert(tx.from, new_sender_balance);
-
-                    let receiver_balance = self.get_balance(&tx.to);
-                    let new_receiver_balance = receiver_balance + tx.amount;
-                    self.balances.insert(tx.to, new_receiver_balance);
-                }
-            }
+    fn restore_snapshot(&mut self, chain: &[Block]) -> Option<u64> {
+        let position = self.find_snapshot_on_chain(chain)?;
+        // Снапшоти після точки відновлення належать відкинутому відгалуженню
+        let snapshot = self.snapshots[position].clone();
+        self.restore(&snapshot);
+        Some(snapshot.height)
+    }
+
+    fn snapshot_on(&self, chain: &[Block]) -> Option<StateSnapshot> {
+        let position = self.find_snapshot_on_chain(chain)?;
+        Some(self.snapshots[position].clone())
+    }
+
+    fn latest_snapshot(&self) -> Option<StateSnapshot> {
+        self.snapshots.back().cloned()
+    }
+
+    fn install_snapshot(&mut self, snapshot: StateSnapshot) {
+        self.balances = snapshot.balances.clone();
+        self.snapshots.clear();
+        self.snapshots.push_back(snapshot);
+    }
+
+    async fn replay_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError> {
+        for block in blocks {
+            self.apply_block(block)?;
+            self.commit_block(block);
         }
+        Ok(())
+    }
+
+    async fn rebuild_from_blocks(&mut self, blocks: &[Block]) -> Result<(), BlockError> {
+        self.balances.clear();
+        self.snapshots.clear();
+        self.replay_blocks(blocks).await
     }
 }
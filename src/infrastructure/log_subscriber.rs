@@ -0,0 +1,26 @@
+use crate::domain::config::LogFormat;
+use std::io::IsTerminal;
+use tracing::Level;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
+
+/// Installs the global `tracing` subscriber that writes events to stdout with the fields
+/// of every entered span. `RUST_LOG` directives, when set, refine the configured level.
+pub fn install(level: Level, format: LogFormat) {
+    let filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::from_level(level).into())
+        .from_env_lossy();
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_ansi(std::io::stdout().is_terminal());
+    // Повторне встановлення (напр. у тестах) нічого не змінює
+    let _ = match format {
+        LogFormat::Text => builder.with_target(false).try_init(),
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .try_init(),
+    };
+}
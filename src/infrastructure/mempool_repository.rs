@@ -1,8 +1,10 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use uuid::Uuid;
 
-use crate::domain::{mempool_repository::MempoolRepository, transaction::Transaction};
+use crate::domain::{
+    block::Block, mempool_repository::MempoolRepository, transaction::Transaction,
+};
 
 pub struct InMemoryMempoolRepository {
     pub transactions: VecDeque<Transaction>,
@@ -27,7 +29,54 @@ impl MempoolRepository for InMemoryMempoolRepository {
     fn check_exists_by_id(&self, transaction_id: &Uuid) -> bool {
         self.transactions.iter().any(|t| t.id == *transaction_id)
     }
+    fn get_transaction_by_id(&self, transaction_id: &Uuid) -> Option<Transaction> {
+        self.transactions
+            .iter()
+            .find(|t| t.id == *transaction_id)
+            .cloned()
+    }
     fn drain_transactions(&mut self) -> VecDeque<Transaction> {
         self.transactions.drain(..).collect()
     }
+    fn remove_included(&mut self, block: &Block) {
+        let included: HashSet<Uuid> = block.transactions.iter().map(|t| t.id).collect();
+        self.transactions.retain(|t| !included.contains(&t.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InMemoryMempoolRepository;
+    use crate::domain::{
+        block::Block, mempool_repository::MempoolRepository, transaction::Transaction,
+    };
+    use uuid::Uuid;
+
+    #[test]
+    fn remove_included_keeps_only_transactions_missing_from_the_block() {
+        let mut mempool = InMemoryMempoolRepository::new();
+        let included = Transaction::new(Uuid::new_v4(), Uuid::new_v4(), 1.0);
+        let pending = Transaction::new(Uuid::new_v4(), Uuid::new_v4(), 2.0);
+        mempool.add_transaction(included.clone());
+        mempool.add_transaction(pending.clone());
+        let block = Block::new(
+            1,
+            0,
+            "v1".to_string(),
+            1,
+            vec![included],
+            "0".repeat(64),
+            "0".repeat(64),
+            "test-key".to_string(),
+        );
+
+        mempool.remove_included(&block);
+
+        let ids: Vec<Uuid> = mempool
+            .get_all_transactions()
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![pending.id]);
+    }
 }
@@ -1,3 +1,12 @@
+pub mod config_file;
+pub mod file_address_book_store;
+pub mod file_blockchain_repository;
+pub mod file_mempool_repository;
+pub mod file_user_state_repository;
+pub mod identity_file;
 pub mod in_memory_blockchain_repository;
 pub mod in_memory_user_state_repository;
+pub mod log_subscriber;
 pub mod mempool_repository;
+pub mod peer_codec;
+pub mod secure_channel;
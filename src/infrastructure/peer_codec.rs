@@ -0,0 +1,273 @@
+use crate::domain::error::FrameError;
+use crate::domain::peer_message::{MessageKind, PeerMessage};
+use crate::infrastructure::secure_channel::{OpeningKey, SealingKey, TAG_BYTES};
+use serde::de::DeserializeOwned;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// Кадр: довжина зашифрованого тіла (u32, big-endian), тип повідомлення (u8), тіло в JSON,
+// зашифроване разом із тегом автентичності. Заголовок не шифрується, щоб ліміт типу
+// перевірявся до читання тіла, але входить в автентифіковані дані
+const HEADER_BYTES: usize = 5;
+
+// Кадр, якого отримувач не прийме, не надсилається зовсім; ключ при цьому не
+// зсувається, тож з'єднанням можна користуватися далі
+pub fn encode(message: &PeerMessage, key: &mut SealingKey) -> Result<Vec<u8>, FrameError> {
+    let body = match message {
+        PeerMessage::Hello(hello) => serde_json::to_vec(hello),
+        PeerMessage::Block(block) => serde_json::to_vec(block),
+        PeerMessage::Vote(vote) => serde_json::to_vec(vote),
+        PeerMessage::TxAnnounce(transaction) => serde_json::to_vec(transaction),
+        PeerMessage::SyncRequest(request) => serde_json::to_vec(request),
+        PeerMessage::SyncResponse(response) => serde_json::to_vec(response),
+    }
+    .expect("peer messages always serialize");
+    let kind = message.kind();
+    if body.len() > kind.max_body_bytes() {
+        return Err(FrameError::TooLarge {
+            kind: kind.as_str(),
+            len: body.len(),
+            max: kind.max_body_bytes(),
+        });
+    }
+    let mut frame = Vec::with_capacity(HEADER_BYTES + body.len() + TAG_BYTES);
+    frame.extend_from_slice(&((body.len() + TAG_BYTES) as u32).to_be_bytes());
+    frame.push(kind.as_byte());
+    let mut sealed = body;
+    key.seal(&frame, &mut sealed);
+    frame.extend_from_slice(&sealed);
+    Ok(frame)
+}
+
+pub async fn write_message<W>(
+    writer: &mut W,
+    key: &mut SealingKey,
+    message: &PeerMessage,
+) -> Result<(), FrameError>
+where
+    W: AsyncWrite + Unpin,
+{
+    let frame = encode(message, key)?;
+    writer
+        .write_all(&frame)
+        .await
+        .map_err(|e| FrameError::Io(e.to_string()))?;
+    writer
+        .flush()
+        .await
+        .map_err(|e| FrameError::Io(e.to_string()))
+}
+
+// Після Malformed з'єднанням можна користуватися далі: тіло кадру вже прочитане.
+// Після решти помилок межі кадрів втрачено, і з'єднання треба закрити
+pub async fn read_message<R>(
+    reader: &mut R,
+    key: &mut OpeningKey,
+) -> Result<PeerMessage, FrameError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0u8; HEADER_BYTES];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Err(FrameError::Closed),
+        Err(e) => return Err(FrameError::Io(e.to_string())),
+    }
+    let sealed_len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let kind = MessageKind::from_byte(header[4]).ok_or(FrameError::UnknownKind(header[4]))?;
+    let Some(len) = sealed_len.checked_sub(TAG_BYTES) else {
+        return Err(FrameError::Decrypt);
+    };
+    if len > kind.max_body_bytes() {
+        return Err(FrameError::TooLarge {
+            kind: kind.as_str(),
+            len,
+            max: kind.max_body_bytes(),
+        });
+    }
+    let mut sealed = vec![0u8; sealed_len];
+    reader
+        .read_exact(&mut sealed)
+        .await
+        .map_err(|e| FrameError::Io(e.to_string()))?;
+    let body = key.open(&header, &mut sealed).ok_or(FrameError::Decrypt)?;
+    decode(kind, body)
+}
+
+fn decode(kind: MessageKind, body: &[u8]) -> Result<PeerMessage, FrameError> {
+    Ok(match kind {
+        MessageKind::Hello => PeerMessage::Hello(parse(kind, body)?),
+        MessageKind::Block => PeerMessage::Block(parse(kind, body)?),
+        MessageKind::Vote => PeerMessage::Vote(parse(kind, body)?),
+        MessageKind::TxAnnounce => PeerMessage::TxAnnounce(parse(kind, body)?),
+        MessageKind::SyncRequest => PeerMessage::SyncRequest(parse(kind, body)?),
+        MessageKind::SyncResponse => PeerMessage::SyncResponse(parse(kind, body)?),
+    })
+}
+
+fn parse<T: DeserializeOwned>(kind: MessageKind, body: &[u8]) -> Result<T, FrameError> {
+    serde_json::from_slice(body).map_err(|e| FrameError::Malformed {
+        kind: kind.as_str(),
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HEADER_BYTES, encode, read_message};
+    use crate::domain::error::FrameError;
+    use crate::domain::gossip::{GOSSIP_TTL, Gossip};
+    use crate::domain::node_identity::NodeIdentity;
+    use crate::domain::peer_message::{MessageKind, PeerMessage, SyncQuery, SyncRequest};
+    use crate::domain::vote::Vote;
+    use crate::infrastructure::secure_channel::{OpeningKey, SealingKey, TAG_BYTES};
+
+    const KEY: [u8; 32] = [7; 32];
+
+    fn vote() -> Gossip<Vote> {
+        Gossip::new(Vote::signed(
+            &NodeIdentity::for_tests("v2"),
+            "k",
+            "v2",
+            "ab".repeat(32),
+            "ACK",
+        ))
+    }
+
+    // Кадр із довільним тілом, якого encode не створить
+    fn raw_frame(key: &mut SealingKey, kind: u8, body: &[u8]) -> Vec<u8> {
+        let mut frame = ((body.len() + TAG_BYTES) as u32).to_be_bytes().to_vec();
+        frame.push(kind);
+        let mut sealed = body.to_vec();
+        key.seal(&frame, &mut sealed);
+        frame.extend_from_slice(&sealed);
+        frame
+    }
+
+    #[tokio::test]
+    async fn reads_back_consecutive_frames() {
+        let request = SyncRequest {
+            id: 7,
+            query: SyncQuery::Blocks { from: 1, to: 100 },
+        };
+        let mut sealing = SealingKey::new(&KEY);
+        let mut stream = encode(&PeerMessage::Vote(vote()), &mut sealing).unwrap();
+        stream.extend(encode(&PeerMessage::SyncRequest(request), &mut sealing).unwrap());
+        let mut reader = stream.as_slice();
+        let mut opening = OpeningKey::new(&KEY);
+
+        match read_message(&mut reader, &mut opening).await.unwrap() {
+            PeerMessage::Vote(vote) => {
+                assert_eq!(vote.ttl, GOSSIP_TTL);
+                let pinned = [("v2".to_string(), NodeIdentity::for_tests("v2").public_key())];
+                assert!(vote.item.verify("k", &pinned.into()));
+            }
+            other => panic!("expected a vote, got {:?}", other.kind()),
+        }
+        match read_message(&mut reader, &mut opening).await.unwrap() {
+            PeerMessage::SyncRequest(request) => {
+                assert_eq!(request.id, 7);
+                assert!(matches!(
+                    request.query,
+                    SyncQuery::Blocks { from: 1, to: 100 }
+                ));
+            }
+            other => panic!("expected a sync request, got {:?}", other.kind()),
+        }
+        assert_eq!(
+            read_message(&mut reader, &mut opening).await.unwrap_err(),
+            FrameError::Closed
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_and_malformed_frames() {
+        let max = MessageKind::Vote.max_body_bytes();
+        let mut oversized = ((max + 1 + TAG_BYTES) as u32).to_be_bytes().to_vec();
+        oversized.push(MessageKind::Vote.as_byte());
+        assert_eq!(
+            read_message(&mut oversized.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::TooLarge {
+                kind: "vote",
+                len: max + 1,
+                max
+            }
+        );
+
+        let malformed = raw_frame(
+            &mut SealingKey::new(&KEY),
+            MessageKind::Block.as_byte(),
+            b"{}",
+        );
+        assert!(matches!(
+            read_message(&mut malformed.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::Malformed { kind: "block", .. }
+        ));
+
+        let unknown = [0, 0, 0, 0, 42];
+        assert_eq!(
+            read_message(&mut unknown.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::UnknownKind(42)
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_to_encode_an_oversized_frame() {
+        let oversized = PeerMessage::Vote(Gossip::new(Vote::signed(
+            &NodeIdentity::for_tests("v2"),
+            "k",
+            &"v".repeat(MessageKind::Vote.max_body_bytes()),
+            "ab".repeat(32),
+            "ACK",
+        )));
+        let mut sealing = SealingKey::new(&KEY);
+        assert!(matches!(
+            encode(&oversized, &mut sealing),
+            Err(FrameError::TooLarge { kind: "vote", .. })
+        ));
+
+        // Відмова не зсуває ключ, тож наступний кадр отримувач розшифрує
+        let frame = encode(&PeerMessage::Vote(vote()), &mut sealing).unwrap();
+        assert!(matches!(
+            read_message(&mut frame.as_slice(), &mut OpeningKey::new(&KEY)).await,
+            Ok(PeerMessage::Vote(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_and_foreign_frames() {
+        let vote = PeerMessage::Vote(vote());
+        let mut tampered = encode(&vote, &mut SealingKey::new(&KEY)).unwrap();
+        tampered[HEADER_BYTES + 3] ^= 1;
+        assert_eq!(
+            read_message(&mut tampered.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::Decrypt
+        );
+
+        // Тип повідомлення не зашифрований, але підміну видає тег
+        let mut retyped = encode(&vote, &mut SealingKey::new(&KEY)).unwrap();
+        retyped[4] = MessageKind::TxAnnounce.as_byte();
+        assert_eq!(
+            read_message(&mut retyped.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::Decrypt
+        );
+
+        let foreign = encode(&vote, &mut SealingKey::new(&[8; 32])).unwrap();
+        assert_eq!(
+            read_message(&mut foreign.as_slice(), &mut OpeningKey::new(&KEY))
+                .await
+                .unwrap_err(),
+            FrameError::Decrypt
+        );
+    }
+}
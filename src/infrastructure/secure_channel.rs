@@ -0,0 +1,207 @@
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::agreement::{self, EphemeralPrivateKey, UnparsedPublicKey, X25519};
+use ring::hkdf::{HKDF_SHA256, Salt};
+use ring::rand::SystemRandom;
+use sha2::{Digest, Sha256};
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// Першими байтами з'єднання обидві сторони надсилають версію схеми та ефемерний ключ X25519
+const PREAMBLE: &[u8; 8] = b"RCHAIN/1";
+const EPHEMERAL_KEY_BYTES: usize = 32;
+pub const TAG_BYTES: usize = 16;
+
+/// Encrypts outgoing frames. Each frame uses the next nonce, so frames must be
+/// sealed in the order they are written.
+pub struct SealingKey {
+    key: LessSafeKey,
+    counter: u64,
+}
+
+/// Decrypts incoming frames in the order they were sealed.
+pub struct OpeningKey {
+    key: LessSafeKey,
+    counter: u64,
+}
+
+/// Keys of one P2P connection, agreed without any long-term secret. The
+/// transcript hash identifies the connection: each side signs it with its
+/// identity key to prove who it is.
+pub struct Session {
+    pub sealing: SealingKey,
+    pub opening: OpeningKey,
+    pub transcript: [u8; 32],
+}
+
+impl SealingKey {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            key: aead_key(key),
+            counter: 0,
+        }
+    }
+
+    // Шифрує body на місці й дописує тег; aad автентифікується, але не шифрується
+    pub fn seal(&mut self, aad: &[u8], body: &mut Vec<u8>) {
+        let nonce = next_nonce(&mut self.counter);
+        self.key
+            .seal_in_place_append_tag(nonce, Aad::from(aad), body)
+            .expect("frames are far below the ChaCha20-Poly1305 limit");
+    }
+}
+
+impl OpeningKey {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            key: aead_key(key),
+            counter: 0,
+        }
+    }
+
+    // None, якщо кадр змінено дорогою або він не з цього з'єднання
+    pub fn open<'a>(&mut self, aad: &[u8], sealed: &'a mut [u8]) -> Option<&'a [u8]> {
+        let nonce = next_nonce(&mut self.counter);
+        self.key
+            .open_in_place(nonce, Aad::from(aad), sealed)
+            .ok()
+            .map(|body| &*body)
+    }
+}
+
+/// Exchanges ephemeral X25519 keys over a fresh connection and derives a key
+/// for each direction. `initiator` is true on the side that dialed.
+pub async fn establish<R, W>(reader: &mut R, writer: &mut W, initiator: bool) -> io::Result<Session>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let rng = SystemRandom::new();
+    let private_key = EphemeralPrivateKey::generate(&X25519, &rng).map_err(crypto_error)?;
+    let public_key = private_key.compute_public_key().map_err(crypto_error)?;
+
+    let mut preamble = PREAMBLE.to_vec();
+    preamble.extend_from_slice(public_key.as_ref());
+    writer.write_all(&preamble).await?;
+    writer.flush().await?;
+
+    let mut remote = [0u8; PREAMBLE.len() + EPHEMERAL_KEY_BYTES];
+    reader.read_exact(&mut remote).await?;
+    if &remote[..PREAMBLE.len()] != PREAMBLE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "peer does not speak the encrypted P2P protocol",
+        ));
+    }
+    let remote_key = &remote[PREAMBLE.len()..];
+
+    // Обидві сторони хешують ключі в однаковому порядку: спершу того, хто підключався
+    let (initiator_key, responder_key) = if initiator {
+        (public_key.as_ref(), remote_key)
+    } else {
+        (remote_key, public_key.as_ref())
+    };
+    let transcript: [u8; 32] = Sha256::new()
+        .chain_update(PREAMBLE)
+        .chain_update(initiator_key)
+        .chain_update(responder_key)
+        .finalize()
+        .into();
+
+    let (to_responder, to_initiator) = agreement::agree_ephemeral(
+        private_key,
+        &UnparsedPublicKey::new(&X25519, remote_key),
+        |shared_secret| {
+            let prk = Salt::new(HKDF_SHA256, &transcript).extract(shared_secret);
+            (
+                derive_key(&prk, b"initiator to responder"),
+                derive_key(&prk, b"responder to initiator"),
+            )
+        },
+    )
+    .map_err(crypto_error)?;
+    let (sealing, opening) = if initiator {
+        (to_responder, to_initiator)
+    } else {
+        (to_initiator, to_responder)
+    };
+    Ok(Session {
+        sealing: SealingKey::new(&sealing),
+        opening: OpeningKey::new(&opening),
+        transcript,
+    })
+}
+
+/// What each side signs with its identity key. The role byte keeps a peer
+/// from replaying our own signature back to us.
+pub fn hello_payload(transcript: &[u8; 32], initiator: bool) -> Vec<u8> {
+    let mut payload = b"rust-chain hello".to_vec();
+    payload.extend_from_slice(transcript);
+    payload.push(if initiator { b'I' } else { b'R' });
+    payload
+}
+
+fn derive_key(prk: &ring::hkdf::Prk, info: &[u8]) -> [u8; 32] {
+    let info = [info];
+    let mut key = [0u8; 32];
+    prk.expand(&info, HKDF_SHA256)
+        .and_then(|okm| okm.fill(&mut key))
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    key
+}
+
+fn aead_key(key: &[u8; 32]) -> LessSafeKey {
+    LessSafeKey::new(
+        UnboundKey::new(&CHACHA20_POLY1305, key).expect("ChaCha20-Poly1305 keys are 32 bytes"),
+    )
+}
+
+// Лічильник кадрів у останніх 8 байтах nonce, як у Noise
+fn next_nonce(counter: &mut u64) -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    nonce[NONCE_LEN - 8..].copy_from_slice(&counter.to_be_bytes());
+    *counter += 1;
+    Nonce::assume_unique_for_key(nonce)
+}
+
+fn crypto_error(_: ring::error::Unspecified) -> io::Error {
+    io::Error::other("key exchange failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{establish, hello_payload};
+
+    #[tokio::test]
+    async fn both_sides_agree_on_keys_and_transcript() {
+        let (initiator, responder) = tokio::io::duplex(1024);
+        let (mut initiator_reader, mut initiator_writer) = tokio::io::split(initiator);
+        let (mut responder_reader, mut responder_writer) = tokio::io::split(responder);
+        let (initiator, responder) = tokio::join!(
+            establish(&mut initiator_reader, &mut initiator_writer, true),
+            establish(&mut responder_reader, &mut responder_writer, false),
+        );
+        let (mut initiator, mut responder) = (initiator.unwrap(), responder.unwrap());
+        assert_eq!(initiator.transcript, responder.transcript);
+        assert_ne!(
+            hello_payload(&initiator.transcript, true),
+            hello_payload(&responder.transcript, false)
+        );
+
+        for body in [b"first".to_vec(), b"second".to_vec()] {
+            let mut sealed = body.clone();
+            initiator.sealing.seal(b"header", &mut sealed);
+            assert_ne!(sealed[..body.len()], body[..]);
+            assert_eq!(
+                responder.opening.open(b"header", &mut sealed),
+                Some(&body[..])
+            );
+        }
+
+        // Змінений заголовок чи кадр, відбитий назад відправнику, не розшифровуються
+        let mut sealed = b"vote".to_vec();
+        responder.sealing.seal(b"header", &mut sealed);
+        let mut reflected = sealed.clone();
+        assert_eq!(initiator.opening.open(b"HEADER", &mut sealed), None);
+        assert_eq!(responder.opening.open(b"header", &mut reflected), None);
+    }
+}